@@ -0,0 +1,194 @@
+use crate::config::{Config, ConfigError, RateSource};
+use crate::services::{
+    EcbFetcher, HttpClientOptions, JsonRateFetcher, RateProvider, RedisStore, RetryPolicy,
+    next_fire_times, parse_cron,
+};
+use chrono::Utc;
+use std::fmt;
+use std::time::Duration;
+
+/// Longest time the Redis check waits for a connection
+const REDIS_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of a single startup check: a detail on success, the reason on failure
+struct CheckResult {
+    name: &'static str,
+    outcome: Result<String, String>,
+}
+
+/// Pass/fail report printed by `--check`
+#[derive(Default)]
+pub struct CheckReport {
+    results: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    fn record(&mut self, name: &'static str, outcome: Result<String, String>) {
+        self.results.push(CheckResult { name, outcome });
+    }
+
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.outcome.is_ok())
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            match &result.outcome {
+                Ok(detail) => writeln!(f, "PASS  {:<7} {}", result.name, detail)?,
+                Err(reason) => writeln!(f, "FAIL  {:<7} {}", result.name, reason)?,
+            }
+        }
+
+        let failed = self
+            .results
+            .iter()
+            .filter(|result| result.outcome.is_err())
+            .count();
+        if failed == 0 {
+            write!(f, "All {} checks passed", self.results.len())
+        } else {
+            write!(f, "{} of {} checks failed", failed, self.results.len())
+        }
+    }
+}
+
+/// Run the startup validations (configuration, cron, Redis, rate source)
+/// without binding the HTTP listener or starting the scheduler
+/// Later checks are skipped when the configuration itself fails to load
+pub async fn run_checks() -> CheckReport {
+    check_config(Config::from_env()).await
+}
+
+async fn check_config(config: Result<Config, ConfigError>) -> CheckReport {
+    let mut report = CheckReport::default();
+
+    let config = match config {
+        Ok(config) => {
+            report.record("config", Ok("loaded".to_string()));
+            config
+        }
+        Err(e) => {
+            report.record("config", Err(e.to_string()));
+            return report;
+        }
+    };
+
+    report.record("cron", check_cron(&config.update_cron));
+    report.record("redis", check_redis(&config).await);
+    report.record("rates", check_rate_source(&config).await);
+
+    report
+}
+
+fn check_cron(expression: &str) -> Result<String, String> {
+    let cron = parse_cron(expression)?;
+
+    match next_fire_times(&cron, Utc::now(), 1).first() {
+        Some(next) => Ok(format!(
+            "'{}', next run at {}",
+            expression,
+            next.format("%Y-%m-%d %H:%M:%S UTC")
+        )),
+        None => Err(format!("'{}' never fires", expression)),
+    }
+}
+
+/// Connect and PING; the URL is not echoed since it may hold a password
+async fn check_redis(config: &Config) -> Result<String, String> {
+    let connect = async {
+        let store = RedisStore::new(
+            &config.redis_url,
+            &config.redis_prefix,
+            config.history_retention_days,
+            config.redis_ca_cert.as_deref(),
+        )
+        .await?;
+        store.health_check().await
+    };
+
+    match tokio::time::timeout(REDIS_CHECK_TIMEOUT, connect).await {
+        Ok(Ok(())) => Ok("reachable".to_string()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("no answer within {:?}", REDIS_CHECK_TIMEOUT)),
+    }
+}
+
+/// Fetch the daily rates once from the configured RATE_SOURCE, without retries
+async fn check_rate_source(config: &Config) -> Result<String, String> {
+    let retry_policy = RetryPolicy {
+        max_attempts: 1,
+        base_delay: Duration::ZERO,
+    };
+    let client_options = HttpClientOptions {
+        timeout: Duration::from_secs(config.ecb_timeout_secs),
+        user_agent: config.ecb_user_agent.clone(),
+        proxy: config.ecb_proxy.clone(),
+        max_redirects: config.ecb_max_redirects,
+    };
+
+    let provider: Box<dyn RateProvider> = match config.rate_source {
+        RateSource::Ecb => Box::new(
+            EcbFetcher::new(config.ecb_url.clone(), retry_policy, &client_options)
+                .map_err(|e| e.to_string())?
+                .with_min_rates(config.ecb_min_rates),
+        ),
+        RateSource::Json => Box::new(
+            JsonRateFetcher::new(config.json_rates_url.clone(), retry_policy, &client_options)
+                .map_err(|e| e.to_string())?
+                .with_min_rates(config.ecb_min_rates),
+        ),
+    };
+
+    let rates = provider.fetch_rates().await.map_err(|e| e.to_string())?;
+
+    Ok(format!(
+        "{} rates for {} from {}",
+        rates.rates.len(),
+        rates.date,
+        provider.name()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_cron() {
+        let detail = check_cron("0 0 16 * * *").unwrap();
+        assert!(detail.starts_with("'0 0 16 * * *', next run at "));
+
+        let reason = check_cron("0 0 25 * * *").unwrap_err();
+        assert!(reason.contains("hour"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_is_reported_not_fatal() {
+        let report = check_config(Err(ConfigError::ServerPort("abc".to_string()))).await;
+
+        assert!(!report.passed());
+        assert_eq!(
+            report.to_string(),
+            "FAIL  config  Invalid SERVER_PORT 'abc': expected a port number from 0 to 65535\n\
+             1 of 1 checks failed"
+        );
+    }
+
+    #[test]
+    fn test_report_fails_when_any_check_fails() {
+        let mut report = CheckReport::default();
+        report.record("config", Ok("loaded".to_string()));
+        assert!(report.passed());
+
+        report.record("redis", Err("Connection refused".to_string()));
+
+        assert!(!report.passed());
+        assert_eq!(
+            report.to_string(),
+            "PASS  config  loaded\nFAIL  redis   Connection refused\n1 of 2 checks failed"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::handlers::convert::{convert_with_rates, parse_request};
+use crate::models::{ConvertQuery, LatestRatesResponse};
+use crate::services::{RedisStore, rebase_rates};
+use clap::{Parser, Subcommand};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Currency conversion API server
+///
+/// Runs the server when no subcommand is given
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// Validate configuration, Redis, the rate source and the cron schedule, then exit
+    /// (nonzero on any failure) without starting the server
+    #[arg(long)]
+    pub check: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// One-off operations against the rates currently stored in Redis
+/// Results are printed to stdout as JSON
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Convert an amount between two currencies, exactly as GET /api/convert does
+    Convert {
+        /// Source ISO 4217 currency code or CURRENCY_ALIASES alias
+        #[arg(long)]
+        from: String,
+        /// Target ISO 4217 currency code or CURRENCY_ALIASES alias
+        #[arg(long)]
+        to: String,
+        /// Non-negative decimal amount
+        #[arg(long)]
+        amount: String,
+    },
+    /// Print the latest rate table
+    Latest {
+        /// Rebase the table onto this currency (the stored base when omitted)
+        #[arg(long)]
+        base: Option<String>,
+    },
+}
+
+impl Command {
+    /// Run the command against `store` and return its JSON output
+    pub async fn run(
+        self,
+        store: &RedisStore,
+        config: &Config,
+    ) -> Result<serde_json::Value, ApiError> {
+        let rates = store.get_rates().await?.ok_or(ApiError::NoRatesAvailable)?;
+
+        match self {
+            Command::Convert { from, to, amount } => {
+                let mut params = ConvertQuery {
+                    from,
+                    to,
+                    amount,
+                    date: None,
+                    round: None,
+                    explain: None,
+                    mode: None,
+                    spread_bps: None,
+                    format: None,
+                    locale: None,
+                    percent: None,
+                    rounding: None,
+                    via: None,
+                    minor_units: None,
+                };
+                params.resolve_aliases(&config.currency_aliases);
+
+                // Same validation, CONVERT_PAIRS check and spread as the HTTP endpoint
+                let amount = parse_request(&params, config, None)?;
+                let response = convert_with_rates(
+                    &rates,
+                    &params,
+                    amount,
+                    config.default_spread_bps,
+                    store.rate_cache(),
+                )
+                .map_err(|e| e.with_available_currencies(&rates))?;
+
+                Ok(json!(response))
+            }
+            Command::Latest { base } => {
+                let rates = match base {
+                    Some(base) => rebase_rates(&rates, &base.to_uppercase())?,
+                    None => Arc::unwrap_or_clone(rates),
+                };
+
+                Ok(json!(LatestRatesResponse::from(&rates)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_no_subcommand_runs_server() {
+        let cli = Cli::try_parse_from(["currency-converter-api"]).unwrap();
+
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_parse_convert() {
+        let cli = Cli::try_parse_from([
+            "currency-converter-api",
+            "convert",
+            "--from",
+            "USD",
+            "--to",
+            "JPY",
+            "--amount",
+            "100",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Some(Command::Convert { from, to, amount }) => {
+                assert_eq!((from.as_str(), to.as_str()), ("USD", "JPY"));
+                assert_eq!(amount, "100");
+            }
+            other => panic!("Expected convert command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_check() {
+        let cli = Cli::try_parse_from(["currency-converter-api", "--check"]).unwrap();
+
+        assert!(cli.check);
+        assert!(cli.command.is_none());
+        assert!(Cli::try_parse_from(["currency-converter-api", "--check", "latest"]).is_err());
+    }
+
+    #[test]
+    fn test_convert_requires_amount() {
+        let result = Cli::try_parse_from([
+            "currency-converter-api",
+            "convert",
+            "--from",
+            "USD",
+            "--to",
+            "JPY",
+        ]);
+
+        assert!(result.is_err());
+    }
+}
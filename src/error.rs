@@ -1,9 +1,10 @@
+use crate::models::{AmountError, DailyRate, ErrorResponse, FieldError, field_errors};
 use axum::{
     Json,
-    http::StatusCode,
+    extract::rejection::{JsonRejection, StringRejection},
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
-use serde_json::json;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -11,17 +12,45 @@ pub enum ApiError {
     #[error("Currency code '{0}' not found in exchange rates")]
     CurrencyNotFound(String),
 
+    /// CurrencyNotFound along with the codes the rate table does have, 404
+    #[error("Currency code '{code}' not found in exchange rates")]
+    UnknownCurrency {
+        code: String,
+        available: Vec<String>,
+    },
+
     #[error("No exchange rates available. Please try again later.")]
     NoRatesAvailable,
 
+    #[error("No exchange rates available for {0}")]
+    NoRatesForDate(String),
+
+    /// Latest rates older than STALE_THRESHOLD_HOURS while SERVE_STALE is off, 503
+    #[error("Exchange rates are {0} hours old. Please try again later.")]
+    StaleRates(i64),
+
+    /// Malformed input (wrong format or length), 400
     #[error("Invalid parameter: {0}")]
     ValidationError(String),
 
+    /// ValidationError from `Validate`, listing every rejected field at once, 400
+    #[error("Invalid parameter: {}", .0.iter().map(|e| format!("{}: {}", e.field, e.message)).collect::<Vec<_>>().join("; "))]
+    InvalidFields(Vec<FieldError>),
+
+    /// Well-formed input that cannot be served as asked (e.g. start after end), 422
+    #[error("Invalid parameter: {0}")]
+    Unprocessable(String),
+
     #[error("Failed to fetch ECB data: {0}")]
     EcbFetchError(String),
 
+    /// Redis rejected or failed a command, 500
     #[error("Redis error: {0}")]
-    RedisError(#[from] redis::RedisError),
+    RedisError(redis::RedisError),
+
+    /// Redis unreachable (connection refused, dropped or timed out), 503
+    #[error("Redis connection lost: {0}")]
+    RedisUnavailable(redis::RedisError),
 
     #[error("XML parse error: {0}")]
     XmlParseError(String),
@@ -31,14 +60,116 @@ pub enum ApiError {
 
     #[error("Internal server error: {0}")]
     InternalError(String),
+
+    #[error("Missing or invalid API key")]
+    Unauthorized,
+
+    #[error("Rate limit exceeded. Retry after {0} seconds.")]
+    RateLimited(u64),
+
+    #[error("A rate update is already in progress")]
+    RefreshInProgress,
+
+    #[error("Too many WebSocket connections, try again later")]
+    TooManyConnections,
+
+    /// MAX_CONCURRENT_REQUESTS already being handled, 503
+    #[error("Server is busy, try again later")]
+    Overloaded,
+
+    #[error("Request body is too large")]
+    PayloadTooLarge,
+
+    /// Handler exceeded REQUEST_TIMEOUT_MS (HEALTH_TIMEOUT_MS for /health), 504
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Conversion from {0} to {1} is not allowed")]
+    PairNotAllowed(String, String),
+
+    #[error("Fetched rates are missing required currencies: {}", .0.join(", "))]
+    MissingRequiredCurrencies(Vec<String>),
+}
+
+impl ApiError {
+    /// Stable machine-readable identifier sent as `code` next to the message
+    /// Clients switch on this; never rename an existing code
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::CurrencyNotFound(_) | ApiError::UnknownCurrency { .. } => {
+                "CURRENCY_NOT_FOUND"
+            }
+            ApiError::NoRatesAvailable => "NO_RATES_AVAILABLE",
+            ApiError::NoRatesForDate(_) => "NO_RATES_FOR_DATE",
+            ApiError::StaleRates(_) => "RATES_STALE",
+            ApiError::ValidationError(_) | ApiError::InvalidFields(_) => "VALIDATION_ERROR",
+            ApiError::Unprocessable(_) => "UNPROCESSABLE_ENTITY",
+            ApiError::EcbFetchError(_) => "UPSTREAM_FETCH_FAILED",
+            ApiError::RedisError(_) => "STORAGE_ERROR",
+            ApiError::RedisUnavailable(_) => "STORAGE_UNAVAILABLE",
+            ApiError::XmlParseError(_) => "UPSTREAM_PARSE_FAILED",
+            ApiError::CalculationError(_) => "CALCULATION_ERROR",
+            ApiError::InternalError(_) => "INTERNAL_ERROR",
+            ApiError::Unauthorized => "UNAUTHORIZED",
+            ApiError::RateLimited(_) => "RATE_LIMITED",
+            ApiError::RefreshInProgress => "REFRESH_IN_PROGRESS",
+            ApiError::TooManyConnections => "TOO_MANY_CONNECTIONS",
+            ApiError::Overloaded => "SERVER_OVERLOADED",
+            ApiError::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            ApiError::Timeout => "REQUEST_TIMEOUT",
+            ApiError::PairNotAllowed(..) => "PAIR_NOT_ALLOWED",
+            ApiError::MissingRequiredCurrencies(_) => "MISSING_REQUIRED_CURRENCIES",
+        }
+    }
+
+    /// List the currencies `rates` does have on a CurrencyNotFound, so clients can
+    /// self-correct; any other error is returned unchanged
+    pub fn with_available_currencies(self, rates: &DailyRate) -> Self {
+        match self {
+            ApiError::CurrencyNotFound(code) => {
+                let mut available: Vec<String> = rates
+                    .rates
+                    .keys()
+                    .chain(std::iter::once(&rates.base))
+                    .cloned()
+                    .collect();
+                available.sort();
+                available.dedup();
+
+                ApiError::UnknownCurrency { code, available }
+            }
+            other => other,
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        let code = self.code();
+        let retry_after = match self {
+            ApiError::RateLimited(seconds) => Some(seconds),
+            _ => None,
+        };
+        let available = match &self {
+            ApiError::UnknownCurrency { available, .. } => Some(available.clone()),
+            _ => None,
+        };
+        let errors = match &self {
+            ApiError::InvalidFields(errors) => Some(errors.clone()),
+            _ => None,
+        };
+
         let (status, error_message) = match self {
-            ApiError::CurrencyNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            ApiError::CurrencyNotFound(_) | ApiError::UnknownCurrency { .. } => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
             ApiError::NoRatesAvailable => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
-            ApiError::ValidationError(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            ApiError::NoRatesForDate(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            ApiError::StaleRates(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            ApiError::ValidationError(_) | ApiError::InvalidFields(_) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            ApiError::Unprocessable(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
             ApiError::EcbFetchError(ref msg) => {
                 tracing::error!("ECB fetch error: {}", msg);
                 (
@@ -47,10 +178,17 @@ impl IntoResponse for ApiError {
                 )
             }
             ApiError::RedisError(ref err) => {
-                tracing::error!("Redis error: {}", err);
+                tracing::error!("Redis command failed: {}", err);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    "Database connection error".to_string(),
+                    "Database error".to_string(),
+                )
+            }
+            ApiError::RedisUnavailable(ref err) => {
+                tracing::error!("Redis connection lost: {}", err);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "Database temporarily unavailable".to_string(),
                 )
             }
             ApiError::XmlParseError(ref msg) => {
@@ -62,9 +200,10 @@ impl IntoResponse for ApiError {
             }
             ApiError::CalculationError(ref msg) => {
                 tracing::error!("Calculation error: {}", msg);
+                // Messages describe the numbers involved, never server internals
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    "Calculation error occurred".to_string(),
+                    format!("Calculation error: {}", msg),
                 )
             }
             ApiError::InternalError(ref msg) => {
@@ -74,13 +213,93 @@ impl IntoResponse for ApiError {
                     "Internal server error".to_string(),
                 )
             }
+            ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
+            ApiError::RateLimited(_) => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            ApiError::RefreshInProgress => (StatusCode::CONFLICT, self.to_string()),
+            ApiError::TooManyConnections => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            ApiError::Overloaded => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            ApiError::PairNotAllowed(..) => (StatusCode::FORBIDDEN, self.to_string()),
+            ApiError::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            ApiError::Timeout => (StatusCode::GATEWAY_TIMEOUT, self.to_string()),
+            ApiError::MissingRequiredCurrencies(_) => {
+                tracing::error!("{}", self);
+                (StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
+            }
         };
 
-        let body = Json(json!({
-            "error": error_message,
-        }));
+        let body = Json(ErrorResponse {
+            error: error_message,
+            code: code.to_string(),
+            available,
+            errors,
+        });
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+
+        response
+    }
+}
+
+/// Whether `err` means the connection to Redis is gone rather than the command being rejected
+pub fn is_connection_error(err: &redis::RedisError) -> bool {
+    err.is_connection_dropped()
+        || err.is_connection_refusal()
+        || err.is_io_error()
+        || err.is_timeout()
+}
+
+// Lost connections are transient (503); anything else Redis reports is a failed command (500)
+impl From<redis::RedisError> for ApiError {
+    fn from(err: redis::RedisError) -> Self {
+        if is_connection_error(&err) {
+            ApiError::RedisUnavailable(err)
+        } else {
+            ApiError::RedisError(err)
+        }
+    }
+}
+
+// JSON body rejections (oversized, malformed or wrongly typed bodies)
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge
+        } else {
+            ApiError::ValidationError(rejection.body_text())
+        }
+    }
+}
+
+impl From<StringRejection> for ApiError {
+    fn from(rejection: StringRejection) -> Self {
+        if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge
+        } else {
+            ApiError::ValidationError(rejection.body_text())
+        }
+    }
+}
+
+// Derived `Validate` failures, reported field by field
+impl From<validator::ValidationErrors> for ApiError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        ApiError::InvalidFields(field_errors(&errors))
+    }
+}
+
+// Unparseable amounts are malformed input; negative or oversized ones are semantic
+impl From<AmountError> for ApiError {
+    fn from(err: AmountError) -> Self {
+        match err {
+            AmountError::Malformed(msg) => ApiError::ValidationError(msg),
+            AmountError::OutOfRange(msg) => ApiError::Unprocessable(msg),
+        }
     }
 }
 
@@ -90,3 +309,86 @@ impl From<anyhow::Error> for ApiError {
         ApiError::InternalError(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_currency_not_found_lists_available_currencies() {
+        let rates = DailyRate {
+            date: "2024-12-04".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.05)),
+                ("JPY".to_string(), dec!(158.2)),
+            ]),
+        };
+
+        let response = ApiError::CurrencyNotFound("XXX".to_string())
+            .with_available_currencies(&rates)
+            .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": "Currency code 'XXX' not found in exchange rates",
+                "code": "CURRENCY_NOT_FOUND",
+                "available": ["EUR", "JPY", "USD"]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_fields_listed_in_body() {
+        let response = ApiError::InvalidFields(vec![
+            FieldError {
+                field: "from".to_string(),
+                message: "bad from".to_string(),
+            },
+            FieldError {
+                field: "to".to_string(),
+                message: "bad to".to_string(),
+            },
+        ])
+        .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": "Invalid parameter: from: bad from; to: bad to",
+                "code": "VALIDATION_ERROR",
+                "errors": [
+                    {"field": "from", "message": "bad from"},
+                    {"field": "to", "message": "bad to"}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_with_available_currencies_keeps_other_errors() {
+        let rates = DailyRate {
+            date: "2024-12-04".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::new(),
+        };
+
+        assert!(matches!(
+            ApiError::NoRatesAvailable.with_available_currencies(&rates),
+            ApiError::NoRatesAvailable
+        ));
+    }
+}
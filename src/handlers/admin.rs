@@ -0,0 +1,109 @@
+use crate::error::ApiError;
+use crate::models::{
+    DEFAULT_UPDATES_LIMIT, ImportResponse, ImportedDay, SkippedDay, UpdatesQuery, UpdatesResponse,
+};
+use crate::services::{parse_ecb_archive, validate_rates};
+use crate::state::AppState;
+use axum::{
+    Json,
+    extract::{Query, State, rejection::StringRejection},
+};
+use validator::Validate;
+
+/// Store the days of a posted ECB XML file (daily or 90-day history) in rate history
+/// Only history is written; the latest rates stay with the scheduled updates
+pub async fn import_handler(
+    State(state): State<AppState>,
+    body: Result<String, StringRejection>,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let body = body?;
+
+    // A bad upload is the caller's mistake, not an upstream failure
+    let parsed = parse_ecb_archive(&body, state.config.ecb_min_rates).map_err(|e| match e {
+        ApiError::XmlParseError(msg) => ApiError::ValidationError(msg),
+        e => e,
+    })?;
+
+    let mut days = Vec::with_capacity(parsed.days.len());
+    let mut skipped = parsed.rejected;
+    for day in &parsed.days {
+        if let Err(reason) = validate_rates(day) {
+            tracing::warn!("Skipping imported snapshot for {}: {}", day.date, reason);
+            skipped.push(SkippedDay {
+                date: day.date.clone(),
+                reason,
+            });
+            continue;
+        }
+
+        state.store.store_snapshot(day).await?;
+        days.push(ImportedDay {
+            date: day.date.clone(),
+            rates_count: day.rates.len(),
+        });
+    }
+
+    if days.is_empty() {
+        return Err(ApiError::Unprocessable(
+            "payload contains no valid rate days".to_string(),
+        ));
+    }
+
+    tracing::info!("Imported {} historical rate snapshots", days.len());
+
+    Ok(Json(ImportResponse {
+        imported: days.len(),
+        days,
+        skipped,
+    }))
+}
+
+/// Most recent successful rate updates, newest first
+pub async fn updates_handler(
+    State(state): State<AppState>,
+    Query(params): Query<UpdatesQuery>,
+) -> Result<Json<UpdatesResponse>, ApiError> {
+    params.validate()?;
+
+    let updates = state
+        .store
+        .get_update_log(params.limit.unwrap_or(DEFAULT_UPDATES_LIMIT))
+        .await?;
+
+    Ok(Json(UpdatesResponse { updates }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, extract::DefaultBodyLimit, http::Request, routing::post};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_import_limit_overrides_global_limit() {
+        // Same extraction as import_handler, without the Redis-backed state
+        async fn import(body: Result<String, StringRejection>) -> Result<Json<usize>, ApiError> {
+            Ok(Json(body?.len()))
+        }
+        let app = || {
+            Router::new()
+                .route(
+                    "/api/admin/import",
+                    post(import).layer(DefaultBodyLimit::max(1024)),
+                )
+                .layer(DefaultBodyLimit::max(64))
+        };
+        let request = |len: usize| {
+            Request::post("/api/admin/import")
+                .header("content-type", "application/xml")
+                .body(Body::from("x".repeat(len)))
+                .unwrap()
+        };
+
+        let response = app().oneshot(request(512)).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+        let response = app().oneshot(request(2048)).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
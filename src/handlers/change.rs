@@ -0,0 +1,111 @@
+use crate::error::ApiError;
+use crate::handlers::timeseries::{latest_stored_date, parse_past_date};
+use crate::models::{ChangeQuery, ChangeResponse, DailyRate};
+use crate::services::{RedisStore, convert_currency, rate_change};
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use validator::Validate;
+
+/// Change of `symbol` against `base` between two dates
+/// Dates without a snapshot (weekends, holidays) fall back to the nearest prior
+/// available date; the dates actually used are reported in the response
+pub async fn change_handler(
+    State(store): State<RedisStore>,
+    Query(params): Query<ChangeQuery>,
+) -> Result<Json<ChangeResponse>, ApiError> {
+    // Validate query parameters
+    params.validate()?;
+
+    let latest_stored = latest_stored_date(&store).await?;
+    let start = parse_past_date("start", &params.start, latest_stored)?;
+    let end = parse_past_date("end", &params.end, latest_stored)?;
+
+    if start > end {
+        return Err(ApiError::Unprocessable(
+            "start must not be after end".to_string(),
+        ));
+    }
+
+    let symbol = params.symbol.to_uppercase();
+
+    let start_rates = snapshot_on_or_before(&store, start).await?;
+    let end_rates = snapshot_on_or_before(&store, end).await?;
+
+    // Without an explicit base, rates are quoted against the stored table's own base
+    let base = match &params.base {
+        Some(base) => base.to_uppercase(),
+        None => end_rates.base.clone(),
+    };
+
+    let (_, start_rate) = convert_currency(&start_rates, &base, &symbol, Decimal::ONE)?;
+    let (_, end_rate) = convert_currency(&end_rates, &base, &symbol, Decimal::ONE)?;
+    let (change, change_percent) = rate_change(start_rate, end_rate)?;
+
+    Ok(Json(ChangeResponse {
+        base,
+        symbol,
+        start: start.to_string(),
+        end: end.to_string(),
+        start_rate_date: start_rates.date,
+        end_rate_date: end_rates.date,
+        start_rate,
+        end_rate,
+        change,
+        change_percent,
+    }))
+}
+
+async fn snapshot_on_or_before(store: &RedisStore, date: NaiveDate) -> Result<DailyRate, ApiError> {
+    store
+        .get_rates_on_or_before(date)
+        .await?
+        .ok_or_else(|| ApiError::NoRatesForDate(date.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_end_after_latest_stored_is_rejected() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let store = RedisStore::new(&redis_url, "test-change:", 0, None)
+            .await
+            .unwrap();
+        for (date, usd) in [("2024-12-03", dec!(1.0498)), ("2024-12-04", dec!(1.0512))] {
+            store
+                .store_rates(&DailyRate {
+                    date: date.to_string(),
+                    base: "EUR".to_string(),
+                    rates: HashMap::from([("USD".to_string(), usd)]),
+                })
+                .await
+                .unwrap();
+        }
+
+        let query = |end: &str| {
+            Query(ChangeQuery {
+                base: None,
+                symbol: "USD".to_string(),
+                start: "2024-12-03".to_string(),
+                end: end.to_string(),
+            })
+        };
+
+        let Json(response) = change_handler(State(store.clone()), query("2024-12-04"))
+            .await
+            .unwrap();
+        assert_eq!(response.end_rate, dec!(1.0512));
+
+        let result = change_handler(State(store), query("2024-12-05")).await;
+        assert!(matches!(result, Err(ApiError::ValidationError(_))));
+    }
+}
@@ -1,38 +1,937 @@
+use crate::config::Config;
 use crate::error::ApiError;
-use crate::models::{ConvertQuery, ConvertResponse};
-use crate::services::{RedisStore, convert_currency};
+use crate::handlers::rates::stale_rate_headers;
+use crate::handlers::timeseries::{ensure_historical, latest_stored_date};
+use crate::models::{
+    BatchConvertError, BatchConvertRequest, BatchConvertResult, ConvertAllQuery,
+    ConvertAllResponse, ConvertQuery, ConvertResponse, ConvertedAmount, DailyRate, ErrorResponse,
+    RateQuery, RateResponse, currency_meta, parse_amount, parse_symbols,
+};
+use crate::services::{
+    DEFAULT_LOCALE, RateCache, apply_spread, convert_amount, convert_many, cross_rate,
+    explain_conversion, format_amount, inverse_rate, reverse_amount, via_rate,
+};
+use crate::state::AppState;
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::{Query, State, rejection::JsonRejection},
+    http::HeaderMap,
 };
+use chrono::{NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use validator::Validate;
 
+/// Maximum number of conversions accepted in a single batch request
+const MAX_BATCH_SIZE: usize = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/convert",
+    tag = "conversion",
+    params(ConvertQuery),
+    responses(
+        (status = 200, description = "Converted amount", body = ConvertResponse),
+        (status = 400, description = "Malformed parameters", body = ErrorResponse),
+        (status = 403, description = "Pair not in CONVERT_PAIRS", body = ErrorResponse),
+        (status = 404, description = "Currency or dated snapshot not found", body = ErrorResponse),
+        (status = 422, description = "Amount out of range or unsupported locale", body = ErrorResponse),
+        (status = 503, description = "No rates loaded yet, or stale with SERVE_STALE off", body = ErrorResponse)
+    )
+)]
 pub async fn convert_handler(
-    State(store): State<RedisStore>,
-    Query(params): Query<ConvertQuery>,
-) -> Result<Json<ConvertResponse>, ApiError> {
-    // Validate query parameters
-    params
-        .validate()
-        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
-
-    // Parse and validate amount
-    let amount = params
-        .parse_amount()
-        .map_err(|e| ApiError::ValidationError(e))?;
-
-    // Get rates from Redis
-    let rates = store.get_rates().await?.ok_or(ApiError::NoRatesAvailable)?;
+    State(state): State<AppState>,
+    Query(mut params): Query<ConvertQuery>,
+) -> Result<(HeaderMap, Json<ConvertResponse>), ApiError> {
+    params.resolve_aliases(&state.config.currency_aliases);
+    let (headers, response) = do_convert(&state, &params).await?;
+    Ok((headers, Json(response)))
+}
+
+/// Latest market rate for a single pair, without the amount math of /api/convert
+#[utoipa::path(
+    get,
+    path = "/api/rate",
+    tag = "conversion",
+    params(RateQuery),
+    responses(
+        (status = 200, description = "Latest rate for the pair", body = RateResponse),
+        (status = 400, description = "Malformed currency code", body = ErrorResponse),
+        (status = 403, description = "Pair not in CONVERT_PAIRS", body = ErrorResponse),
+        (status = 404, description = "Currency not found", body = ErrorResponse),
+        (status = 503, description = "No rates loaded yet, or stale with SERVE_STALE off", body = ErrorResponse)
+    )
+)]
+pub async fn rate_handler(
+    State(state): State<AppState>,
+    Query(mut params): Query<RateQuery>,
+) -> Result<(HeaderMap, Json<RateResponse>), ApiError> {
+    params.resolve_aliases(&state.config.currency_aliases);
+    params.validate()?;
+
+    ensure_pair_allowed(&state.config.convert_pairs, &params.from, &params.to)?;
+
+    let rates = state
+        .store
+        .get_rates()
+        .await?
+        .ok_or(ApiError::NoRatesAvailable)?;
+    let headers = stale_rate_headers(
+        &rates.date,
+        Utc::now(),
+        state.config.stale_threshold_hours,
+        state.config.serve_stale,
+    )?;
+
+    let response = RateResponse {
+        rate: pair_rate(state.store.rate_cache(), &rates, &params.from, &params.to)
+            .map_err(|e| e.with_available_currencies(&rates))?,
+        date: rates.date.clone(),
+    };
+
+    Ok((headers, Json(response)))
+}
+
+/// Rate from `from` to `to`, derived the same way as a conversion of 1 unit
+fn pair_rate(
+    cache: &RateCache,
+    rates: &DailyRate,
+    from: &str,
+    to: &str,
+) -> Result<Decimal, ApiError> {
+    cache.get_or_compute(rates, from, to, || cross_rate(rates, from, to))
+}
+
+/// POST variant of convert_handler reading the same fields from a JSON body,
+/// so long decimal amounts are not mangled in query strings
+pub async fn convert_post_handler(
+    State(state): State<AppState>,
+    body: Result<Json<ConvertQuery>, JsonRejection>,
+) -> Result<(HeaderMap, Json<ConvertResponse>), ApiError> {
+    let Json(mut params) = body?;
+    params.resolve_aliases(&state.config.currency_aliases);
+    let (headers, response) = do_convert(&state, &params).await?;
+    Ok((headers, Json(response)))
+}
+
+/// Validate a single conversion request and convert it against the latest or historical rates
+/// Conversions at the latest rates also carry the stale-rate headers
+async fn do_convert(
+    state: &AppState,
+    params: &ConvertQuery,
+) -> Result<(HeaderMap, ConvertResponse), ApiError> {
+    let store = &state.store;
+    let mut headers = HeaderMap::new();
+
+    // Historical requests are checked against the latest stored date
+    let latest_stored = match params.date {
+        Some(_) => latest_stored_date(store).await?,
+        None => None,
+    };
+
+    // Validate parameters and parse amount
+    let amount = parse_request(params, &state.config, latest_stored)?;
+
+    // Get latest or historical rates from Redis, falling back to the nearest prior business day
+    let rates = match params.parse_date().map_err(ApiError::ValidationError)? {
+        Some(date) => store
+            .get_rates_on_or_before(date)
+            .await?
+            .map(Arc::new)
+            .ok_or_else(|| ApiError::NoRatesForDate(date.to_string()))?,
+        None => {
+            let rates = store.get_rates().await?.ok_or(ApiError::NoRatesAvailable)?;
+            headers = stale_rate_headers(
+                &rates.date,
+                Utc::now(),
+                state.config.stale_threshold_hours,
+                state.config.serve_stale,
+            )?;
+            rates
+        }
+    };
 
     // Perform conversion (optimized O(1) direct calculation)
-    let (result, rate) = convert_currency(&rates, &params.from, &params.to, amount)?;
+    let response = convert_with_rates(
+        &rates,
+        params,
+        amount,
+        state.config.default_spread_bps,
+        state.store.rate_cache(),
+    )
+    .map_err(|e| e.with_available_currencies(&rates))?;
+
+    Ok((headers, response))
+}
+
+/// Convert several amounts in one request
+/// Entries are processed independently: a failing entry carries an inline
+/// error instead of aborting the batch, and input order is preserved
+/// A batch with any entry at the latest rates carries the stale-rate headers
+pub async fn convert_batch_handler(
+    State(state): State<AppState>,
+    body: Result<Json<BatchConvertRequest>, JsonRejection>,
+) -> Result<(HeaderMap, Json<Vec<BatchConvertResult>>), ApiError> {
+    // Oversized bodies (over MAX_BODY_BYTES) surface here as a 413
+    let Json(mut body) = body?;
+
+    // Validate batch size
+    if body.conversions.is_empty() || body.conversions.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::Unprocessable(format!(
+            "conversions must contain between 1 and {} entries",
+            MAX_BATCH_SIZE
+        )));
+    }
+
+    for entry in &mut body.conversions {
+        entry.resolve_aliases(&state.config.currency_aliases);
+    }
+
+    let store = &state.store;
+
+    // Latest rates back undated entries and bound the dates dated ones may ask for
+    let latest = store.get_rates().await?;
+    let latest_stored = latest
+        .as_ref()
+        .and_then(|latest| NaiveDate::parse_from_str(&latest.date, "%Y-%m-%d").ok());
+
+    // Missing or refused stale latest rates fail the undated entries inline, so dated entries
+    // in the same batch still convert
+    let now = Utc::now();
+    let undated_rates = || -> Result<(&DailyRate, HeaderMap), ApiError> {
+        let latest = latest.as_deref().ok_or(ApiError::NoRatesAvailable)?;
+        let headers = stale_rate_headers(
+            &latest.date,
+            now,
+            state.config.stale_threshold_hours,
+            state.config.serve_stale,
+        )?;
+        Ok((latest, headers))
+    };
+    let headers = if body
+        .conversions
+        .iter()
+        .any(|entry| matches!(entry.parse_date(), Ok(None)))
+    {
+        undated_rates()
+            .map(|(_, headers)| headers)
+            .unwrap_or_default()
+    } else {
+        HeaderMap::new()
+    };
+
+    // Load each distinct historical snapshot once
+    let mut snapshots: HashMap<NaiveDate, Option<DailyRate>> = HashMap::new();
+    for entry in &body.conversions {
+        if let Ok(Some(date)) = entry.parse_date()
+            && !snapshots.contains_key(&date)
+        {
+            snapshots.insert(date, store.get_rates_on_or_before(date).await?);
+        }
+    }
+
+    let results = body
+        .conversions
+        .iter()
+        .map(|entry| {
+            let converted = parse_request(entry, &state.config, latest_stored).and_then(|amount| {
+                let rates = match entry.parse_date().map_err(ApiError::ValidationError)? {
+                    Some(date) => snapshots
+                        .get(&date)
+                        .and_then(Option::as_ref)
+                        .ok_or_else(|| ApiError::NoRatesForDate(date.to_string()))?,
+                    None => undated_rates()?.0,
+                };
+                convert_with_rates(
+                    rates,
+                    entry,
+                    amount,
+                    state.config.default_spread_bps,
+                    state.store.rate_cache(),
+                )
+            });
+
+            match converted {
+                Ok(response) => BatchConvertResult::Success(Box::new(response)),
+                Err(e) => BatchConvertResult::Failure(BatchConvertError {
+                    from: entry.from.to_uppercase(),
+                    to: entry.to.to_uppercase(),
+                    amount: entry.amount.clone(),
+                    error: e.to_string(),
+                    code: e.code().to_string(),
+                }),
+            }
+        })
+        .collect();
+
+    Ok((headers, Json(results)))
+}
+
+/// Convert one amount of `from` into every currency of the latest table, or the `symbols` subset
+/// Each target is one O(1) cross-rate lookup against the same table, so N targets cost what
+/// an N-entry batch does, minus the per-entry validation and ConvertResponse extras
+pub async fn convert_all_handler(
+    State(state): State<AppState>,
+    Query(mut params): Query<ConvertAllQuery>,
+) -> Result<(HeaderMap, Json<ConvertAllResponse>), ApiError> {
+    params.resolve_aliases(&state.config.currency_aliases);
+    params.validate()?;
+
+    let symbols = params
+        .symbols
+        .as_deref()
+        .map(parse_symbols)
+        .transpose()
+        .map_err(ApiError::ValidationError)?;
+    let amount = parse_amount(
+        &params.amount,
+        state.config.max_convert_amount,
+        state.config.amount_grouping,
+    )?;
+
+    let rates = state
+        .store
+        .get_rates()
+        .await?
+        .ok_or(ApiError::NoRatesAvailable)?;
+    let headers = stale_rate_headers(
+        &rates.date,
+        Utc::now(),
+        state.config.stale_threshold_hours,
+        state.config.serve_stale,
+    )?;
+
+    let from = params.from.to_uppercase();
+    let results = convert_to_all(
+        &rates,
+        &from,
+        amount,
+        symbols.as_deref(),
+        &state.config.convert_pairs,
+    )
+    .map_err(|e| e.with_available_currencies(&rates))?;
+
+    Ok((
+        headers,
+        Json(ConvertAllResponse {
+            from,
+            amount,
+            date: rates.date.clone(),
+            results,
+        }),
+    ))
+}
+
+/// Convert `amount` of `from` into each target, rounding results to the target's minor unit
+/// Without `symbols`, targets are every other currency in `rates` allowed by CONVERT_PAIRS;
+/// explicitly requested symbols must exist and be allowed
+fn convert_to_all(
+    rates: &DailyRate,
+    from: &str,
+    amount: Decimal,
+    symbols: Option<&[String]>,
+    pairs: &[(String, String)],
+) -> Result<BTreeMap<String, ConvertedAmount>, ApiError> {
+    if from != rates.base && !rates.rates.contains_key(from) {
+        return Err(ApiError::CurrencyNotFound(from.to_string()));
+    }
+
+    let targets: Vec<&str> = match symbols {
+        Some(symbols) => {
+            for to in symbols {
+                ensure_pair_allowed(pairs, from, to)?;
+            }
+            symbols.iter().map(String::as_str).collect()
+        }
+        None => rates
+            .rates
+            .keys()
+            .chain(std::iter::once(&rates.base))
+            .map(String::as_str)
+            .filter(|to| *to != from && pair_allowed(pairs, from, to))
+            .collect(),
+    };
+
+    Ok(convert_many(rates, from, amount, &targets)?
+        .into_iter()
+        .map(|(to, (result, rate))| {
+            let result = match currency_meta(&to) {
+                Some(meta) => result.round_dp_with_strategy(
+                    meta.minor_units,
+                    RoundingStrategy::MidpointAwayFromZero,
+                ),
+                None => result,
+            };
+            (to, ConvertedAmount { result, rate })
+        })
+        .collect())
+}
+
+/// Validate conversion parameters and parse the amount
+/// A `date` must not be after today (UTC) nor newer than `latest_stored`
+pub(crate) fn parse_request(
+    params: &ConvertQuery,
+    config: &Config,
+    latest_stored: Option<NaiveDate>,
+) -> Result<Decimal, ApiError> {
+    params.validate()?;
+
+    if let Some(date) = params.parse_date().map_err(ApiError::ValidationError)? {
+        ensure_historical(date, latest_stored, Utc::now().date_naive())
+            .map_err(|e| ApiError::ValidationError(format!("Invalid date: {}", e)))?;
+    }
+    params.parse_percent().map_err(ApiError::ValidationError)?;
+
+    ensure_pair_allowed(&config.convert_pairs, &params.from, &params.to)?;
+
+    Ok(params.parse_amount(config.max_convert_amount, config.amount_grouping)?)
+}
+
+/// Reject pairs missing from a non-empty CONVERT_PAIRS allow-list
+pub(crate) fn ensure_pair_allowed(
+    pairs: &[(String, String)],
+    from: &str,
+    to: &str,
+) -> Result<(), ApiError> {
+    let from = from.to_uppercase();
+    let to = to.to_uppercase();
+
+    if pair_allowed(pairs, &from, &to) {
+        return Ok(());
+    }
+
+    Err(ApiError::PairNotAllowed(from, to))
+}
+
+/// Whether an uppercase pair passes the CONVERT_PAIRS allow-list (empty allows all)
+pub(crate) fn pair_allowed(pairs: &[(String, String)], from: &str, to: &str) -> bool {
+    pairs.is_empty() || pairs.iter().any(|(f, t)| f == from && t == to)
+}
 
-    Ok(Json(ConvertResponse {
+/// Convert an already validated request against the given rates
+/// The result is rounded with the requested `rounding` mode (half away from zero by default),
+/// except reverse results which are rounded up so the source amount always covers the target;
+/// the rate keeps full precision
+pub(crate) fn convert_with_rates(
+    rates: &DailyRate,
+    params: &ConvertQuery,
+    amount: Decimal,
+    default_spread_bps: u32,
+    cache: &RateCache,
+) -> Result<ConvertResponse, ApiError> {
+    let reverse = params.is_reverse();
+    let spread_bps = params.spread_bps.unwrap_or(default_spread_bps);
+
+    // Market cross rate, reused from the cache for hot pairs; only the amount math is per request.
+    // A pinned `via` currency is composed from two legs and never cached
+    let via = params
+        .via
+        .as_deref()
+        .map(|via| via_rate(rates, &params.from, via, &params.to))
+        .transpose()?;
+    let base_rate = match &via {
+        Some((rate, _)) => *rate,
+        None => cache.get_or_compute(rates, &params.from, &params.to, || {
+            cross_rate(rates, &params.from, &params.to)
+        })?,
+    };
+
+    let (result, rate) = if reverse {
+        // Reverse: divide the wanted amount by the rate
+        let rate = apply_spread(base_rate, spread_bps)?;
+        (reverse_amount(amount, rate)?, rate)
+    } else if spread_bps == 0 {
+        // Without a spread the market rate is used as-is
+        (convert_amount(amount, base_rate)?, base_rate)
+    } else {
+        let rate = apply_spread(base_rate, spread_bps)?;
+        (convert_amount(amount, rate)?, rate)
+    };
+
+    let round_dp = params.round_dp();
+    let rounding = params.rounding_mode();
+    let (strategy, rounding_mode) = if reverse {
+        (RoundingStrategy::AwayFromZero, "up")
+    } else {
+        (rounding.strategy(), rounding.as_str())
+    };
+    let result = match round_dp {
+        Some(dp) => result.round_dp_with_strategy(dp, strategy),
+        None => result,
+    };
+
+    // Percentage of the rounded result, rounded the same way as the result
+    let percent_amount = params
+        .parse_percent()
+        .map_err(ApiError::ValidationError)?
+        .map(|percent| percent_of(result, percent, round_dp, strategy))
+        .transpose()?;
+
+    // Integer minor units, rounded the same way as the result
+    let result_minor_units = if params.minor_units.unwrap_or(false) {
+        Some(to_minor_units(result, params.result_currency(), strategy)?)
+    } else {
+        None
+    };
+
+    // Display string is built from the rounded result
+    let formatted_result = match (&params.locale, params.format) {
+        (Some(locale), _) => Some(format_amount(result, params.result_currency(), locale)?),
+        (None, Some(true)) => Some(format_amount(
+            result,
+            params.result_currency(),
+            DEFAULT_LOCALE,
+        )?),
+        (None, _) => None,
+    };
+
+    // Triangulation breakdown is only computed when requested
+    let path = match (params.explain.unwrap_or(false), via) {
+        (false, _) => None,
+        (true, Some((_, path))) => Some(path),
+        (true, None) => Some(explain_conversion(rates, &params.from, &params.to)?),
+    };
+    let intermediate_rate = path.as_ref().and_then(|path| path.intermediate_rate);
+
+    // Historical requests report which snapshot actually served them
+    let requested_date = params
+        .parse_date()
+        .map_err(ApiError::ValidationError)?
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    let rate_date = requested_date.as_ref().map(|_| rates.date.clone());
+
+    Ok(ConvertResponse {
         from: params.from.to_uppercase(),
         to: params.to.to_uppercase(),
         amount,
         result,
+        mode: if reverse { "reverse" } else { "forward" }.to_string(),
         rate,
-        date: rates.date,
-    }))
+        base_rate,
+        effective_rate: rate,
+        spread_bps,
+        inverse_rate: inverse_rate(rate)?,
+        date: rates.date.clone(),
+        rounded: round_dp.is_some(),
+        rounding_mode: round_dp.map(|_| rounding_mode.to_string()),
+        formatted_result,
+        percent_amount,
+        result_minor_units,
+        via: params.via.as_ref().map(|via| via.to_uppercase()),
+        path: path.map(|path| path.legs),
+        intermediate_rate,
+        requested_date,
+        rate_date,
+    })
+}
+
+/// `amount` as a whole number of `currency`'s ISO 4217 minor units (e.g. 12.345 USD -> 1235
+/// cents half-up), rejecting amounts whose scaled value does not fit in an i64
+fn to_minor_units(
+    amount: Decimal,
+    currency: &str,
+    strategy: RoundingStrategy,
+) -> Result<i64, ApiError> {
+    let meta = currency_meta(currency).ok_or_else(|| {
+        ApiError::Unprocessable(format!("No ISO 4217 minor unit known for {}", currency))
+    })?;
+
+    // ISO minor units are at most 4, so the scale itself cannot overflow
+    amount
+        .checked_mul(Decimal::from(10u64.pow(meta.minor_units)))
+        .map(|scaled| scaled.round_dp_with_strategy(0, strategy))
+        .and_then(|scaled| scaled.to_i64())
+        .ok_or_else(|| {
+            ApiError::Unprocessable(format!(
+                "{} {} is too large to express in minor units",
+                amount, meta.code
+            ))
+        })
+}
+
+/// `percent` % of `value`, rounded with `strategy` to `round_dp` places when given
+fn percent_of(
+    value: Decimal,
+    percent: Decimal,
+    round_dp: Option<u32>,
+    strategy: RoundingStrategy,
+) -> Result<Decimal, ApiError> {
+    let amount = value
+        .checked_mul(percent)
+        .and_then(|product| product.checked_div(Decimal::ONE_HUNDRED))
+        .ok_or_else(|| {
+            ApiError::CalculationError(format!("{}% of {} overflows", percent, value))
+        })?;
+
+    Ok(match round_dp {
+        Some(dp) => amount.round_dp_with_strategy(dp, strategy),
+        None => amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn friday_rates() -> DailyRate {
+        DailyRate {
+            date: "2024-12-06".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("EUR".to_string(), Decimal::ONE),
+                ("USD".to_string(), dec!(1.0575)),
+            ]),
+        }
+    }
+
+    fn query(date: Option<&str>) -> ConvertQuery {
+        ConvertQuery {
+            from: "EUR".to_string(),
+            to: "USD".to_string(),
+            amount: "100".to_string(),
+            date: date.map(str::to_string),
+            round: None,
+            explain: None,
+            mode: None,
+            spread_bps: None,
+            format: None,
+            locale: None,
+            percent: None,
+            rounding: None,
+            via: None,
+            minor_units: None,
+        }
+    }
+
+    fn cross_rates() -> DailyRate {
+        DailyRate {
+            date: "2024-12-06".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("EUR".to_string(), Decimal::ONE),
+                ("USD".to_string(), dec!(1.05)),
+                ("JPY".to_string(), dec!(158.2)),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_convert_to_all_targets() {
+        let results = convert_to_all(&cross_rates(), "USD", dec!(100), None, &[]).unwrap();
+
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["EUR", "JPY"]);
+        assert_eq!(results["EUR"].result, dec!(95.24));
+        // JPY has no minor unit; the rate keeps full precision
+        assert_eq!(results["JPY"].result, dec!(15067));
+        assert_eq!(results["JPY"].rate, dec!(158.2) / dec!(1.05));
+    }
+
+    #[test]
+    fn test_convert_to_all_filters_targets() {
+        let symbols = vec!["JPY".to_string()];
+        let results = convert_to_all(&cross_rates(), "USD", dec!(1), Some(&symbols), &[]).unwrap();
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["JPY"]);
+
+        let pairs = vec![("USD".to_string(), "EUR".to_string())];
+        let results = convert_to_all(&cross_rates(), "USD", dec!(1), None, &pairs).unwrap();
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["EUR"]);
+        assert!(matches!(
+            convert_to_all(&cross_rates(), "USD", dec!(1), Some(&symbols), &pairs),
+            Err(ApiError::PairNotAllowed(..))
+        ));
+
+        let unknown = vec!["CHF".to_string()];
+        assert!(matches!(
+            convert_to_all(&cross_rates(), "USD", dec!(1), Some(&unknown), &[]),
+            Err(ApiError::CurrencyNotFound(code)) if code == "CHF"
+        ));
+        assert!(matches!(
+            convert_to_all(&cross_rates(), "CHF", dec!(1), None, &[]),
+            Err(ApiError::CurrencyNotFound(code)) if code == "CHF"
+        ));
+    }
+
+    #[test]
+    fn test_percent_of_rounded_result() {
+        assert_eq!(
+            percent_of(
+                dec!(103.50),
+                dec!(10),
+                Some(2),
+                RoundingStrategy::MidpointAwayFromZero
+            )
+            .unwrap(),
+            dec!(10.35)
+        );
+        assert_eq!(
+            percent_of(
+                dec!(103.55),
+                dec!(10),
+                Some(2),
+                RoundingStrategy::MidpointAwayFromZero
+            )
+            .unwrap(),
+            dec!(10.36)
+        );
+        assert_eq!(
+            percent_of(
+                dec!(103.55),
+                dec!(10),
+                None,
+                RoundingStrategy::MidpointAwayFromZero
+            )
+            .unwrap(),
+            dec!(10.355)
+        );
+        assert_eq!(
+            percent_of(
+                dec!(103.50),
+                dec!(0),
+                Some(2),
+                RoundingStrategy::MidpointAwayFromZero
+            )
+            .unwrap(),
+            dec!(0)
+        );
+    }
+
+    #[test]
+    fn test_weekend_request_reports_served_date() {
+        let response = convert_with_rates(
+            &friday_rates(),
+            &query(Some("2024-12-07")),
+            dec!(100),
+            0,
+            &RateCache::default(),
+        )
+        .unwrap();
+
+        assert_eq!(response.requested_date.as_deref(), Some("2024-12-07"));
+        assert_eq!(response.rate_date.as_deref(), Some("2024-12-06"));
+        assert_eq!(response.date, "2024-12-06");
+    }
+
+    #[test]
+    fn test_latest_request_omits_dates() {
+        let response = convert_with_rates(
+            &friday_rates(),
+            &query(None),
+            dec!(100),
+            0,
+            &RateCache::default(),
+        )
+        .unwrap();
+
+        assert!(response.requested_date.is_none());
+        assert!(response.rate_date.is_none());
+    }
+
+    #[test]
+    fn test_formatted_result_uses_rounded_value() {
+        let mut params = query(None);
+        params.locale = Some("de-DE".to_string());
+        let response = convert_with_rates(
+            &friday_rates(),
+            &params,
+            dec!(100),
+            0,
+            &RateCache::default(),
+        )
+        .unwrap();
+
+        assert_eq!(response.result, dec!(105.75));
+        assert_eq!(response.formatted_result.as_deref(), Some("105,75 $"));
+
+        params.locale = None;
+        params.format = Some(true);
+        let response = convert_with_rates(
+            &friday_rates(),
+            &params,
+            dec!(100),
+            0,
+            &RateCache::default(),
+        )
+        .unwrap();
+        assert_eq!(response.formatted_result.as_deref(), Some("$105.75"));
+    }
+
+    #[test]
+    fn test_via_pins_triangulation_currency() {
+        let mut params = query(None);
+        params.from = "JPY".to_string();
+        params.to = "EUR".to_string();
+        params.via = Some("usd".to_string());
+        params.explain = Some(true);
+
+        let response = convert_with_rates(
+            &cross_rates(),
+            &params,
+            dec!(1000),
+            0,
+            &RateCache::default(),
+        )
+        .unwrap();
+
+        let (rate, _) = via_rate(&cross_rates(), "JPY", "USD", "EUR").unwrap();
+        assert_eq!(response.base_rate, rate);
+        assert_eq!(response.via.as_deref(), Some("USD"));
+        assert_eq!(response.path.unwrap().len(), 2);
+        assert!(response.intermediate_rate.is_some());
+
+        params.via = Some("GBP".to_string());
+        assert!(matches!(
+            convert_with_rates(&cross_rates(), &params, dec!(1), 0, &RateCache::default()),
+            Err(ApiError::CurrencyNotFound(code)) if code == "GBP"
+        ));
+    }
+
+    #[test]
+    fn test_to_minor_units() {
+        let half_up = RoundingStrategy::MidpointAwayFromZero;
+        assert_eq!(to_minor_units(dec!(12.345), "USD", half_up).unwrap(), 1235);
+        assert_eq!(to_minor_units(dec!(1503), "JPY", half_up).unwrap(), 1503);
+        assert_eq!(to_minor_units(dec!(1.2345), "BHD", half_up).unwrap(), 1235);
+        assert_eq!(
+            to_minor_units(dec!(12.345), "USD", RoundingStrategy::ToZero).unwrap(),
+            1234
+        );
+        assert!(matches!(
+            to_minor_units(dec!(100_000_000_000_000_000), "USD", half_up),
+            Err(ApiError::Unprocessable(_))
+        ));
+    }
+
+    #[test]
+    fn test_minor_units_follow_rounded_result() {
+        let mut params = query(None);
+        params.minor_units = Some(true);
+        let response = convert_with_rates(
+            &friday_rates(),
+            &params,
+            dec!(100),
+            0,
+            &RateCache::default(),
+        )
+        .unwrap();
+
+        assert_eq!(response.result, dec!(105.75));
+        assert_eq!(response.result_minor_units, Some(10575));
+    }
+
+    #[test]
+    fn test_rounding_mode_applies_to_result() {
+        // 2.5 USD at a rate of 1 lands exactly on the midpoint when rounded to whole units
+        let rates = DailyRate {
+            date: "2024-12-06".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("EUR".to_string(), Decimal::ONE),
+                ("USD".to_string(), Decimal::ONE),
+            ]),
+        };
+        let convert = |rounding: Option<&str>| {
+            let mut params = query(None);
+            params.round = Some(0);
+            params.rounding = rounding.map(str::to_string);
+            convert_with_rates(&rates, &params, dec!(2.5), 0, &RateCache::default()).unwrap()
+        };
+
+        let half_up = convert(None);
+        assert_eq!(half_up.result, dec!(3));
+        assert_eq!(half_up.rounding_mode.as_deref(), Some("half_up"));
+
+        let half_even = convert(Some("half_even"));
+        assert_eq!(half_even.result, dec!(2));
+        assert_eq!(half_even.rounding_mode.as_deref(), Some("half_even"));
+
+        let down = convert(Some("down"));
+        assert_eq!(down.result, dec!(2));
+        assert_eq!(down.rounding_mode.as_deref(), Some("down"));
+    }
+
+    #[test]
+    fn test_reverse_mode_rounds_source_up() {
+        let mut params = query(None);
+        params.from = "USD".to_string();
+        params.to = "EUR".to_string();
+        params.mode = Some("reverse".to_string());
+        let response = convert_with_rates(
+            &friday_rates(),
+            &params,
+            dec!(100),
+            0,
+            &RateCache::default(),
+        )
+        .unwrap();
+
+        // 100 EUR needs 105.75 USD exactly; the result is in USD
+        assert_eq!(response.mode, "reverse");
+        assert_eq!(response.rounding_mode.as_deref(), Some("up"));
+        assert_eq!(response.result, dec!(105.75));
+
+        // 10 EUR needs 10.575 USD, rounded up to a cent that still covers it
+        let response =
+            convert_with_rates(&friday_rates(), &params, dec!(10), 0, &RateCache::default())
+                .unwrap();
+        assert_eq!(response.result, dec!(10.58));
+
+        // The percentage is rounded up too, whatever `rounding` asks for
+        params.percent = Some("10".to_string());
+        params.rounding = Some("down".to_string());
+        let response =
+            convert_with_rates(&friday_rates(), &params, dec!(10), 0, &RateCache::default())
+                .unwrap();
+        assert_eq!(response.rounding_mode.as_deref(), Some("up"));
+        assert_eq!(response.percent_amount, Some(dec!(1.06)));
+    }
+
+    #[test]
+    fn test_reverse_mode_with_spread_needs_more_source() {
+        let mut params = query(None);
+        params.mode = Some("REVERSE".to_string());
+        let response = convert_with_rates(
+            &friday_rates(),
+            &params,
+            dec!(105.75),
+            100,
+            &RateCache::default(),
+        )
+        .unwrap();
+
+        // 1% spread: effective rate 1.046925, so more than 100 EUR is needed
+        assert_eq!(response.effective_rate, dec!(1.046925));
+        assert_eq!(response.result, dec!(101.02));
+    }
+
+    #[test]
+    fn test_pair_rate_matches_conversion_rate() {
+        let rates = friday_rates();
+        let response =
+            convert_with_rates(&rates, &query(None), dec!(100), 0, &RateCache::default()).unwrap();
+
+        assert_eq!(
+            pair_rate(&RateCache::default(), &rates, "eur", "usd").unwrap(),
+            response.base_rate
+        );
+        assert!(matches!(
+            pair_rate(&RateCache::default(), &rates, "EUR", "XXX"),
+            Err(ApiError::CurrencyNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_pair_allow_list() {
+        let pairs = vec![("EUR".to_string(), "USD".to_string())];
+
+        assert!(ensure_pair_allowed(&[], "GBP", "JPY").is_ok());
+        assert!(ensure_pair_allowed(&pairs, "eur", "usd").is_ok());
+        // Pairs are directed
+        assert!(matches!(
+            ensure_pair_allowed(&pairs, "USD", "EUR"),
+            Err(ApiError::PairNotAllowed(from, to)) if from == "USD" && to == "EUR"
+        ));
+    }
 }
@@ -0,0 +1,105 @@
+use crate::models::{CURRENCIES, CurrenciesResponse, CurrencyInfo, DailyRate, currency_meta};
+use crate::services::RedisStore;
+use axum::{Json, extract::State};
+use std::collections::BTreeMap;
+
+/// List every ISO 4217 currency, sorted alphabetically, flagging those with a live rate
+/// Served from the static table so UIs can bootstrap before the first fetch;
+/// a Redis failure is reported as no rates rather than as an error
+pub async fn currencies_handler(State(store): State<RedisStore>) -> Json<CurrenciesResponse> {
+    let rates = store.get_rates().await.unwrap_or_else(|e| {
+        tracing::warn!("Listing currencies without rate availability: {}", e);
+        None
+    });
+
+    Json(currency_list(rates.as_deref()))
+}
+
+/// Build the currency list, marking codes present in `rates` (base included)
+/// Codes in the rates but missing from the ISO table are listed without metadata
+fn currency_list(rates: Option<&DailyRate>) -> CurrenciesResponse {
+    let is_available = |code: &str| {
+        rates.is_some_and(|rates| rates.base == code || rates.rates.contains_key(code))
+    };
+
+    let mut currencies: BTreeMap<String, CurrencyInfo> = CURRENCIES
+        .iter()
+        .map(|meta| {
+            (
+                meta.code.to_string(),
+                CurrencyInfo {
+                    name: Some(meta.name),
+                    symbol: Some(meta.symbol),
+                    minor_units: Some(meta.minor_units),
+                    rate_available: is_available(meta.code),
+                },
+            )
+        })
+        .collect();
+
+    if let Some(rates) = rates {
+        for code in rates.rates.keys().chain(std::iter::once(&rates.base)) {
+            currencies.entry(code.clone()).or_insert_with(|| {
+                let meta = currency_meta(code);
+                CurrencyInfo {
+                    name: meta.map(|meta| meta.name),
+                    symbol: meta.map(|meta| meta.symbol),
+                    minor_units: meta.map(|meta| meta.minor_units),
+                    rate_available: true,
+                }
+            });
+        }
+    }
+
+    CurrenciesResponse {
+        rates_available: rates.is_some(),
+        date: rates.map(|rates| rates.date.clone()),
+        currencies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_lists_iso_table_without_rates() {
+        let response = currency_list(None);
+
+        assert!(!response.rates_available);
+        assert!(response.date.is_none());
+        assert_eq!(response.currencies.len(), CURRENCIES.len());
+        assert!(
+            response
+                .currencies
+                .values()
+                .all(|info| !info.rate_available)
+        );
+        assert_eq!(response.currencies["JPY"].minor_units, Some(0));
+    }
+
+    #[test]
+    fn test_flags_currencies_with_rates() {
+        let rates = DailyRate {
+            date: "2024-12-04".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("USD".to_string(), Decimal::new(105, 2)),
+                ("XYZ".to_string(), Decimal::ONE),
+            ]),
+        };
+
+        let response = currency_list(Some(&rates));
+
+        assert!(response.rates_available);
+        assert_eq!(response.date.as_deref(), Some("2024-12-04"));
+        assert!(response.currencies["EUR"].rate_available);
+        assert!(response.currencies["USD"].rate_available);
+        assert!(!response.currencies["GBP"].rate_available);
+        // Non-ISO codes from the feed are still listed
+        assert!(response.currencies["XYZ"].rate_available);
+        assert!(response.currencies["XYZ"].name.is_none());
+    }
+}
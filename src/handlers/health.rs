@@ -1,23 +1,126 @@
 use crate::error::ApiError;
-use crate::models::HealthResponse;
-use crate::services::RedisStore;
-use axum::{extract::State, Json};
+use crate::models::{HealthResponse, ReadinessResponse};
+use crate::services::{RedisStore, is_stale, rate_age_hours};
+use crate::state::AppState;
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{SecondsFormat, Utc};
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "monitoring",
+    responses((status = 200, description = "Service health", body = HealthResponse))
+)]
 pub async fn health_handler(
-    State(store): State<RedisStore>,
+    State(state): State<AppState>,
 ) -> Result<Json<HealthResponse>, ApiError> {
     // Check Redis health
-    let redis_status = match store.health_check().await {
-        Ok(_) => "healthy",
-        Err(_) => "unhealthy",
+    let redis_healthy = state.store.health_check().await.is_ok();
+    let redis_stats = state.store.connection_stats();
+    let redis_status = if redis_healthy {
+        "healthy"
+    } else {
+        "unhealthy"
     };
 
-    // Get last update date
-    let last_update = store.get_last_update_date().await.ok().flatten();
+    // Date of the rates actually served, read together with the table
+    let last_update = state
+        .store
+        .get_rates()
+        .await
+        .ok()
+        .flatten()
+        .map(|rates| rates.date.clone());
+
+    // Flag rates left unreplaced past the configured threshold
+    let age_hours = last_update
+        .as_deref()
+        .and_then(|date| rate_age_hours(date, Utc::now()));
+    let stale = last_update
+        .as_deref()
+        .is_some_and(|date| is_stale(date, Utc::now(), state.config.stale_threshold_hours));
+
+    // The last fetch was rejected for lacking required currencies
+    let missing_currencies = state.updater.missing_required();
+
+    // Upstream reachability, only probed when HEALTH_CHECK_ECB is enabled
+    let ecb_reachable = match &state.ecb_probe {
+        Some(probe) => Some(probe.is_reachable().await),
+        None => None,
+    };
+
+    // A scheduled update that never ran (or keeps failing) past its due time
+    let scheduler_overdue = state.scheduler.is_overdue(Utc::now());
+
+    let status = if stale
+        || !missing_currencies.is_empty()
+        || ecb_reachable == Some(false)
+        || scheduler_overdue
+    {
+        "degraded"
+    } else {
+        "ok"
+    };
 
     Ok(Json(HealthResponse {
-        status: "ok".to_string(),
+        status: status.to_string(),
         redis: redis_status.to_string(),
+        redis_ping_ms: redis_stats
+            .last_ping
+            .filter(|_| redis_healthy)
+            .map(|ping| ping.as_secs_f64() * 1000.0),
+        redis_reconnects: redis_stats.reconnects,
         last_update,
+        stale,
+        age_hours,
+        ecb: ecb_reachable.map(|reachable| {
+            if reachable {
+                "reachable"
+            } else {
+                "unreachable"
+            }
+            .to_string()
+        }),
+        scheduler: if scheduler_overdue {
+            "unhealthy"
+        } else {
+            "healthy"
+        }
+        .to_string(),
+        scheduler_last_run: state
+            .scheduler
+            .last_run()
+            .map(|at| at.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        missing_currencies,
     }))
 }
+
+/// Readiness probe: 200 only when Redis is reachable and rates are loaded,
+/// 503 otherwise so load balancers hold traffic until conversions can be served
+pub async fn readiness_handler(
+    State(store): State<RedisStore>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let redis_healthy = store.health_check().await.is_ok();
+    let rates_loaded = redis_healthy && store.has_rates().await.unwrap_or(false);
+    let ready = redis_healthy && rates_loaded;
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadinessResponse {
+            ready,
+            redis: if redis_healthy {
+                "healthy"
+            } else {
+                "unhealthy"
+            }
+            .to_string(),
+            rates_loaded,
+        }),
+    )
+}
@@ -0,0 +1,34 @@
+use crate::services::rate_age_hours;
+use crate::state::AppState;
+use crate::telemetry::{RATE_AGE_HOURS, REDIS_PING_SECONDS, REDIS_RECONNECTS_TOTAL};
+use axum::{extract::State, http::header, response::IntoResponse};
+use chrono::Utc;
+
+/// Prometheus text exposition of all recorded metrics
+pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    // Refresh the rate age gauge at scrape time so it keeps growing between updates
+    if let Some(age) = state
+        .store
+        .get_last_update_date()
+        .await
+        .ok()
+        .flatten()
+        .and_then(|date| rate_age_hours(&date, Utc::now()))
+    {
+        metrics::gauge!(RATE_AGE_HOURS).set(age as f64);
+    }
+
+    // Connection stats are kept by the store and copied in at scrape time
+    let redis = state.store.connection_stats();
+    if let Some(ping) = redis.last_ping {
+        metrics::gauge!(REDIS_PING_SECONDS).set(ping.as_secs_f64());
+    }
+    metrics::counter!(REDIS_RECONNECTS_TOTAL).absolute(redis.reconnects);
+
+    state.metrics.run_upkeep();
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
@@ -1,7 +1,25 @@
+pub mod admin;
+pub mod change;
 pub mod convert;
+pub mod currencies;
 pub mod health;
+pub mod metrics;
+pub mod pairs;
+pub mod portfolio;
 pub mod rates;
+pub mod refresh;
+pub mod timeseries;
+pub mod ws;
 
+pub use admin::*;
+pub use change::*;
 pub use convert::*;
+pub use currencies::*;
 pub use health::*;
+pub use metrics::*;
+pub use pairs::*;
+pub use portfolio::*;
 pub use rates::*;
+pub use refresh::*;
+pub use timeseries::*;
+pub use ws::*;
@@ -0,0 +1,158 @@
+use crate::error::ApiError;
+use crate::handlers::convert::pair_allowed;
+use crate::models::{CurrencyPair, DEFAULT_PAIRS_LIMIT, DailyRate, PairsQuery, PairsResponse};
+use crate::state::AppState;
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use validator::Validate;
+
+/// Directed pairs convertible with the latest rates, one page at a time
+/// Every currency in the table converts to every other one, limited by CONVERT_PAIRS
+pub async fn pairs_handler(
+    State(state): State<AppState>,
+    Query(params): Query<PairsQuery>,
+) -> Result<Json<PairsResponse>, ApiError> {
+    // Validate query parameters
+    params.validate()?;
+
+    let rates = state
+        .store
+        .get_rates()
+        .await?
+        .ok_or(ApiError::NoRatesAvailable)?;
+
+    let base = params.base.map(|base| base.to_uppercase());
+    let pairs = directed_pairs(&rates, base.as_deref(), &state.config.convert_pairs)?;
+
+    let limit = params.limit.unwrap_or(DEFAULT_PAIRS_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+
+    Ok(Json(PairsResponse {
+        date: rates.date.clone(),
+        total: pairs.len(),
+        offset,
+        limit,
+        pairs: pairs.into_iter().skip(offset).take(limit).collect(),
+    }))
+}
+
+/// All allowed pairs between currencies in `rates`, sorted by source then target
+fn directed_pairs(
+    rates: &DailyRate,
+    base: Option<&str>,
+    allowed: &[(String, String)],
+) -> Result<Vec<CurrencyPair>, ApiError> {
+    // The table's base is quoted implicitly, so it is a currency even when missing from `rates`
+    let mut codes: Vec<&str> = rates
+        .rates
+        .keys()
+        .chain(std::iter::once(&rates.base))
+        .map(String::as_str)
+        .collect();
+    codes.sort_unstable();
+    codes.dedup();
+
+    if let Some(base) = base
+        && !codes.contains(&base)
+    {
+        return Err(ApiError::CurrencyNotFound(base.to_string()));
+    }
+
+    let sources = codes
+        .iter()
+        .filter(|from| base.is_none_or(|base| base == **from));
+
+    Ok(sources
+        .flat_map(|from| codes.iter().map(move |to| (*from, *to)))
+        .filter(|(from, to)| from != to && pair_allowed(allowed, from, to))
+        .map(|(from, to)| CurrencyPair {
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    fn rates() -> DailyRate {
+        DailyRate {
+            date: "2024-12-04".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("EUR".to_string(), dec!(1)),
+                ("USD".to_string(), dec!(1.0534)),
+                ("GBP".to_string(), dec!(0.8345)),
+                ("JPY".to_string(), dec!(158.23)),
+            ]),
+        }
+    }
+
+    fn pair(from: &str, to: &str) -> CurrencyPair {
+        CurrencyPair {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_all_directed_pairs() {
+        let pairs = directed_pairs(&rates(), None, &[]).unwrap();
+
+        // N * (N - 1) pairs, sorted
+        assert_eq!(pairs.len(), 12);
+        assert_eq!(pairs[0], pair("EUR", "GBP"));
+        assert_eq!(pairs[11], pair("USD", "JPY"));
+        assert!(pairs.iter().all(|pair| pair.from != pair.to));
+    }
+
+    #[test]
+    fn test_pairs_for_base() {
+        let pairs = directed_pairs(&rates(), Some("USD"), &[]).unwrap();
+
+        assert_eq!(
+            pairs,
+            vec![pair("USD", "EUR"), pair("USD", "GBP"), pair("USD", "JPY")]
+        );
+        assert!(matches!(
+            directed_pairs(&rates(), Some("CHF"), &[]),
+            Err(ApiError::CurrencyNotFound(code)) if code == "CHF"
+        ));
+    }
+
+    #[test]
+    fn test_pairs_respect_allow_list() {
+        let allowed = vec![
+            ("EUR".to_string(), "USD".to_string()),
+            ("USD".to_string(), "EUR".to_string()),
+            ("EUR".to_string(), "CHF".to_string()),
+        ];
+
+        let pairs = directed_pairs(&rates(), None, &allowed).unwrap();
+
+        assert_eq!(pairs, vec![pair("EUR", "USD"), pair("USD", "EUR")]);
+    }
+
+    #[test]
+    fn test_pairs_include_base_missing_from_rates() {
+        let rates = DailyRate {
+            date: "2024-12-04".to_string(),
+            base: "USD".to_string(),
+            rates: HashMap::from([("EUR".to_string(), dec!(0.95))]),
+        };
+
+        assert_eq!(
+            directed_pairs(&rates, None, &[]).unwrap(),
+            vec![pair("EUR", "USD"), pair("USD", "EUR")]
+        );
+        assert_eq!(
+            directed_pairs(&rates, Some("USD"), &[]).unwrap(),
+            vec![pair("USD", "EUR")]
+        );
+    }
+}
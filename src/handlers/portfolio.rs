@@ -0,0 +1,189 @@
+use crate::error::ApiError;
+use crate::handlers::convert::ensure_pair_allowed;
+use crate::handlers::rates::stale_rate_headers;
+use crate::models::{
+    DailyRate, PortfolioLine, PortfolioRequest, PortfolioResponse, currency_meta, parse_amount,
+};
+use crate::services::convert_currency;
+use crate::state::AppState;
+use axum::{Json, extract::State, extract::rejection::JsonRejection, http::HeaderMap};
+use chrono::Utc;
+use rust_decimal::{Decimal, RoundingStrategy};
+use validator::Validate;
+
+/// Maximum number of holdings in a single portfolio
+const MAX_HOLDINGS: usize = 100;
+
+/// Value a basket of currency holdings in one target currency
+/// Each holding is converted at the latest market rate (no spread); any
+/// unknown currency fails the whole request; stale rates carry the stale-rate headers
+pub async fn portfolio_handler(
+    State(state): State<AppState>,
+    body: Result<Json<PortfolioRequest>, JsonRejection>,
+) -> Result<(HeaderMap, Json<PortfolioResponse>), ApiError> {
+    let Json(request) = body?;
+
+    request.validate()?;
+
+    if request.holdings.is_empty() || request.holdings.len() > MAX_HOLDINGS {
+        return Err(ApiError::Unprocessable(format!(
+            "holdings must contain between 1 and {} currencies",
+            MAX_HOLDINGS
+        )));
+    }
+
+    let target = request.target.to_uppercase();
+    let holdings = request
+        .holdings
+        .iter()
+        .map(|(code, amount)| {
+            let code = code.to_uppercase();
+            let amount = parse_amount(
+                &amount.0,
+                state.config.max_convert_amount,
+                state.config.amount_grouping,
+            )
+            .map_err(|e| e.context(&format!("{} holding", code)))?;
+            ensure_pair_allowed(&state.config.convert_pairs, &code, &target)?;
+            Ok((code, amount))
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    let rates = state
+        .store
+        .get_rates()
+        .await?
+        .ok_or(ApiError::NoRatesAvailable)?;
+    let headers = stale_rate_headers(
+        &rates.date,
+        Utc::now(),
+        state.config.stale_threshold_hours,
+        state.config.serve_stale,
+    )?;
+
+    let (lines, total) = value_holdings(&rates, &target, holdings)
+        .map_err(|e| e.with_available_currencies(&rates))?;
+
+    let response = PortfolioResponse {
+        target,
+        date: rates.date.clone(),
+        holdings: lines,
+        total,
+    };
+
+    Ok((headers, Json(response)))
+}
+
+/// Convert each holding to `target`, sum the values and work out each share
+/// Values are rounded to the target's ISO 4217 minor unit before summing, so the
+/// lines always add up to the total
+fn value_holdings(
+    rates: &DailyRate,
+    target: &str,
+    holdings: Vec<(String, Decimal)>,
+) -> Result<(Vec<PortfolioLine>, Decimal), ApiError> {
+    let round_dp = currency_meta(target).map(|meta| meta.minor_units);
+
+    let mut total = Decimal::ZERO;
+    let mut lines = Vec::with_capacity(holdings.len());
+
+    for (currency, amount) in holdings {
+        let (value, rate) = convert_currency(rates, &currency, target, amount)?;
+        let value = match round_dp {
+            Some(dp) => value.round_dp_with_strategy(dp, RoundingStrategy::MidpointAwayFromZero),
+            None => value,
+        };
+
+        total = total.checked_add(value).ok_or_else(|| {
+            ApiError::CalculationError("Portfolio total is too large".to_string())
+        })?;
+        lines.push(PortfolioLine {
+            currency,
+            amount,
+            rate,
+            value,
+            percent: Decimal::ZERO,
+        });
+    }
+
+    // An all-zero portfolio leaves every share at zero
+    if !total.is_zero() {
+        for line in &mut lines {
+            line.percent = (line.value * Decimal::ONE_HUNDRED / total)
+                .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero);
+        }
+    }
+
+    Ok((lines, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    fn rates() -> DailyRate {
+        DailyRate {
+            date: "2024-12-04".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("EUR".to_string(), dec!(1)),
+                ("USD".to_string(), dec!(1.0534)),
+                ("JPY".to_string(), dec!(158.23)),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_value_holdings_sums_rounded_lines() {
+        let holdings = vec![
+            ("JPY".to_string(), dec!(50000)),
+            ("USD".to_string(), dec!(1000)),
+            ("EUR".to_string(), dec!(10)),
+        ];
+
+        let (lines, total) = value_holdings(&rates(), "EUR", holdings).unwrap();
+
+        // 50000 / 158.23 = 315.996..., 1000 / 1.0534 = 949.307...
+        assert_eq!(lines[0].value, dec!(316.00));
+        assert_eq!(lines[1].value, dec!(949.31));
+        assert_eq!(lines[2].value, dec!(10));
+        assert_eq!(total, dec!(1275.31));
+        assert_eq!(total, lines.iter().map(|line| line.value).sum::<Decimal>());
+        assert_eq!(lines[0].percent, dec!(24.78));
+        assert_eq!(lines[1].percent, dec!(74.44));
+        assert_eq!(lines[2].percent, dec!(0.78));
+    }
+
+    #[test]
+    fn test_value_holdings_zero_total_has_zero_shares() {
+        let (lines, total) =
+            value_holdings(&rates(), "EUR", vec![("USD".to_string(), dec!(0))]).unwrap();
+
+        assert_eq!(total, Decimal::ZERO);
+        assert_eq!(lines[0].percent, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_value_holdings_uses_target_minor_units() {
+        let (lines, total) =
+            value_holdings(&rates(), "JPY", vec![("USD".to_string(), dec!(1000))]).unwrap();
+
+        // 1000 USD = 150209.8... JPY, which has no minor unit
+        assert_eq!(lines[0].value, dec!(150209));
+        assert_eq!(total, dec!(150209));
+    }
+
+    #[test]
+    fn test_value_holdings_names_unknown_currency() {
+        let holdings = vec![
+            ("USD".to_string(), dec!(1000)),
+            ("CHF".to_string(), dec!(500)),
+        ];
+
+        let err = value_holdings(&rates(), "EUR", holdings).unwrap_err();
+
+        assert!(matches!(err, ApiError::CurrencyNotFound(code) if code == "CHF"));
+    }
+}
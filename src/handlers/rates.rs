@@ -1,40 +1,810 @@
 use crate::error::ApiError;
-use crate::models::{LatestRatesQuery, LatestRatesResponse};
-use crate::services::{rebase_rates, RedisStore};
-use axum::{extract::{Query, State}, Json};
+use crate::models::{
+    ComparedRate, ComparedRatesResponse, DailyRate, ErrorResponse, LatestRatesQuery,
+    LatestRatesResponse, parse_symbols,
+};
+use crate::services::{RedisStore, is_stale, rate_age_hours, rate_change, rebase_rates};
+use crate::state::AppState;
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use validator::Validate;
 
+/// Set to `true` on responses built from rates older than STALE_THRESHOLD_HOURS
+const RATE_STALE_HEADER: HeaderName = HeaderName::from_static("x-rate-stale");
+
+/// Age in hours of stale rates, sent along with RATE_STALE_HEADER
+const RATE_AGE_HEADER: HeaderName = HeaderName::from_static("x-rate-age-hours");
+
+#[utoipa::path(
+    get,
+    path = "/api/latest",
+    tag = "rates",
+    params(LatestRatesQuery),
+    responses(
+        (status = 200, description = "Latest rate table, as `currency,rate` rows with `Accept: text/csv`",
+            content((LatestRatesResponse = "application/json"), (String = "text/csv"))),
+        (status = 304, description = "Unchanged since the client's ETag or Last-Modified"),
+        (status = 400, description = "Malformed parameters", body = ErrorResponse),
+        (status = 422, description = "Symbol missing from the rate table", body = ErrorResponse),
+        (status = 503, description = "No rates loaded yet, or stale with SERVE_STALE off", body = ErrorResponse)
+    )
+)]
 pub async fn latest_rates_handler(
-    State(store): State<RedisStore>,
-    Query(params): Query<LatestRatesQuery>,
-) -> Result<Json<LatestRatesResponse>, ApiError> {
+    State(state): State<AppState>,
+    Query(mut params): Query<LatestRatesQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    params.resolve_aliases(&state.config.currency_aliases);
+
     // Validate query parameters
-    params
-        .validate()
-        .map_err(|e| ApiError::ValidationError(e.to_string()))?;
-
-    // Get rates from Redis
-    let rates = store
-        .get_rates()
-        .await?
-        .ok_or(ApiError::NoRatesAvailable)?;
-
-    // If base currency is specified, rebase the rates
-    let result = if let Some(base) = params.base {
-        let rebased = rebase_rates(&rates, &base)?;
-        LatestRatesResponse {
-            date: rebased.date,
-            base: rebased.base,
-            rates: rebased.rates,
+    params.validate()?;
+
+    let symbols = params
+        .symbols
+        .as_deref()
+        .map(parse_symbols)
+        .transpose()
+        .map_err(ApiError::ValidationError)?;
+
+    // Get rates from Redis (shared with other requests, not copied)
+    let store = &state.store;
+    let rates = store.get_rates().await?.ok_or(ApiError::NoRatesAvailable)?;
+    let stale_headers = stale_rate_headers(
+        &rates.date,
+        Utc::now(),
+        state.config.stale_threshold_hours,
+        state.config.serve_stale,
+    )?;
+
+    // Rebase unless the table is already on the requested base (cached per base and date)
+    let table = match &params.base {
+        Some(base) if !base.eq_ignore_ascii_case(&rates.base) => {
+            store.rebase_cache().get_or_rebase(&rates, base)?
+        }
+        _ => rates,
+    };
+
+    // Keep only the requested symbols (after any rebase); the full table is served as is
+    let mut selected = match &symbols {
+        Some(symbols) => Cow::Owned(filter_symbols(&table.rates, symbols)?),
+        None => Cow::Borrowed(&table.rates),
+    };
+
+    // Slice in currency code order so consecutive pages neither overlap nor skip codes
+    let page = Page::from_query(&params);
+    let total = page.map(|page| {
+        let total = selected.len();
+        selected = Cow::Owned(page.slice(&selected));
+        total
+    });
+
+    // Changes are computed from full-precision rates, before any rounding
+    let compared = match params.compare {
+        Some(_) => {
+            let previous = previous_table(store, &table).await?;
+            Some((compare_rates(&selected, previous.as_ref()), previous))
+        }
+        None => None,
+    };
+
+    // Round for display only; stored and cached tables keep full precision
+    if let Some(precision) = params.precision {
+        round_rates(selected.to_mut(), precision);
+    }
+
+    // Rates change once per ECB publication, so the request parameters identify the body
+    let format = ResponseFormat::negotiate(&headers);
+    let etag = rates_etag(
+        &table.date,
+        &table.base,
+        symbols.as_deref(),
+        params.precision,
+        compared.is_some(),
+        page,
+        format,
+    );
+    let last_modified = last_modified(&table.date);
+    let mut cache_headers = [
+        (header::ETAG, HeaderValue::from_str(&etag)),
+        (header::LAST_MODIFIED, HeaderValue::from_str(&last_modified)),
+        (header::VARY, Ok(HeaderValue::from_static("accept"))),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.ok().map(|value| (name, value)))
+    .collect::<HeaderMap>();
+    cache_headers.extend(stale_headers);
+
+    if is_not_modified(&headers, &etag, &table.date) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let body = match compared {
+        Some((mut rates, previous)) => {
+            if let Some(precision) = params.precision {
+                round_compared(&mut rates, precision);
+            }
+            format.render_compared(ComparedRatesResponse {
+                date: &table.date,
+                base: &table.base,
+                previous_date: previous.as_ref().map(|previous| previous.date.as_str()),
+                total,
+                rates: &rates,
+            })
+        }
+        None => format.render(LatestRatesResponse {
+            date: &table.date,
+            base: &table.base,
+            total,
+            rates: &selected,
+        }),
+    };
+
+    Ok((cache_headers, body).into_response())
+}
+
+/// Slice of a rate table requested with `limit` and/or `offset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Page {
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl Page {
+    /// None when neither parameter is given, so the whole table is returned
+    fn from_query(params: &LatestRatesQuery) -> Option<Self> {
+        (params.limit.is_some() || params.offset.is_some()).then(|| Page {
+            offset: params.offset.unwrap_or(0),
+            limit: params.limit,
+        })
+    }
+
+    /// Rates on this page, counted in currency code order
+    fn slice(self, rates: &HashMap<String, Decimal>) -> HashMap<String, Decimal> {
+        let sorted: BTreeMap<_, _> = rates.iter().collect();
+        sorted
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|(currency, rate)| (currency.clone(), *rate))
+            .collect()
+    }
+}
+
+/// Headers flagging latest rates dated `date` as stale, empty while they are fresh
+/// With `serve_stale` off, stale rates are refused instead of flagged
+pub(crate) fn stale_rate_headers(
+    date: &str,
+    now: DateTime<Utc>,
+    threshold_hours: i64,
+    serve_stale: bool,
+) -> Result<HeaderMap, ApiError> {
+    let mut headers = HeaderMap::new();
+    let (true, Some(age)) = (
+        is_stale(date, now, threshold_hours),
+        rate_age_hours(date, now),
+    ) else {
+        return Ok(headers);
+    };
+    if !serve_stale {
+        return Err(ApiError::StaleRates(age));
+    }
+
+    headers.insert(RATE_STALE_HEADER, HeaderValue::from_static("true"));
+    headers.insert(RATE_AGE_HEADER, HeaderValue::from(age));
+    Ok(headers)
+}
+
+/// Most recent snapshot before `table`'s date, on the same base
+/// Missing history, or a previous table lacking the base, yields None rather than an error
+async fn previous_table(
+    store: &RedisStore,
+    table: &DailyRate,
+) -> Result<Option<DailyRate>, ApiError> {
+    let Some(before) = NaiveDate::parse_from_str(&table.date, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.pred_opt())
+    else {
+        return Ok(None);
+    };
+
+    let Some(previous) = store.get_rates_on_or_before(before).await? else {
+        return Ok(None);
+    };
+
+    // Not cached: the rebase cache holds the latest date only
+    Ok(rebase_rates(&previous, &table.base).ok())
+}
+
+/// Pair each rate with its previous value and percentage change, where known
+fn compare_rates(
+    rates: &HashMap<String, Decimal>,
+    previous: Option<&DailyRate>,
+) -> BTreeMap<String, ComparedRate> {
+    rates
+        .iter()
+        .map(|(currency, &rate)| {
+            let previous = previous.and_then(|table| table.rates.get(currency).copied());
+            let change_pct = previous
+                .and_then(|previous| rate_change(previous, rate).ok())
+                .map(|(_, percent)| percent);
+
+            (
+                currency.clone(),
+                ComparedRate {
+                    rate,
+                    previous,
+                    change_pct,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Round rates, previous rates and changes to `precision` decimal places
+fn round_compared(rates: &mut BTreeMap<String, ComparedRate>, precision: u32) {
+    for compared in rates.values_mut() {
+        compared.rate = compared.rate.round_dp(precision);
+        compared.previous = compared.previous.map(|rate| rate.round_dp(precision));
+        compared.change_pct = compared.change_pct.map(|pct| pct.round_dp(precision));
+    }
+}
+
+/// Representation of a rate table chosen from the Accept header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    Json,
+    Csv,
+}
+
+impl ResponseFormat {
+    /// CSV only when `text/csv` is listed explicitly with a quality at least
+    /// that of `application/json`; anything else, including no Accept, is JSON
+    fn negotiate(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return ResponseFormat::Json;
+        };
+
+        let quality = |media_type: &str| {
+            accept
+                .split(',')
+                .filter_map(|range| {
+                    let mut parts = range.split(';').map(str::trim);
+                    let range_type = parts.next()?;
+                    if !range_type.eq_ignore_ascii_case(media_type) {
+                        return None;
+                    }
+                    let q = parts
+                        .filter_map(|param| param.strip_prefix("q="))
+                        .find_map(|q| q.parse::<f32>().ok())
+                        .unwrap_or(1.0);
+                    Some(q)
+                })
+                .fold(None, |best: Option<f32>, q| {
+                    Some(best.map_or(q, |b| b.max(q)))
+                })
+        };
+
+        match (quality("text/csv"), quality("application/json")) {
+            (Some(csv), json) if csv > 0.0 && csv >= json.unwrap_or(0.0) => ResponseFormat::Csv,
+            _ => ResponseFormat::Json,
         }
-    } else {
-        // Return rates with default EUR base
-        LatestRatesResponse {
-            date: rates.date,
-            base: rates.base,
-            rates: rates.rates,
+    }
+
+    /// Build the response body for a table compared with the previous business day
+    fn render_compared(self, table: ComparedRatesResponse) -> Response {
+        match self {
+            ResponseFormat::Json => Json(table).into_response(),
+            ResponseFormat::Csv => (
+                [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+                compared_csv(&table),
+            )
+                .into_response(),
+        }
+    }
+
+    /// Build the response body for an already rebased, filtered and rounded table
+    fn render(self, table: LatestRatesResponse) -> Response {
+        match self {
+            ResponseFormat::Json => Json(table).into_response(),
+            ResponseFormat::Csv => (
+                [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+                rates_csv(&table),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// `currency,rate` rows sorted by code, preceded by `#` lines carrying the base and date
+fn rates_csv(table: &LatestRatesResponse) -> String {
+    let rates: BTreeMap<_, _> = table.rates.iter().collect();
+
+    let mut csv = format!("# base={}\n# date={}\n", table.base, table.date);
+    if let Some(total) = table.total {
+        csv.push_str(&format!("# total={}\n", total));
+    }
+    csv.push_str("currency,rate\n");
+    for (currency, rate) in rates {
+        csv.push_str(&format!("{},{}\n", currency, rate));
+    }
+
+    csv
+}
+
+/// `currency,rate,previous,change_pct` rows sorted by code; unknown changes are left empty
+fn compared_csv(table: &ComparedRatesResponse) -> String {
+    let mut csv = format!(
+        "# base={}\n# date={}\n# previous_date={}\n",
+        table.base,
+        table.date,
+        table.previous_date.unwrap_or_default()
+    );
+    if let Some(total) = table.total {
+        csv.push_str(&format!("# total={}\n", total));
+    }
+    csv.push_str("currency,rate,previous,change_pct\n");
+    for (currency, compared) in table.rates {
+        let optional = |value: Option<Decimal>| value.map(|v| v.to_string()).unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            currency,
+            compared.rate,
+            optional(compared.previous),
+            optional(compared.change_pct)
+        ));
+    }
+
+    csv
+}
+
+/// Round every rate to `precision` decimal places
+fn round_rates(rates: &mut HashMap<String, Decimal>, precision: u32) {
+    for rate in rates.values_mut() {
+        *rate = rate.round_dp(precision);
+    }
+}
+
+/// Strong ETag for a rate table, e.g. `"2024-12-04:USD"` or `"2024-12-04:USD:GBP,JPY:p4:prev:o10:l5:csv"`
+fn rates_etag(
+    date: &str,
+    base: &str,
+    symbols: Option<&[String]>,
+    precision: Option<u32>,
+    compare: bool,
+    page: Option<Page>,
+    format: ResponseFormat,
+) -> String {
+    let mut parts = vec![date.to_string(), base.to_string()];
+    if let Some(symbols) = symbols {
+        parts.push(symbols.join(","));
+    }
+    if let Some(precision) = precision {
+        parts.push(format!("p{}", precision));
+    }
+    if compare {
+        parts.push("prev".to_string());
+    }
+    if let Some(page) = page {
+        parts.push(format!("o{}", page.offset));
+        if let Some(limit) = page.limit {
+            parts.push(format!("l{}", limit));
         }
+    }
+    if format == ResponseFormat::Csv {
+        parts.push("csv".to_string());
+    }
+
+    format!("\"{}\"", parts.join(":"))
+}
+
+/// HTTP date for midnight UTC of the rate date, e.g. `Wed, 04 Dec 2024 00:00:00 GMT`
+fn last_modified(date: &str) -> String {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| time.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+        .unwrap_or_default()
+}
+
+/// Whether the client's cached copy is still current
+/// If-None-Match takes precedence; If-Modified-Since is only consulted without it
+fn is_not_modified(headers: &HeaderMap, etag: &str, date: &str) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        return if_none_match.to_str().is_ok_and(|value| {
+            value
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag)
+        });
+    }
+
+    let Some(since) = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+    else {
+        return false;
     };
 
-    Ok(Json(result))
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .is_some_and(|modified| modified.and_utc() <= since)
+}
+
+/// Restrict a rate table to `symbols`, rejecting codes missing from it
+fn filter_symbols(
+    rates: &HashMap<String, Decimal>,
+    symbols: &[String],
+) -> Result<HashMap<String, Decimal>, ApiError> {
+    symbols
+        .iter()
+        .map(|symbol| {
+            rates
+                .get(symbol)
+                .map(|rate| (symbol.clone(), *rate))
+                .ok_or_else(|| {
+                    ApiError::Unprocessable(format!(
+                        "Unknown currency code '{}' in symbols",
+                        symbol
+                    ))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn rates() -> HashMap<String, Decimal> {
+        HashMap::from([
+            ("USD".to_string(), dec!(1.05)),
+            ("GBP".to_string(), dec!(0.85)),
+            ("JPY".to_string(), dec!(158.2)),
+        ])
+    }
+
+    #[test]
+    fn test_filter_symbols() {
+        let symbols = vec!["USD".to_string(), "GBP".to_string()];
+
+        let filtered = filter_symbols(&rates(), &symbols).unwrap();
+
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered["USD"], dec!(1.05));
+        assert_eq!(filtered["GBP"], dec!(0.85));
+    }
+
+    fn headers(name: header::HeaderName, value: &str) -> HeaderMap {
+        HeaderMap::from_iter([(name, HeaderValue::from_str(value).unwrap())])
+    }
+
+    #[test]
+    fn test_rates_etag() {
+        let symbols = vec!["GBP".to_string(), "JPY".to_string()];
+
+        assert_eq!(
+            rates_etag(
+                "2024-12-04",
+                "USD",
+                None,
+                None,
+                false,
+                None,
+                ResponseFormat::Json
+            ),
+            "\"2024-12-04:USD\""
+        );
+        assert_eq!(
+            rates_etag(
+                "2024-12-04",
+                "USD",
+                Some(&symbols),
+                None,
+                false,
+                None,
+                ResponseFormat::Json
+            ),
+            "\"2024-12-04:USD:GBP,JPY\""
+        );
+        assert_eq!(
+            rates_etag(
+                "2024-12-04",
+                "USD",
+                Some(&symbols),
+                Some(4),
+                false,
+                None,
+                ResponseFormat::Json
+            ),
+            "\"2024-12-04:USD:GBP,JPY:p4\""
+        );
+        assert_eq!(
+            rates_etag(
+                "2024-12-04",
+                "USD",
+                None,
+                None,
+                false,
+                None,
+                ResponseFormat::Csv
+            ),
+            "\"2024-12-04:USD:csv\""
+        );
+        assert_eq!(
+            rates_etag(
+                "2024-12-04",
+                "USD",
+                None,
+                Some(4),
+                true,
+                None,
+                ResponseFormat::Json
+            ),
+            "\"2024-12-04:USD:p4:prev\""
+        );
+        assert_eq!(
+            rates_etag(
+                "2024-12-04",
+                "USD",
+                None,
+                None,
+                false,
+                Some(Page {
+                    offset: 10,
+                    limit: Some(5)
+                }),
+                ResponseFormat::Json
+            ),
+            "\"2024-12-04:USD:o10:l5\""
+        );
+    }
+
+    #[test]
+    fn test_page_slices_in_code_order() {
+        let rates = rates();
+        let page = |offset, limit| Page { offset, limit };
+
+        let first = page(0, Some(2)).slice(&rates);
+        assert_eq!(
+            first,
+            HashMap::from([
+                ("GBP".to_string(), dec!(0.85)),
+                ("JPY".to_string(), dec!(158.2))
+            ])
+        );
+        assert_eq!(
+            page(2, Some(2)).slice(&rates),
+            HashMap::from([("USD".to_string(), dec!(1.05))])
+        );
+        assert_eq!(page(1, None).slice(&rates).len(), 2);
+        assert!(page(5, Some(2)).slice(&rates).is_empty());
+    }
+
+    #[test]
+    fn test_round_rates() {
+        let mut rates = HashMap::from([
+            ("EUR".to_string(), dec!(0.952380952380952380952380952)),
+            ("JPY".to_string(), dec!(150.5)),
+        ]);
+
+        round_rates(&mut rates, 4);
+
+        assert_eq!(rates["EUR"], dec!(0.9524));
+        assert_eq!(rates["JPY"], dec!(150.5));
+    }
+
+    #[test]
+    fn test_last_modified() {
+        assert_eq!(last_modified("2024-12-04"), "Wed, 04 Dec 2024 00:00:00 GMT");
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = "\"2024-12-04:EUR\"";
+
+        assert!(is_not_modified(
+            &headers(
+                header::IF_NONE_MATCH,
+                "\"2024-12-03:EUR\", \"2024-12-04:EUR\""
+            ),
+            etag,
+            "2024-12-04"
+        ));
+        assert!(is_not_modified(
+            &headers(header::IF_NONE_MATCH, "W/\"2024-12-04:EUR\""),
+            etag,
+            "2024-12-04"
+        ));
+        assert!(!is_not_modified(
+            &headers(header::IF_NONE_MATCH, "\"2024-12-03:EUR\""),
+            etag,
+            "2024-12-04"
+        ));
+        assert!(!is_not_modified(&HeaderMap::new(), etag, "2024-12-04"));
+    }
+
+    #[test]
+    fn test_if_modified_since() {
+        let etag = "\"2024-12-04:EUR\"";
+
+        assert!(is_not_modified(
+            &headers(header::IF_MODIFIED_SINCE, "Wed, 04 Dec 2024 00:00:00 GMT"),
+            etag,
+            "2024-12-04"
+        ));
+        assert!(!is_not_modified(
+            &headers(header::IF_MODIFIED_SINCE, "Tue, 03 Dec 2024 00:00:00 GMT"),
+            etag,
+            "2024-12-04"
+        ));
+    }
+
+    #[test]
+    fn test_filter_symbols_names_unknown_code() {
+        let symbols = vec!["USD".to_string(), "XYZ".to_string()];
+
+        let err = filter_symbols(&rates(), &symbols).unwrap_err();
+
+        assert!(err.to_string().contains("'XYZ'"));
+    }
+
+    #[test]
+    fn test_negotiate_format() {
+        let negotiate = |accept: &str| ResponseFormat::negotiate(&headers(header::ACCEPT, accept));
+
+        assert_eq!(
+            ResponseFormat::negotiate(&HeaderMap::new()),
+            ResponseFormat::Json
+        );
+        assert_eq!(negotiate("text/csv"), ResponseFormat::Csv);
+        assert_eq!(negotiate("text/csv, */*;q=0.1"), ResponseFormat::Csv);
+        assert_eq!(negotiate("application/json"), ResponseFormat::Json);
+        assert_eq!(
+            negotiate("application/json, text/csv;q=0.5"),
+            ResponseFormat::Json
+        );
+        assert_eq!(negotiate("text/csv;q=0"), ResponseFormat::Json);
+        assert_eq!(negotiate("text/html, */*"), ResponseFormat::Json);
+    }
+
+    #[test]
+    fn test_rates_csv() {
+        let rates = rates();
+        let mut table = LatestRatesResponse {
+            date: "2024-12-04",
+            base: "EUR",
+            total: None,
+            rates: &rates,
+        };
+
+        assert_eq!(
+            rates_csv(&table),
+            "# base=EUR\n# date=2024-12-04\ncurrency,rate\nGBP,0.85\nJPY,158.2\nUSD,1.05\n"
+        );
+
+        table.total = Some(40);
+        assert!(
+            rates_csv(&table)
+                .starts_with("# base=EUR\n# date=2024-12-04\n# total=40\ncurrency,rate\n")
+        );
+    }
+
+    #[test]
+    fn test_compare_rates() {
+        let previous = DailyRate {
+            date: "2024-12-03".to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.04)),
+                ("GBP".to_string(), dec!(0.85)),
+            ]),
+        };
+        let rates = HashMap::from([
+            ("USD".to_string(), dec!(1.05)),
+            ("JPY".to_string(), dec!(158.2)),
+        ]);
+
+        let mut compared = compare_rates(&rates, Some(&previous));
+        round_compared(&mut compared, 2);
+
+        assert_eq!(
+            compared["USD"],
+            ComparedRate {
+                rate: dec!(1.05),
+                previous: Some(dec!(1.04)),
+                change_pct: Some(dec!(0.96)),
+            }
+        );
+        // No previous rate for JPY, so only the current rate is reported
+        assert_eq!(compared["JPY"].previous, None);
+        assert_eq!(compared["JPY"].change_pct, None);
+    }
+
+    #[test]
+    fn test_compare_without_history_omits_changes() {
+        let rates = HashMap::from([("USD".to_string(), dec!(1.05))]);
+        let compared = compare_rates(&rates, None);
+
+        assert_eq!(
+            serde_json::to_value(&compared).unwrap(),
+            serde_json::json!({"USD": {"rate": "1.05"}})
+        );
+    }
+
+    #[test]
+    fn test_compared_csv() {
+        let rates = BTreeMap::from([
+            (
+                "USD".to_string(),
+                ComparedRate {
+                    rate: dec!(1.05),
+                    previous: Some(dec!(1.04)),
+                    change_pct: Some(dec!(0.96)),
+                },
+            ),
+            (
+                "JPY".to_string(),
+                ComparedRate {
+                    rate: dec!(158.2),
+                    previous: None,
+                    change_pct: None,
+                },
+            ),
+        ]);
+        let table = ComparedRatesResponse {
+            date: "2024-12-04",
+            base: "EUR",
+            previous_date: Some("2024-12-03"),
+            total: None,
+            rates: &rates,
+        };
+
+        assert_eq!(
+            compared_csv(&table),
+            "# base=EUR\n# date=2024-12-04\n# previous_date=2024-12-03\ncurrency,rate,previous,change_pct\nJPY,158.2,,\nUSD,1.05,1.04,0.96\n"
+        );
+    }
+
+    #[test]
+    fn test_stale_rate_headers() {
+        use chrono::TimeZone;
+        // Published 2024-12-04 15:00 UTC -> 18 hours old, then 51 hours old, 26 hours
+        // past Thursday's publication
+        let fresh = Utc.with_ymd_and_hms(2024, 12, 5, 9, 0, 0).unwrap();
+        let stale = Utc.with_ymd_and_hms(2024, 12, 6, 18, 0, 0).unwrap();
+
+        assert!(
+            stale_rate_headers("2024-12-04", fresh, 26, true)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            stale_rate_headers("2024-12-04", fresh, 26, false)
+                .unwrap()
+                .is_empty()
+        );
+
+        let headers = stale_rate_headers("2024-12-04", stale, 26, true).unwrap();
+        assert_eq!(headers[RATE_STALE_HEADER], "true");
+        assert_eq!(headers[RATE_AGE_HEADER], "51");
+
+        assert!(matches!(
+            stale_rate_headers("2024-12-04", stale, 26, false),
+            Err(ApiError::StaleRates(51))
+        ));
+    }
 }
@@ -0,0 +1,23 @@
+use crate::error::ApiError;
+use crate::models::RefreshResponse;
+use crate::services::RateUpdater;
+use axum::{Json, extract::State};
+
+/// Fetch and store the latest rates immediately
+/// Returns 409 Conflict while another update (manual or scheduled) is running
+/// The update runs in its own task, so a client that disconnects cannot cancel it
+/// halfway through storing rates or before the update lock is released
+pub async fn refresh_handler(
+    State(updater): State<RateUpdater>,
+) -> Result<Json<RefreshResponse>, ApiError> {
+    tracing::info!("Manual exchange rate refresh requested");
+
+    let rates = tokio::spawn(async move { updater.update().await })
+        .await
+        .map_err(|e| ApiError::InternalError(format!("Rate refresh task failed: {}", e)))??;
+
+    Ok(Json(RefreshResponse {
+        date: rates.date,
+        rates_count: rates.rates.len(),
+    }))
+}
@@ -0,0 +1,199 @@
+use crate::error::ApiError;
+use crate::models::{ECB_BASE_CURRENCY, TimeseriesQuery, TimeseriesResponse, parse_symbols};
+use crate::services::{RedisStore, ensure_not_future, ensure_published, rebase_rates};
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::{NaiveDate, Utc};
+use std::collections::BTreeMap;
+use validator::Validate;
+
+/// Maximum number of days a single time-series request may span
+const MAX_RANGE_DAYS: i64 = 366;
+
+pub async fn timeseries_handler(
+    State(store): State<RedisStore>,
+    Query(params): Query<TimeseriesQuery>,
+) -> Result<Json<TimeseriesResponse>, ApiError> {
+    // Validate query parameters
+    params.validate()?;
+
+    let latest_stored = latest_stored_date(&store).await?;
+    let start = parse_past_date("start_date", &params.start_date, latest_stored)?;
+    let end = parse_past_date("end_date", &params.end_date, latest_stored)?;
+
+    if start > end {
+        return Err(ApiError::Unprocessable(
+            "start_date must not be after end_date".to_string(),
+        ));
+    }
+
+    if (end - start).num_days() >= MAX_RANGE_DAYS {
+        return Err(ApiError::Unprocessable(format!(
+            "Date range must not exceed {} days",
+            MAX_RANGE_DAYS
+        )));
+    }
+
+    let symbols = params
+        .symbols
+        .as_deref()
+        .map(parse_symbols)
+        .transpose()
+        .map_err(ApiError::ValidationError)?;
+
+    // Days without a stored snapshot are simply omitted
+    let snapshots = store.get_rates_range(start, end).await?;
+
+    // Without an explicit base, every day is quoted against the first stored table's base
+    let base = match &params.base {
+        Some(base) => base.to_uppercase(),
+        None => snapshots
+            .first()
+            .map_or(ECB_BASE_CURRENCY, |snapshot| snapshot.base.as_str())
+            .to_string(),
+    };
+
+    let mut rates = BTreeMap::new();
+    for snapshot in snapshots {
+        // Skip days where the requested base was not published
+        let daily_rate = match rebase_rates(&snapshot, &base) {
+            Ok(daily_rate) => daily_rate,
+            Err(ApiError::CurrencyNotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+
+        let day_rates = match &symbols {
+            Some(symbols) => daily_rate
+                .rates
+                .into_iter()
+                .filter(|(currency, _)| symbols.contains(currency))
+                .collect(),
+            None => daily_rate.rates,
+        };
+
+        rates.insert(daily_rate.date, day_rates);
+    }
+
+    Ok(Json(TimeseriesResponse {
+        base,
+        start_date: start.to_string(),
+        end_date: end.to_string(),
+        rates,
+    }))
+}
+
+pub(crate) fn parse_date(field: &str, value: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| {
+        ApiError::ValidationError(format!(
+            "Invalid {} '{}', expected YYYY-MM-DD: {}",
+            field, value, e
+        ))
+    })
+}
+
+/// Parse a historical date, rejecting dates after today (UTC) or newer than the stored rates
+pub(crate) fn parse_past_date(
+    field: &str,
+    value: &str,
+    latest_stored: Option<NaiveDate>,
+) -> Result<NaiveDate, ApiError> {
+    let date = parse_date(field, value)?;
+    ensure_historical(date, latest_stored, Utc::now().date_naive())
+        .map_err(|e| ApiError::ValidationError(format!("Invalid {}: {}", field, e)))?;
+
+    Ok(date)
+}
+
+/// Check a requested date against today and, once any rates are stored, the latest stored date
+pub(crate) fn ensure_historical(
+    date: NaiveDate,
+    latest_stored: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<(), String> {
+    ensure_not_future(date, today)?;
+    match latest_stored {
+        Some(latest_stored) => ensure_published(date, latest_stored, today),
+        None => Ok(()),
+    }
+}
+
+/// Date of the latest stored rates, `None` before the first update
+pub(crate) async fn latest_stored_date(store: &RedisStore) -> Result<Option<NaiveDate>, ApiError> {
+    Ok(store
+        .get_last_update_date()
+        .await?
+        .and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DailyRate;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 12, d).unwrap()
+    }
+
+    #[test]
+    fn test_ensure_historical() {
+        // Nothing stored yet: only future dates are rejected
+        assert!(ensure_historical(day(9), None, day(9)).is_ok());
+        assert!(ensure_historical(day(10), None, day(9)).is_err());
+
+        assert!(ensure_historical(day(8), Some(day(6)), day(10)).is_ok());
+        assert!(ensure_historical(day(9), Some(day(6)), day(10)).is_err());
+        assert!(ensure_historical(day(11), Some(day(6)), day(10)).is_err());
+
+        // Today falls back to yesterday's business day only once that day is stored
+        assert!(ensure_historical(day(10), Some(day(9)), day(10)).is_ok());
+        assert!(ensure_historical(day(10), Some(day(6)), day(10)).is_err());
+        assert!(ensure_historical(day(9), Some(day(6)), day(9)).is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_dates_after_latest_stored_are_rejected() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let store = RedisStore::new(&redis_url, "test-timeseries:", 0, None)
+            .await
+            .unwrap();
+        store
+            .store_rates(&DailyRate {
+                date: "2024-12-04".to_string(),
+                base: "EUR".to_string(),
+                rates: HashMap::from([("USD".to_string(), dec!(1.0512))]),
+            })
+            .await
+            .unwrap();
+
+        let query = |start_date: &str, end_date: &str| {
+            Query(TimeseriesQuery {
+                base: None,
+                symbols: None,
+                start_date: start_date.to_string(),
+                end_date: end_date.to_string(),
+            })
+        };
+
+        let Json(response) =
+            timeseries_handler(State(store.clone()), query("2024-12-02", "2024-12-04"))
+                .await
+                .unwrap();
+        assert_eq!(response.rates.len(), 1);
+
+        let result =
+            timeseries_handler(State(store.clone()), query("2024-12-02", "2024-12-05")).await;
+        assert!(matches!(result, Err(ApiError::ValidationError(_))));
+
+        // Today only falls back to the previous business day once that day is stored,
+        // which 2024-12-04 is not
+        let today = Utc::now().date_naive().to_string();
+        let result = timeseries_handler(State(store), query(&today, &today)).await;
+        assert!(matches!(result, Err(ApiError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::ApiError;
+use crate::models::DailyRate;
+use crate::state::AppState;
+use axum::{
+    extract::{
+        State,
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
+    },
+    response::Response,
+};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, broadcast, watch};
+
+/// Stream rate tables over a WebSocket
+/// The latest rates are sent on connect, then every newly stored update; on shutdown
+/// the stream ends with a Going Away close frame so clients reconnect elsewhere
+pub async fn ws_rates_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    // Reserve a connection slot before upgrading; released when the socket closes
+    let permit = state
+        .ws_connections
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| ApiError::TooManyConnections)?;
+
+    // Subscribe before reading the latest rates so no update is missed in between
+    let updates = state.updater.subscribe();
+    let latest = state.store.get_rates().await?;
+
+    let shutdown = state.shutdown.clone();
+
+    Ok(ws.on_upgrade(move |socket| stream_rates(socket, updates, latest, shutdown, permit)))
+}
+
+async fn stream_rates(
+    mut socket: WebSocket,
+    mut updates: broadcast::Receiver<Arc<DailyRate>>,
+    latest: Option<Arc<DailyRate>>,
+    mut shutdown: watch::Receiver<bool>,
+    _permit: OwnedSemaphorePermit,
+) {
+    if let Some(rates) = latest
+        && send_rates(&mut socket, &rates).await.is_err()
+    {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(rates) => {
+                    if send_rates(&mut socket, &rates).await.is_err() {
+                        break;
+                    }
+                }
+                // Slow client: older updates were dropped, the next recv yields the newest
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!("WebSocket client skipped {} rate updates", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // The flag only ever flips to true; a dropped sender also means shutdown
+            _ = shutdown.changed() => {
+                let _ = socket
+                    .send(Message::Close(Some(CloseFrame {
+                        code: close_code::AWAY,
+                        reason: "server shutting down".into(),
+                    })))
+                    .await;
+                break;
+            }
+            message = socket.recv() => match message {
+                // Client closed the connection or it dropped
+                None | Some(Err(_)) | Some(Ok(Message::Close(_))) => break,
+                // Other client messages are ignored; pings are answered automatically
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    tracing::debug!("WebSocket rate stream closed");
+}
+
+async fn send_rates(socket: &mut WebSocket, rates: &DailyRate) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(rates).map_err(axum::Error::new)?;
+    socket.send(Message::Text(json.into())).await
+}
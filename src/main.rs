@@ -1,44 +1,222 @@
+mod check;
+mod cli;
 mod config;
 mod error;
 mod handlers;
+mod middleware;
 mod models;
+mod openapi;
 mod routes;
 mod services;
+mod state;
+mod telemetry;
+#[cfg(unix)]
+mod uds;
 
-use config::Config;
+use clap::Parser;
+use cli::Cli;
+use config::{Config, LogFormat, RateSource};
 use routes::create_router;
-use services::{EcbFetcher, RateScheduler, RedisStore, update_rates};
+use services::{
+    EcbFetcher, EcbProbe, FallbackFetcher, HttpClientOptions, JsonRateFetcher, RateAlerter,
+    RateProvider, RateScheduler, RateUpdater, RedisStore, RetryPolicy, UpdateLock, UpdatePolicy,
+    backfill_90d, backfill_history, backfill_locked,
+};
+use state::AppState;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
+use tokio::sync::{Semaphore, watch};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Longest time shutdown waits for an in-flight rate update before giving up
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
+    let cli = Cli::parse();
+
+    // Load environment variables (before tracing, so .env can set RUST_LOG and LOG_FORMAT)
+    dotenvy::dotenv().ok();
+
+    // Initialize tracing (on stderr for subcommands and --check, keeping stdout for their output)
+    let writer = if cli.command.is_some() || cli.check {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    let (pretty_layer, json_layer) = match LogFormat::from_env()? {
+        LogFormat::Pretty => (
+            Some(tracing_subscriber::fmt::layer().with_writer(writer)),
+            None,
+        ),
+        LogFormat::Json => (
+            None,
+            Some(tracing_subscriber::fmt::layer().json().with_writer(writer)),
+        ),
+    };
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "info,currency_converter_api=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(pretty_layer)
+        .with(json_layer)
         .init();
 
-    // Load environment variables
-    dotenvy::dotenv().ok();
+    // Validate the deployment and exit without serving
+    if cli.check {
+        let report = check::run_checks().await;
+        println!("{}", report);
+        if !report.passed() {
+            return Err("Startup checks failed".into());
+        }
+        return Ok(());
+    }
 
     // Load configuration
     let config = Config::from_env()?;
     tracing::info!("Loaded configuration");
+    // Already validated, so only the scheme is left to check
+    if reqwest::Url::parse(&config.ecb_url).is_ok_and(|url| url.scheme() == "http") {
+        tracing::warn!(
+            "ECB_URL {} is plain http; rates will be fetched without TLS",
+            config.ecb_url
+        );
+    }
+
+    // One-off subcommands only need the rates already in Redis
+    if let Some(command) = cli.command {
+        let store = RedisStore::new(
+            &config.redis_url,
+            &config.redis_prefix,
+            config.history_retention_days,
+            config.redis_ca_cert.as_deref(),
+        )
+        .await?;
+        let output = command.run(&store, &config).await?;
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    // Install Prometheus metrics recorder
+    let metrics = telemetry::install_recorder()?;
 
     // Connect to Redis
-    let store = RedisStore::new(&config.redis_url).await?;
+    let store = RedisStore::new(
+        &config.redis_url,
+        &config.redis_prefix,
+        config.history_retention_days,
+        config.redis_ca_cert.as_deref(),
+    )
+    .await?
+    .with_rates_ttl(config.rates_ttl_seconds)
+    .with_latest_cache_ttl(Duration::from_secs(config.rates_cache_ttl_secs))
+    .with_rate_cache_size(config.rate_cache_size)
+    .with_history_lookback(config.history_lookback_days);
     tracing::info!("Connected to Redis");
 
-    // Create ECB fetcher
-    let fetcher = EcbFetcher::new(config.ecb_url.clone());
+    let available_dates = store.list_available_dates().await?;
+    tracing::info!(
+        "{} historical rate snapshots available",
+        available_dates.len()
+    );
+
+    // Create ECB fetcher, falling back to mirrors when configured
+    let retry_policy = RetryPolicy {
+        max_attempts: config.ecb_retry_attempts.max(1),
+        base_delay: Duration::from_millis(config.ecb_retry_base_delay_ms),
+    };
+    let client_options = HttpClientOptions {
+        timeout: Duration::from_secs(config.ecb_timeout_secs),
+        user_agent: config.ecb_user_agent.clone(),
+        proxy: config.ecb_proxy.clone(),
+        max_redirects: config.ecb_max_redirects,
+    };
+    let fetcher = EcbFetcher::new(config.ecb_url.clone(), retry_policy, &client_options)?
+        .with_min_rates(config.ecb_min_rates);
+
+    // Taken around every fetch from ECB, so replicas don't download the same feed at once
+    let update_lock = (config.update_lock_ttl_secs > 0).then(|| UpdateLock {
+        ttl: Duration::from_secs(config.update_lock_ttl_secs),
+        wait: Duration::from_secs(config.update_lock_wait_secs),
+    });
+
+    // On cold start, seed history from the 90-day feed (non-blocking - log error but continue);
+    // BACKFILL_ON_START re-imports the whole window even when history exists
+    if config.backfill_on_start {
+        tracing::info!(
+            "BACKFILL_ON_START set, refreshing rate history from {}",
+            config.ecb_history_url
+        );
+        let backfill = backfill_90d(
+            &fetcher,
+            &config.ecb_history_url,
+            &store,
+            &config.required_currencies,
+        );
+        if let Err(e) = backfill_locked(&store, update_lock, backfill).await {
+            tracing::warn!("History refresh failed: {}", e);
+        }
+    } else if available_dates.is_empty() {
+        tracing::info!(
+            "No rate history found, backfilling from {}",
+            config.ecb_history_url
+        );
+        let backfill = backfill_history(&fetcher, &config.ecb_history_url, &store);
+        if let Err(e) = backfill_locked(&store, update_lock, backfill).await {
+            tracing::warn!("History backfill failed: {}", e);
+        }
+    }
+
+    // Daily rates come from the ECB feed or a JSON mirror; history always uses the ECB feed
+    let primary: Box<dyn RateProvider> = match config.rate_source {
+        RateSource::Ecb => Box::new(fetcher),
+        RateSource::Json => {
+            tracing::info!("Using JSON rate source: {}", config.json_rates_url);
+            Box::new(
+                JsonRateFetcher::new(config.json_rates_url.clone(), retry_policy, &client_options)?
+                    .with_min_rates(config.ecb_min_rates),
+            )
+        }
+    };
+
+    let provider: Arc<dyn RateProvider> = if config.ecb_fallback_urls.is_empty() {
+        Arc::from(primary)
+    } else {
+        let mut providers: Vec<Box<dyn RateProvider>> = vec![primary];
+        for url in &config.ecb_fallback_urls {
+            providers.push(Box::new(
+                EcbFetcher::new(url.clone(), retry_policy, &client_options)?
+                    .with_min_rates(config.ecb_min_rates),
+            ));
+        }
+        tracing::info!(
+            "Configured {} fallback rate provider(s)",
+            config.ecb_fallback_urls.len()
+        );
+        Arc::new(FallbackFetcher::new(providers))
+    };
+
+    // Shared by the scheduler and the manual refresh endpoint
+    let updater = RateUpdater::new(provider, store.clone()).with_policy(UpdatePolicy {
+        required_currencies: config.required_currencies.clone(),
+        store_raw_xml: config.store_raw_xml,
+        lock: update_lock,
+        warmup_bases: config.warmup_bases.clone(),
+        alert: config
+            .alert_webhook_url
+            .clone()
+            .map(|url| RateAlerter::new(url, config.alert_threshold_pct, &client_options))
+            .transpose()?,
+    });
 
     // Perform initial fetch (non-blocking - log error but continue)
     tracing::info!("Attempting initial fetch of exchange rates...");
-    match update_rates(&fetcher, &store).await {
+    match updater.update().await {
         Ok(_) => {
             tracing::info!("Initial exchange rates loaded successfully");
         }
@@ -48,35 +226,135 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Create and start the scheduler
-    let mut scheduler =
-        RateScheduler::new(config.update_cron.clone(), fetcher, store.clone()).await?;
+    let mut scheduler = RateScheduler::new(config.update_cron.clone(), updater.clone()).await?;
     scheduler.start().await?;
     tracing::info!(
         "Rate update scheduler started with cron: {}",
         config.update_cron
     );
 
+    // Opt-in upstream check for /health
+    let ecb_probe = config
+        .health_check_ecb
+        .then(|| EcbProbe::new(config.ecb_url.clone(), &client_options))
+        .transpose()?;
+
+    // Flipped once a shutdown signal arrives so WebSocket streams can close themselves
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let notify_shutdown = async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    };
+
     // Create router with shared state
-    let app = create_router(store);
+    let state = AppState {
+        store: store.clone(),
+        config: Arc::new(config.clone()),
+        metrics,
+        updater: updater.clone(),
+        ws_connections: Arc::new(Semaphore::new(config.ws_max_connections)),
+        ecb_probe,
+        scheduler: scheduler.liveness(),
+        in_flight: Default::default(),
+        shutdown: shutdown_rx,
+    };
+    let app = create_router(state.clone());
 
-    // Start server
-    let listener = tokio::net::TcpListener::bind(&config.server_address()).await?;
-    let addr = config.server_address();
-    tracing::info!("Server listening on {}", addr);
+    // Start server with graceful shutdown, on BIND_UDS when set and TCP otherwise
+    match &config.bind_uds {
+        #[cfg(unix)]
+        Some(path) => {
+            let (listener, socket) = uds::bind(path)?;
+            tracing::info!("Server listening on unix:{}", path.display());
 
-    // Run server with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+            // Peers have no IP address; rate limiting relies on X-Forwarded-For from the proxy
+            let server = axum::serve(listener, app.into_make_service())
+                .with_graceful_shutdown(notify_shutdown);
+            serve_until_drained(server, &state).await?;
+            drop(socket);
+        }
+        #[cfg(not(unix))]
+        Some(_) => return Err("BIND_UDS is only supported on Unix".into()),
+        None => {
+            let listener = tokio::net::TcpListener::bind(&config.server_address()).await?;
+            tracing::info!("Server listening on {}", config.server_address());
+
+            let server = axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(notify_shutdown);
+            serve_until_drained(server, &state).await?;
+        }
+    }
 
-    // Shutdown scheduler on exit
+    // Stop firing new jobs, let an in-flight update finish, then close Redis
     tracing::info!("Shutting down scheduler...");
     scheduler.shutdown().await?;
+
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, updater.wait_idle())
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "Rate update still running after {:?}, shutting down anyway",
+            SHUTDOWN_TIMEOUT
+        );
+    }
+
+    // Every other holder of a store handle goes first, so closing the store ends the connection
+    drop(scheduler);
+    drop(state);
+    drop(updater);
+    store.close();
     tracing::info!("Server shutdown complete");
 
     Ok(())
 }
 
+/// Run the server until a shutdown signal, then give open requests and WebSocket
+/// streams SHUTDOWN_GRACE_SECS to finish before dropping the rest
+async fn serve_until_drained(
+    server: impl IntoFuture<Output = std::io::Result<()>>,
+    state: &AppState,
+) -> std::io::Result<()> {
+    let server = server.into_future();
+    tokio::pin!(server);
+
+    let mut shutdown = state.shutdown.clone();
+    tokio::select! {
+        result = &mut server => return result,
+        _ = shutdown.wait_for(|&stopping| stopping) => {}
+    }
+
+    // Upgraded WebSocket connections outlive the server future, so also wait
+    // for every stream to hand back its permit
+    let grace = Duration::from_secs(state.config.shutdown_grace_secs);
+    let ws_max = state.config.ws_max_connections;
+    let drained = tokio::time::timeout(grace, async {
+        let result = (&mut server).await;
+        let _ = state
+            .ws_connections
+            .acquire_many(u32::try_from(ws_max).unwrap_or(u32::MAX))
+            .await;
+        result
+    })
+    .await;
+
+    match drained {
+        Ok(result) => result,
+        Err(_) => {
+            tracing::warn!(
+                "{} requests and {} WebSocket streams still open after {:?}, closing them",
+                state.in_flight.count(),
+                ws_max.saturating_sub(state.ws_connections.available_permits()),
+                grace
+            );
+            Ok(())
+        }
+    }
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
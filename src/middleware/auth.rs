@@ -0,0 +1,147 @@
+use crate::error::ApiError;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use subtle::{Choice, ConstantTimeEq};
+
+/// Header carrying the client's API key
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Paths reachable without an API key (service index and probes)
+const PUBLIC_PATHS: &[&str] = &[
+    "/",
+    "/health",
+    "/readiness",
+    "/docs",
+    "/api-docs/openapi.json",
+];
+
+/// Accepted API keys, held as SHA-256 digests
+/// Digests have a fixed length and are compared in constant time against every
+/// configured key, so response timing reveals neither a key's prefix nor its length
+#[derive(Clone)]
+pub struct ApiKeys(Arc<Vec<[u8; 32]>>);
+
+impl ApiKeys {
+    pub fn new(keys: impl IntoIterator<Item = String>) -> Self {
+        Self(Arc::new(keys.into_iter().map(|key| digest(&key)).collect()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        let candidate = digest(key);
+        self.0
+            .iter()
+            .fold(Choice::from(0), |found, known| {
+                found | known.ct_eq(&candidate)
+            })
+            .into()
+    }
+}
+
+fn digest(key: &str) -> [u8; 32] {
+    Sha256::digest(key.as_bytes()).into()
+}
+
+/// Middleware requiring a valid `X-API-Key` header
+/// A no-op when no keys are configured, so auth stays opt-in
+pub async fn api_key_auth(
+    State(keys): State<ApiKeys>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if keys.is_empty() || PUBLIC_PATHS.contains(&request.uri().path()) {
+        return Ok(next.run(request).await);
+    }
+
+    let authorized = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|key| keys.contains(key));
+
+    if !authorized {
+        return Err(ApiError::Unauthorized);
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::StatusCode, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn app(keys: &[&str]) -> Router {
+        let keys = ApiKeys::new(keys.iter().map(|key| key.to_string()));
+
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/api/latest", get(|| async { "rates" }))
+            .layer(middleware::from_fn_with_state(keys, api_key_auth))
+    }
+
+    async fn status(app: Router, path: &str, key: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri(path);
+        if let Some(key) = key {
+            request = request.header(API_KEY_HEADER, key);
+        }
+
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_invalid_key_rejected() {
+        assert_eq!(
+            status(app(&["secret"]), "/api/latest", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(app(&["secret"]), "/api/latest", Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_valid_key_accepted() {
+        assert_eq!(
+            status(app(&["secret", "other"]), "/api/latest", Some("other")).await,
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn test_contains_matches_whole_keys_only() {
+        let keys = ApiKeys::new(["secret".to_string(), "other".to_string()]);
+
+        assert!(keys.contains("secret"));
+        assert!(keys.contains("other"));
+        assert!(!keys.contains("secre"));
+        assert!(!keys.contains("secret2"));
+        assert!(!keys.contains(""));
+    }
+
+    #[tokio::test]
+    async fn test_public_paths_bypass_auth() {
+        assert_eq!(
+            status(app(&["secret"]), "/health", None).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_keys_configured_is_noop() {
+        assert_eq!(status(app(&[]), "/api/latest", None).await, StatusCode::OK);
+    }
+}
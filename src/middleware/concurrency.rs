@@ -0,0 +1,72 @@
+use crate::error::ApiError;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Path left outside the limit so liveness probes keep answering under load
+const EXEMPT_PATH: &str = "/health";
+
+/// Middleware shedding requests beyond the concurrency limit with 503 instead of queueing them
+/// Each request holds a permit until its response is produced
+pub async fn concurrency_limit(
+    State(permits): State<Arc<Semaphore>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path() == EXEMPT_PATH {
+        return next.run(request).await;
+    }
+
+    match permits.try_acquire_owned() {
+        Ok(_permit) => next.run(request).await,
+        Err(_) => {
+            tracing::warn!(
+                "Concurrency limit reached, rejecting {}",
+                request.uri().path()
+            );
+            ApiError::Overloaded.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::StatusCode, middleware, routing::get};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_sheds_requests_over_limit() {
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "done"
+                }),
+            )
+            .route("/health", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(Semaphore::new(1)),
+                concurrency_limit,
+            ));
+        let get = |path: &str| Request::builder().uri(path).body(Body::empty()).unwrap();
+
+        let running = tokio::spawn(app.clone().oneshot(get("/slow")));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let shed = app.clone().oneshot(get("/slow")).await.unwrap();
+        assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let health = app.clone().oneshot(get("/health")).await.unwrap();
+        assert_eq!(health.status(), StatusCode::OK);
+
+        assert_eq!(running.await.unwrap().unwrap().status(), StatusCode::OK);
+        let after = app.oneshot(get("/slow")).await.unwrap();
+        assert_eq!(after.status(), StatusCode::OK);
+    }
+}
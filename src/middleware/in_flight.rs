@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of requests currently being handled, reported when shutdown cuts them off
+#[derive(Debug, Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Decrements the counter however the request ends, including when its future is dropped
+struct InFlightGuard(InFlight);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Middleware counting requests from arrival until their response is produced
+pub async fn track_in_flight(
+    State(in_flight): State<InFlight>,
+    request: Request,
+    next: Next,
+) -> Response {
+    in_flight.0.fetch_add(1, Ordering::Relaxed);
+    let _guard = InFlightGuard(in_flight);
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, middleware, routing::get};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_counts_running_requests() {
+        let in_flight = InFlight::default();
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "done"
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                in_flight.clone(),
+                track_in_flight,
+            ));
+
+        let request = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+        let running = tokio::spawn(app.oneshot(request));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(in_flight.count(), 1);
+
+        running.await.unwrap().unwrap();
+        assert_eq!(in_flight.count(), 0);
+    }
+}
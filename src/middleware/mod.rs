@@ -0,0 +1,15 @@
+pub mod auth;
+pub mod concurrency;
+pub mod in_flight;
+pub mod metrics;
+pub mod rate_limit;
+pub mod request_id;
+pub mod timeout;
+
+pub use auth::*;
+pub use concurrency::*;
+pub use in_flight::*;
+pub use metrics::*;
+pub use rate_limit::*;
+pub use request_id::*;
+pub use timeout::*;
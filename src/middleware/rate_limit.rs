@@ -0,0 +1,265 @@
+use crate::error::ApiError;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use lru::LruCache;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A bucket left alone this long has refilled to capacity, so dropping it is lossless
+const IDLE_BUCKET: Duration = Duration::from_secs(60);
+
+/// Hard cap on tracked clients; beyond it the least recently seen bucket is evicted
+const MAX_TRACKED_CLIENTS: NonZeroUsize = NonZeroUsize::new(100_000).unwrap();
+
+/// Bucket shared by requests whose client cannot be identified (e.g. over BIND_UDS
+/// without X-Forwarded-For), so they are limited together rather than not at all
+const UNKNOWN_CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token-bucket rate limiter keyed by client IP
+///
+/// State lives in memory and is per process: with several replicas behind a
+/// load balancer, each replica enforces the limit independently.
+#[derive(Clone)]
+pub struct RateLimiter {
+    /// Ordered by last use, so idle and evicted clients come off the cold end in O(1)
+    buckets: Arc<Mutex<LruCache<IpAddr, Bucket>>>,
+    capacity: f64,
+    refill_per_sec: f64,
+    trusted_proxy_hops: usize,
+}
+
+impl RateLimiter {
+    /// Allow `requests_per_minute` per client, with bursts up to the same amount
+    pub fn new(requests_per_minute: u32) -> Self {
+        Self::with_max_clients(requests_per_minute, MAX_TRACKED_CLIENTS)
+    }
+
+    fn with_max_clients(requests_per_minute: u32, max_clients: NonZeroUsize) -> Self {
+        let capacity = requests_per_minute as f64;
+
+        Self {
+            buckets: Arc::new(Mutex::new(LruCache::new(max_clients))),
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            trusted_proxy_hops: 0,
+        }
+    }
+
+    /// Trust the last `hops` X-Forwarded-For entries, appended by our own reverse proxies
+    /// With 0 the header is ignored and the peer address identifies the client
+    pub fn with_trusted_proxy_hops(mut self, hops: usize) -> Self {
+        self.trusted_proxy_hops = hops;
+        self
+    }
+
+    /// Take a token for the client, or return how long to wait before retrying
+    pub fn check(&self, client: IpAddr) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+
+        // Drop idle buckets from the cold end; each is removed once, so this is amortised O(1)
+        while let Some((_, bucket)) = buckets.peek_lru()
+            && now.duration_since(bucket.updated) >= IDLE_BUCKET
+        {
+            buckets.pop_lru();
+        }
+
+        // Still full of active clients: inserting evicts the least recently seen one
+        let bucket = buckets.get_or_insert_mut(client, || Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+
+        // Refill tokens for the elapsed time
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(missing / self.refill_per_sec))
+        }
+    }
+}
+
+/// Middleware rejecting clients that exceed their rate limit with 429
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let client = client_ip(&request, limiter.trusted_proxy_hops).unwrap_or(UNKNOWN_CLIENT);
+    limiter
+        .check(client)
+        .map_err(|retry_after| ApiError::RateLimited(retry_after.as_secs().max(1)))?;
+
+    Ok(next.run(request).await)
+}
+
+/// Resolve the client IP
+/// Each trusted proxy appends the address it received the request from, so the
+/// client is the `trusted_hops`-th entry from the right; entries left of it are
+/// whatever the client sent and are never used. Without trusted proxies, or when
+/// the header is missing, the peer address is used
+fn client_ip(request: &Request, trusted_hops: usize) -> Option<IpAddr> {
+    let forwarded = (trusted_hops > 0)
+        .then(|| request.headers().get("x-forwarded-for"))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            let hops: Vec<&str> = value.split(',').collect();
+            // A shorter chain than configured means the first proxy started it
+            hops.len()
+                .checked_sub(trusted_hops)
+                .map_or(hops.first(), |index| hops.get(index))
+                .and_then(|ip| ip.trim().parse().ok())
+        });
+
+    forwarded.or_else(|| {
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    fn client() -> IpAddr {
+        "203.0.113.7".parse().unwrap()
+    }
+
+    #[test]
+    fn test_allows_burst_up_to_limit() {
+        let limiter = RateLimiter::new(3);
+        let now = Instant::now();
+
+        assert!(limiter.check_at(client(), now).is_ok());
+        assert!(limiter.check_at(client(), now).is_ok());
+        assert!(limiter.check_at(client(), now).is_ok());
+        assert!(limiter.check_at(client(), now).is_err());
+    }
+
+    #[test]
+    fn test_retry_after_and_refill() {
+        // 60/min refills one token per second
+        let limiter = RateLimiter::new(60);
+        let now = Instant::now();
+
+        for _ in 0..60 {
+            limiter.check_at(client(), now).unwrap();
+        }
+
+        let retry_after = limiter.check_at(client(), now).unwrap_err();
+        assert_eq!(retry_after.as_secs(), 1);
+
+        assert!(
+            limiter
+                .check_at(client(), now + Duration::from_secs(1))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_clients_limited_independently() {
+        let limiter = RateLimiter::new(1);
+        let now = Instant::now();
+        let other: IpAddr = "198.51.100.1".parse().unwrap();
+
+        assert!(limiter.check_at(client(), now).is_ok());
+        assert!(limiter.check_at(client(), now).is_err());
+        assert!(limiter.check_at(other, now).is_ok());
+    }
+
+    fn forwarded(chain: &str) -> Request {
+        let mut request = Request::builder()
+            .header("x-forwarded-for", chain)
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 9], 443))));
+        request
+    }
+
+    #[test]
+    fn test_client_ip_uses_trusted_hop() {
+        // The client spoofed the first entry; our single proxy appended the real address
+        let request = forwarded("192.0.2.1, 203.0.113.7");
+        assert_eq!(client_ip(&request, 1), Some(client()));
+
+        // Two proxies: the last entry is the first proxy's address
+        let request = forwarded("192.0.2.1, 203.0.113.7, 10.0.0.1");
+        assert_eq!(client_ip(&request, 2), Some(client()));
+
+        // A chain shorter than configured was started by the first proxy
+        assert_eq!(client_ip(&forwarded("203.0.113.7"), 2), Some(client()));
+    }
+
+    #[test]
+    fn test_client_ip_ignores_forwarded_for_without_trusted_proxies() {
+        let request = forwarded("203.0.113.7");
+        assert_eq!(client_ip(&request, 0), "10.0.0.9".parse().ok());
+
+        let bare = Request::builder().body(Body::empty()).unwrap();
+        assert_eq!(client_ip(&bare, 1), None);
+    }
+
+    #[test]
+    fn test_tracked_clients_are_capped() {
+        let limiter = RateLimiter::with_max_clients(1, NonZeroUsize::new(3).unwrap());
+        let now = Instant::now();
+
+        for i in 0..5u32 {
+            let ip = IpAddr::from(i.to_be_bytes());
+            limiter
+                .check_at(ip, now + Duration::from_millis(i.into()))
+                .unwrap();
+        }
+
+        // Only the most recently seen clients are still tracked
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), 3);
+        assert!(buckets.contains(&IpAddr::from(4u32.to_be_bytes())));
+        assert!(!buckets.contains(&IpAddr::from(0u32.to_be_bytes())));
+    }
+
+    #[test]
+    fn test_idle_clients_are_pruned() {
+        let limiter = RateLimiter::new(1);
+        let now = Instant::now();
+        let other: IpAddr = "198.51.100.1".parse().unwrap();
+
+        limiter.check_at(client(), now).unwrap();
+        limiter
+            .check_at(other, now + Duration::from_secs(30))
+            .unwrap();
+        limiter
+            .check_at(other, now + IDLE_BUCKET + Duration::from_secs(1))
+            .unwrap_err();
+
+        // The first client has refilled, the second was seen 31s ago and is kept
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), 1);
+        assert!(buckets.contains(&other));
+    }
+}
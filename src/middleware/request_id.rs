@@ -0,0 +1,71 @@
+use axum::{extract::Request, http::HeaderName};
+use tracing::Span;
+
+/// Header carrying the per-request correlation ID
+/// A client-supplied value is kept, otherwise a UUID v4 is generated
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Root tracing span for a request, tagged with its ID so all of its log lines correlate
+pub fn request_span(request: &Request) -> Span {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("-");
+
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = %request_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, routing::get};
+    use tower::ServiceExt;
+    use tower_http::{
+        request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+        trace::TraceLayer,
+    };
+
+    fn app() -> Router {
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .layer(TraceLayer::new_for_http().make_span_with(request_span))
+            .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+            .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+    }
+
+    async fn response_id(request_id: Option<&str>) -> String {
+        let mut request = Request::builder().uri("/health");
+        if let Some(request_id) = request_id {
+            request = request.header(&REQUEST_ID_HEADER, request_id);
+        }
+
+        let response = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        response.headers()[&REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_request_id_generated() {
+        let request_id = response_id(None).await;
+
+        assert_eq!(request_id.len(), 36);
+        assert_eq!(request_id.matches('-').count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_client_request_id_echoed() {
+        assert_eq!(response_id(Some("abc-123")).await, "abc-123");
+    }
+}
@@ -0,0 +1,57 @@
+use crate::error::ApiError;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+
+/// Middleware aborting requests whose handler runs longer than the given limit
+/// The handler future is dropped on expiry and the client gets a 504
+pub async fn request_timeout(
+    State(limit): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match tokio::time::timeout(limit, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("Request aborted after {}ms", limit.as_millis());
+            ApiError::Timeout.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::StatusCode, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn app(limit: Duration) -> Router {
+        Router::new()
+            .route("/fast", get(|| async { "ok" }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    "late"
+                }),
+            )
+            .layer(middleware::from_fn_with_state(limit, request_timeout))
+    }
+
+    async fn status(path: &str) -> StatusCode {
+        app(Duration::from_millis(50))
+            .oneshot(Request::builder().uri(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_times_out() {
+        assert_eq!(status("/fast").await, StatusCode::OK);
+        assert_eq!(status("/slow").await, StatusCode::GATEWAY_TIMEOUT);
+    }
+}
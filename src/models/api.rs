@@ -1,63 +1,1257 @@
-use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::models::{DailyRate, currency_meta, is_iso_currency};
+use chrono::NaiveDate;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Deserializer, Serialize, de};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::str::FromStr;
-use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+/// Reject well-formed codes that are not ISO 4217 currencies
+/// Codes with the wrong length are left to the `length` validator
+fn validate_currency_code(code: &str) -> Result<(), ValidationError> {
+    if code.len() != 3 || is_iso_currency(code) {
+        return Ok(());
+    }
+
+    Err(
+        ValidationError::new("currency_code").with_message(Cow::Owned(format!(
+            "Unknown currency code '{}'",
+            code.to_uppercase()
+        ))),
+    )
+}
+
+/// Accept only the conversion directions /api/convert understands
+fn validate_convert_mode(mode: &str) -> Result<(), ValidationError> {
+    if mode.eq_ignore_ascii_case("forward") || mode.eq_ignore_ascii_case("reverse") {
+        return Ok(());
+    }
+
+    Err(
+        ValidationError::new("mode").with_message(Cow::Owned(format!(
+            "Unknown mode '{}', expected forward or reverse",
+            mode
+        ))),
+    )
+}
+
+/// Accept only the rounding modes RoundingMode knows
+fn validate_rounding(rounding: &str) -> Result<(), ValidationError> {
+    if RoundingMode::parse(rounding).is_some() {
+        return Ok(());
+    }
+
+    Err(
+        ValidationError::new("rounding").with_message(Cow::Owned(format!(
+            "Unknown rounding '{}', expected half_up, half_even or down",
+            rounding
+        ))),
+    )
+}
+
+/// How a rounded conversion result treats digits past the requested precision
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Midpoints away from zero (2.5 -> 3)
+    #[default]
+    HalfUp,
+    /// Banker's rounding, midpoints to the even neighbour (2.5 -> 2, 3.5 -> 4)
+    HalfEven,
+    /// Truncate toward zero (2.9 -> 2)
+    Down,
+}
+
+impl RoundingMode {
+    /// Parse `half_up`, `half_even` or `down` (case-insensitive)
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode.to_ascii_lowercase().as_str() {
+            "half_up" => Some(RoundingMode::HalfUp),
+            "half_even" => Some(RoundingMode::HalfEven),
+            "down" => Some(RoundingMode::Down),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoundingMode::HalfUp => "half_up",
+            RoundingMode::HalfEven => "half_even",
+            RoundingMode::Down => "down",
+        }
+    }
+
+    pub fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+        }
+    }
+}
+
+/// Only `previous` (the prior business day) can be compared against
+fn validate_compare(compare: &str) -> Result<(), ValidationError> {
+    if compare.eq_ignore_ascii_case("previous") {
+        return Ok(());
+    }
+
+    Err(
+        ValidationError::new("compare").with_message(Cow::Owned(format!(
+            "Unknown compare '{}', expected previous",
+            compare
+        ))),
+    )
+}
+
+/// Error body returned by every failing endpoint
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// Stable identifier to switch on, e.g. `CURRENCY_NOT_FOUND`
+    #[schema(example = "CURRENCY_NOT_FOUND")]
+    pub code: String,
+    /// Currencies in the rate table, only on `CURRENCY_NOT_FOUND` from conversions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available: Option<Vec<String>>,
+    /// Every rejected field, only on `VALIDATION_ERROR` from parameter validation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<FieldError>>,
+}
+
+/// One invalid field of a request, e.g. `{"field": "from", "message": "..."}`
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Parameter name; nested entries use paths such as `conversions[1].to`
+    pub field: String,
+    pub message: String,
+}
+
+/// Flatten validator errors into one entry per failed rule, sorted by field
+pub fn field_errors(errors: &ValidationErrors) -> Vec<FieldError> {
+    fn collect(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+        for (name, kind) in errors.errors() {
+            let path = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", prefix, name)
+            };
+            match kind {
+                ValidationErrorsKind::Field(failures) => {
+                    out.extend(failures.iter().map(|failure| FieldError {
+                        field: path.clone(),
+                        message: failure.to_string(),
+                    }));
+                }
+                ValidationErrorsKind::Struct(nested) => collect(&path, nested, out),
+                ValidationErrorsKind::List(items) => {
+                    for (index, nested) in items {
+                        collect(&format!("{}[{}]", path, index), nested, out);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    collect("", errors, &mut out);
+    out.sort_by(|a, b| a.field.cmp(&b.field));
+    out
+}
 
 /// Response for GET /api/latest
+// Borrows the rate table so cached rates are serialized without copying them
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LatestRatesResponse<'a> {
+    pub date: &'a str,
+    pub base: &'a str,
+    /// Rates in the table before `limit`/`offset`, only on paginated requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    pub rates: &'a HashMap<String, Decimal>,
+}
+
+impl<'a> From<&'a DailyRate> for LatestRatesResponse<'a> {
+    fn from(rates: &'a DailyRate) -> Self {
+        Self {
+            date: &rates.date,
+            base: &rates.base,
+            total: None,
+            rates: &rates.rates,
+        }
+    }
+}
+
+/// A rate with its change since the previous business day
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ComparedRate {
+    pub rate: Decimal,
+    /// Rate in the previous snapshot, omitted when the currency has none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Decimal>,
+    /// Percentage change from `previous` to `rate`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_pct: Option<Decimal>,
+}
+
+/// Response for GET /api/latest?compare=previous
 #[derive(Debug, Serialize)]
-pub struct LatestRatesResponse {
-    pub date: String,
-    pub base: String,
-    pub rates: HashMap<String, Decimal>,
+pub struct ComparedRatesResponse<'a> {
+    pub date: &'a str,
+    pub base: &'a str,
+    /// Date of the snapshot compared against, omitted when none is stored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_date: Option<&'a str>,
+    /// Rates in the table before `limit`/`offset`, only on paginated requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    pub rates: &'a BTreeMap<String, ComparedRate>,
 }
 
 /// Query parameters for GET /api/latest?base=USD
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LatestRatesQuery {
-    #[validate(length(equal = 3))]
+    /// ISO 4217 code to rebase the rates on, the stored table's base (EUR for ECB) when omitted
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    #[param(min_length = 3, max_length = 3, example = "USD")]
     pub base: Option<String>,
+    /// Comma-separated currency codes to return, all when omitted
+    #[param(example = "GBP,JPY")]
+    pub symbols: Option<String>,
+    /// Decimal places to round each rate to, full precision when omitted
+    #[validate(range(max = 28))]
+    #[param(maximum = 28, example = 6)]
+    pub precision: Option<u32>,
+    /// `previous` adds each currency's rate on the prior business day and the change since
+    #[validate(custom(function = "validate_compare"))]
+    #[param(example = "previous")]
+    pub compare: Option<String>,
+    /// Largest number of rates to return, in currency code order; all when omitted
+    #[validate(range(min = 1))]
+    #[param(minimum = 1, example = 50)]
+    pub limit: Option<usize>,
+    /// Rates to skip, in currency code order, before applying `limit`
+    #[param(example = 0)]
+    pub offset: Option<usize>,
 }
 
-/// Query parameters for GET /api/convert
+impl LatestRatesQuery {
+    /// Map an aliased `base` and `symbols` to canonical codes (call before validation)
+    pub fn resolve_aliases(&mut self, aliases: &HashMap<String, String>) {
+        if let Some(base) = &mut self.base {
+            resolve_alias(base, aliases);
+        }
+        if let Some(symbols) = &mut self.symbols {
+            resolve_symbol_aliases(symbols, aliases);
+        }
+    }
+}
+
+/// Query parameters for GET /api/timeseries
 #[derive(Debug, Deserialize, Validate)]
+pub struct TimeseriesQuery {
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    pub base: Option<String>,
+    pub symbols: Option<String>, // Comma-separated currency codes, all when omitted
+    pub start_date: String,
+    pub end_date: String,
+}
+
+/// Response for GET /api/timeseries
+#[derive(Debug, Serialize)]
+pub struct TimeseriesResponse {
+    pub base: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub rates: BTreeMap<String, HashMap<String, Decimal>>,
+}
+
+/// Query parameters for GET /api/change
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangeQuery {
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    pub base: Option<String>,
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    pub symbol: String,
+    pub start: String,
+    pub end: String,
+}
+
+/// Response for GET /api/change
+#[derive(Debug, Serialize)]
+pub struct ChangeResponse {
+    pub base: String,
+    pub symbol: String,
+    /// Requested dates
+    pub start: String,
+    pub end: String,
+    /// Dates of the snapshots actually used (on or before the requested ones)
+    pub start_rate_date: String,
+    pub end_rate_date: String,
+    pub start_rate: Decimal,
+    pub end_rate: Decimal,
+    /// `end_rate - start_rate`
+    pub change: Decimal,
+    /// `change / start_rate * 100`
+    pub change_percent: Decimal,
+}
+
+/// Replace a CURRENCY_ALIASES alias (case-insensitive, e.g. `euro`) with its canonical code
+/// Anything else is left as is for the regular validation and not-found handling
+pub fn resolve_alias(code: &mut String, aliases: &HashMap<String, String>) {
+    if let Some(canonical) = aliases.get(&code.trim().to_uppercase()) {
+        *code = canonical.clone();
+    }
+}
+
+/// Resolve aliases in a comma-separated `symbols` list, before parse_symbols validates it
+pub fn resolve_symbol_aliases(symbols: &mut String, aliases: &HashMap<String, String>) {
+    if aliases.is_empty() {
+        return;
+    }
+
+    *symbols = symbols
+        .split(',')
+        .map(|symbol| {
+            let mut symbol = symbol.to_string();
+            resolve_alias(&mut symbol, aliases);
+            symbol
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+}
+
+/// Parse a comma-separated list of currency codes (e.g. "USD,GBP")
+pub fn parse_symbols(symbols: &str) -> Result<Vec<String>, String> {
+    symbols
+        .split(',')
+        .map(|symbol| {
+            let symbol = symbol.trim().to_uppercase();
+            if symbol.len() == 3 && symbol.chars().all(|c| c.is_ascii_alphabetic()) {
+                Ok(symbol)
+            } else {
+                Err(format!("Invalid currency code '{}' in symbols", symbol))
+            }
+        })
+        .collect()
+}
+
+/// Deserialize an amount given as a string (query strings, JSON) or a JSON number
+/// Numbers are kept as their decimal text so `parse_amount` handles both the same way
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct AmountVisitor;
+
+    impl<'de> de::Visitor<'de> for AmountVisitor {
+        type Value = String;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an amount as a string or number")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<String, E> {
+            if !value.is_finite() {
+                return Err(E::custom("amount must be a finite number"));
+            }
+            Ok(value.to_string())
+        }
+
+        // serde_json's arbitrary_precision hands JSON numbers over as a single-entry map
+        fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<String, A::Error> {
+            serde_json::Number::deserialize(de::value::MapAccessDeserializer::new(map))
+                .map(|number| number.to_string())
+        }
+    }
+
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+/// `string_or_number` for optional fields; pair with `#[serde(default)]`
+fn optional_string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    string_or_number(deserializer).map(Some)
+}
+
+/// Largest accepted conversion amount unless MAX_CONVERT_AMOUNT overrides it (1e15)
+pub const DEFAULT_MAX_CONVERT_AMOUNT: Decimal = dec!(1_000_000_000_000_000);
+
+/// Parameters for /api/convert (query string for GET, JSON body for POST)
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ConvertQuery {
-    #[validate(length(equal = 3))]
+    /// Source ISO 4217 currency code
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    #[param(min_length = 3, max_length = 3, example = "USD")]
     pub from: String,
-    #[validate(length(equal = 3))]
+    /// Target ISO 4217 currency code
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    #[param(min_length = 3, max_length = 3, example = "EUR")]
     pub to: String,
-    pub amount: String, // Accept as string to parse as Decimal for precision
+    /// Non-negative decimal amount, kept as text (string or JSON number) for precision
+    #[serde(deserialize_with = "string_or_number")]
+    #[param(example = "100.50")]
+    pub amount: String,
+    /// Historical rate date (YYYY-MM-DD), latest when omitted
+    /// Served from the most recent snapshot on or before this date
+    #[param(format = Date)]
+    pub date: Option<String>,
+    /// Decimal places for result, target's ISO minor unit when omitted
+    #[validate(range(max = 28))]
+    #[param(maximum = 28)]
+    pub round: Option<u32>,
+    /// Include the triangulation path in the response
+    pub explain: Option<bool>,
+    /// `forward` (default) converts `amount` of `from` into `to`; `reverse` treats
+    /// `amount` as the `to` amount wanted and returns the `from` amount required
+    #[validate(custom(function = "validate_convert_mode"))]
+    #[param(example = "reverse")]
+    pub mode: Option<String>,
+    /// Retail spread in basis points, DEFAULT_SPREAD_BPS when omitted
+    #[validate(range(max = 9999))]
+    #[param(maximum = 9999)]
+    pub spread_bps: Option<u32>,
+    /// Add a display string (`formatted_result`) alongside the numeric result
+    pub format: Option<bool>,
+    /// Locale for `formatted_result` (e.g. de-DE), en-US when omitted; implies `format=true`
+    #[param(example = "de-DE")]
+    pub locale: Option<String>,
+    /// Percentage (0-100) of `result` to return as `percent_amount`, e.g. a tax or fee rate
+    #[serde(default, deserialize_with = "optional_string_or_number")]
+    #[param(example = "10")]
+    pub percent: Option<String>,
+    /// How `result` is rounded: `half_up` (default), `half_even` or `down`
+    #[validate(custom(function = "validate_rounding"))]
+    #[param(example = "half_even")]
+    pub rounding: Option<String>,
+    /// Triangulate through this currency instead of the table's base (e.g. USD)
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    #[param(min_length = 3, max_length = 3, example = "USD")]
+    pub via: Option<String>,
+    /// Add `result_minor_units`, the result as an integer count of minor units (e.g. cents)
+    pub minor_units: Option<bool>,
+}
+
+/// Why an amount was rejected by `ConvertQuery::parse_amount`
+#[derive(Debug, PartialEq, Eq)]
+pub enum AmountError {
+    /// Not a decimal number
+    Malformed(String),
+    /// A valid number outside the accepted range
+    OutOfRange(String),
+}
+
+impl AmountError {
+    /// Prefix the message with what the amount belongs to, e.g. "USD holding"
+    pub fn context(self, what: &str) -> Self {
+        match self {
+            AmountError::Malformed(msg) => AmountError::Malformed(format!("{}: {}", what, msg)),
+            AmountError::OutOfRange(msg) => AmountError::OutOfRange(format!("{}: {}", what, msg)),
+        }
+    }
+}
+
+/// Thousands separator stripped from amounts unless AMOUNT_GROUPING_SEPARATOR overrides it
+pub const DEFAULT_AMOUNT_GROUPING: char = ',';
+
+/// Parse an amount string to Decimal with validation
+/// Surrounding whitespace is ignored, and `grouping` separators (e.g. `1,234.56`) are
+/// stripped when they split the integer part into groups of three
+/// Amounts above `max` are rejected so cross rates cannot overflow
+pub fn parse_amount(
+    text: &str,
+    max: Decimal,
+    grouping: Option<char>,
+) -> Result<Decimal, AmountError> {
+    let text = text.trim();
+    let digits = match grouping {
+        Some(separator) if text.contains(separator) => strip_grouping(text, separator)?,
+        _ => text.to_string(),
+    };
+
+    let amount = Decimal::from_str(&digits)
+        .map_err(|e| AmountError::Malformed(format!("Invalid amount format '{}': {}", text, e)))?;
+
+    if amount < Decimal::ZERO {
+        return Err(AmountError::OutOfRange(
+            "Amount must be non-negative".to_string(),
+        ));
+    }
+
+    if amount > max {
+        return Err(AmountError::OutOfRange(format!(
+            "Amount must not exceed {}",
+            max
+        )));
+    }
+
+    Ok(amount)
+}
+
+/// Remove thousands separators from `text`, which must split its integer part into groups of
+/// three after a leading group of one to three digits ("1,234,567.5"); misplaced separators
+/// ("1,23", "1,,000", "0.000,1") are rejected rather than guessed at
+fn strip_grouping(text: &str, separator: char) -> Result<String, AmountError> {
+    let (integer, fraction) = text.split_at(text.find('.').unwrap_or(text.len()));
+    let integer = integer.strip_prefix(['-', '+']).unwrap_or(integer);
+
+    let mut groups = integer.split(separator);
+    let leading = groups.next().unwrap_or_default();
+    let well_formed = (1..=3).contains(&leading.len())
+        && groups.all(|group| group.len() == 3)
+        && !fraction.contains(separator);
+
+    if !well_formed {
+        return Err(AmountError::Malformed(format!(
+            "Invalid amount format '{}': misplaced thousands separator '{}'",
+            text, separator
+        )));
+    }
+
+    Ok(text.replace(separator, ""))
+}
+
+impl fmt::Display for AmountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AmountError::Malformed(msg) | AmountError::OutOfRange(msg) => f.write_str(msg),
+        }
+    }
 }
 
 impl ConvertQuery {
-    /// Parse amount string to Decimal with validation
-    pub fn parse_amount(&self) -> Result<Decimal, String> {
-        let amount =
-            Decimal::from_str(&self.amount).map_err(|e| format!("Invalid amount format: {}", e))?;
+    /// Map aliased `from`/`to` codes to their canonical codes (call before validation)
+    pub fn resolve_aliases(&mut self, aliases: &HashMap<String, String>) {
+        resolve_alias(&mut self.from, aliases);
+        resolve_alias(&mut self.to, aliases);
+        if let Some(via) = &mut self.via {
+            resolve_alias(via, aliases);
+        }
+    }
+
+    /// Parse amount string to Decimal with validation, see `parse_amount`
+    pub fn parse_amount(
+        &self,
+        max: Decimal,
+        grouping: Option<char>,
+    ) -> Result<Decimal, AmountError> {
+        parse_amount(&self.amount, max, grouping)
+    }
 
-        if amount < Decimal::ZERO {
-            return Err("Amount must be non-negative".to_string());
+    /// Whether `mode=reverse` was requested (call after validation)
+    pub fn is_reverse(&self) -> bool {
+        self.mode
+            .as_deref()
+            .is_some_and(|mode| mode.eq_ignore_ascii_case("reverse"))
+    }
+
+    /// Currency the result is expressed in: `to`, or `from` in reverse mode
+    pub fn result_currency(&self) -> &str {
+        if self.is_reverse() {
+            &self.from
+        } else {
+            &self.to
         }
+    }
+
+    /// Requested rounding mode, half-up when omitted (call after validation)
+    pub fn rounding_mode(&self) -> RoundingMode {
+        self.rounding
+            .as_deref()
+            .and_then(RoundingMode::parse)
+            .unwrap_or_default()
+    }
+
+    /// Decimal places to round the result to: the explicit `round` parameter,
+    /// otherwise the result currency's ISO 4217 minor unit (None if unknown)
+    pub fn round_dp(&self) -> Option<u32> {
+        self.round
+            .or_else(|| currency_meta(self.result_currency()).map(|meta| meta.minor_units))
+    }
+
+    /// Parse the optional `percent`, which must lie within 0-100
+    pub fn parse_percent(&self) -> Result<Option<Decimal>, String> {
+        self.percent
+            .as_deref()
+            .map(|text| {
+                let percent = Decimal::from_str(text)
+                    .map_err(|e| format!("Invalid percent '{}': {}", text, e))?;
+                if percent < Decimal::ZERO || percent > Decimal::ONE_HUNDRED {
+                    return Err(format!("percent must be between 0 and 100, got {}", text));
+                }
+                Ok(percent)
+            })
+            .transpose()
+    }
 
-        Ok(amount)
+    /// Parse optional historical date (YYYY-MM-DD)
+    pub fn parse_date(&self) -> Result<Option<NaiveDate>, String> {
+        self.date
+            .as_deref()
+            .map(|date| {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid date '{}', expected YYYY-MM-DD: {}", date, e))
+            })
+            .transpose()
     }
 }
 
-/// Response for GET /api/convert
-#[derive(Debug, Serialize)]
+/// Query parameters for /api/rate
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RateQuery {
+    /// Source ISO 4217 currency code
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    #[param(min_length = 3, max_length = 3, example = "USD")]
+    pub from: String,
+    /// Target ISO 4217 currency code
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    #[param(min_length = 3, max_length = 3, example = "JPY")]
+    pub to: String,
+}
+
+impl RateQuery {
+    /// Map aliased `from`/`to` codes to their canonical codes (call before validation)
+    pub fn resolve_aliases(&mut self, aliases: &HashMap<String, String>) {
+        resolve_alias(&mut self.from, aliases);
+        resolve_alias(&mut self.to, aliases);
+    }
+}
+
+/// Response for GET /api/rate
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RateResponse {
+    /// Market rate from `from` to `to`, full precision and without spread
+    pub rate: Decimal,
+    /// Rate date (YYYY-MM-DD)
+    pub date: String,
+}
+
+/// Response for GET and POST /api/convert
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ConvertResponse {
     pub from: String,
     pub to: String,
     pub amount: Decimal,
     pub result: Decimal,
+    /// `forward` or `reverse`; in reverse mode `amount` is in `to` and `result` in `from`
+    pub mode: String,
+    /// Rate applied to produce `result` (equals `effective_rate`)
     pub rate: Decimal,
+    /// Market cross rate before any spread
+    pub base_rate: Decimal,
+    /// `base_rate` after the spread is taken
+    pub effective_rate: Decimal,
+    /// Spread applied, in basis points
+    pub spread_bps: u32,
+    /// Rate for the reverse direction (`to` -> `from`), i.e. `1 / rate`
+    pub inverse_rate: Decimal,
     pub date: String,
+    pub rounded: bool,
+    /// Rounding applied to `result` (`half_up`, `half_even`, `down`, or `up` in
+    /// reverse mode); only when `rounded` is true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounding_mode: Option<String>,
+    /// `result` for display (e.g. "$103.50", "103,50 €"), only when `format` or `locale` is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_result: Option<String>,
+    /// `percent` of `result`, rounded like `result`; only when `percent` is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent_amount: Option<Decimal>,
+    /// `result` in the result currency's ISO 4217 minor units (e.g. cents), only with `minor_units=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_minor_units: Option<i64>,
+    /// Currency the rate was triangulated through, only when `via` is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+    /// Triangulation legs, only when `explain=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Vec<String>>,
+    /// Rate of the leg into the base currency, only for explained cross conversions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intermediate_rate: Option<Decimal>,
+    /// Date asked for via `date`, only for historical requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_date: Option<String>,
+    /// Date of the snapshot actually used, which precedes `requested_date`
+    /// when it fell on a weekend or holiday; only for historical requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_date: Option<String>,
 }
 
-/// Response for GET /health
+/// Holding amount in a portfolio, given as a string or JSON number
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HoldingAmount(#[serde(deserialize_with = "string_or_number")] pub String);
+
+/// Every holding must be keyed by a distinct ISO 4217 code
+fn validate_holding_codes(
+    holdings: &BTreeMap<String, HoldingAmount>,
+) -> Result<(), ValidationError> {
+    let mut seen = Vec::with_capacity(holdings.len());
+
+    for code in holdings.keys() {
+        let upper = code.to_uppercase();
+        if upper.len() != 3 || !upper.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(
+                ValidationError::new("currency_code").with_message(Cow::Owned(format!(
+                    "Invalid currency code '{}' in holdings",
+                    code
+                ))),
+            );
+        }
+        validate_currency_code(code)?;
+
+        // "usd" and "USD" are the same holding
+        if seen.contains(&upper) {
+            return Err(ValidationError::new("duplicate")
+                .with_message(Cow::Owned(format!("Duplicate holding for '{}'", upper))));
+        }
+        seen.push(upper);
+    }
+
+    Ok(())
+}
+
+/// Request body for POST /api/portfolio
+#[derive(Debug, Deserialize, Validate)]
+pub struct PortfolioRequest {
+    /// Amount held per currency code
+    #[validate(custom(function = "validate_holding_codes"))]
+    pub holdings: BTreeMap<String, HoldingAmount>,
+    /// Currency the portfolio is valued in
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    pub target: String,
+}
+
+/// One holding of a valued portfolio
+#[derive(Debug, Serialize)]
+pub struct PortfolioLine {
+    pub currency: String,
+    pub amount: Decimal,
+    /// Market rate from `currency` to the target, full precision
+    pub rate: Decimal,
+    /// `amount` in the target currency, rounded to its minor unit
+    pub value: Decimal,
+    /// Share of the portfolio total in percent, two decimal places
+    pub percent: Decimal,
+}
+
+/// Response for POST /api/portfolio
+#[derive(Debug, Serialize)]
+pub struct PortfolioResponse {
+    pub target: String,
+    pub date: String,
+    pub holdings: Vec<PortfolioLine>,
+    /// Sum of the rounded line values
+    pub total: Decimal,
+}
+
+/// Request body for POST /api/convert/batch
+#[derive(Debug, Deserialize)]
+pub struct BatchConvertRequest {
+    pub conversions: Vec<ConvertQuery>,
+}
+
+/// Query parameters for GET /api/convert/all
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConvertAllQuery {
+    /// Source ISO 4217 currency code
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    pub from: String,
+    /// Non-negative decimal amount, parsed like `ConvertQuery::amount`
+    pub amount: String,
+    /// Comma-separated target codes, every currency in the table when omitted
+    pub symbols: Option<String>,
+}
+
+impl ConvertAllQuery {
+    /// Map an aliased `from` and `symbols` to canonical codes (call before validation)
+    pub fn resolve_aliases(&mut self, aliases: &HashMap<String, String>) {
+        resolve_alias(&mut self.from, aliases);
+        if let Some(symbols) = &mut self.symbols {
+            resolve_symbol_aliases(symbols, aliases);
+        }
+    }
+}
+
+/// One target of GET /api/convert/all
+#[derive(Debug, Serialize)]
+pub struct ConvertedAmount {
+    /// Rounded to the target's ISO 4217 minor unit
+    pub result: Decimal,
+    /// Full-precision market rate from `from` to the target
+    pub rate: Decimal,
+}
+
+/// Response for GET /api/convert/all
+#[derive(Debug, Serialize)]
+pub struct ConvertAllResponse {
+    pub from: String,
+    pub amount: Decimal,
+    pub date: String,
+    /// Keyed by target currency code
+    pub results: BTreeMap<String, ConvertedAmount>,
+}
+
+/// Single entry of the POST /api/convert/batch response
+/// Successful entries serialize as a plain `ConvertResponse`
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum BatchConvertResult {
+    Success(Box<ConvertResponse>),
+    Failure(BatchConvertError),
+}
+
+/// Inline error for a batch entry that could not be converted
 #[derive(Debug, Serialize)]
+pub struct BatchConvertError {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub error: String,
+    pub code: String,
+}
+
+/// Display metadata for a currency in GET /api/currencies
+#[derive(Debug, Serialize)]
+pub struct CurrencyInfo {
+    pub name: Option<&'static str>,
+    pub symbol: Option<&'static str>,
+    pub minor_units: Option<u32>,
+    /// Whether the latest rates include this currency
+    pub rate_available: bool,
+}
+
+/// Response for GET /api/currencies
+#[derive(Debug, Serialize)]
+pub struct CurrenciesResponse {
+    pub rates_available: bool,
+    pub date: Option<String>,
+    pub currencies: BTreeMap<String, CurrencyInfo>,
+}
+
+/// Page size for GET /api/pairs when `limit` is omitted
+pub const DEFAULT_PAIRS_LIMIT: usize = 100;
+
+/// Query parameters for GET /api/pairs
+#[derive(Debug, Deserialize, Validate)]
+pub struct PairsQuery {
+    /// Only pairs converting from this currency
+    #[validate(length(equal = 3), custom(function = "validate_currency_code"))]
+    pub base: Option<String>,
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// A directed conversion pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CurrencyPair {
+    pub from: String,
+    pub to: String,
+}
+
+/// Response for GET /api/pairs
+#[derive(Debug, Serialize)]
+pub struct PairsResponse {
+    pub date: String,
+    /// Matching pairs across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub pairs: Vec<CurrencyPair>,
+}
+
+/// Response for GET /health
+#[derive(Debug, Serialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
     pub redis: String,
+    /// Round trip of this check's Redis PING in milliseconds, omitted when it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redis_ping_ms: Option<f64>,
+    /// Lost Redis connections seen by commands since startup
+    pub redis_reconnects: u64,
     pub last_update: Option<String>,
+    pub stale: bool,
+    pub age_hours: Option<i64>,
+    /// `reachable` or `unreachable`, only when HEALTH_CHECK_ECB is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecb: Option<String>,
+    /// `healthy`, or `unhealthy` when a scheduled update is overdue
+    pub scheduler: String,
+    /// Last successful scheduled update (RFC 3339), None until one succeeds
+    pub scheduler_last_run: Option<String>,
+    /// REQUIRED_CURRENCIES absent from the last fetch, which was therefore rejected
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_currencies: Vec<String>,
+}
+
+/// Response for GET /readiness
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    pub redis: String,
+    pub rates_loaded: bool,
+}
+
+/// Response for POST /api/refresh
+#[derive(Debug, Serialize)]
+pub struct RefreshResponse {
+    pub date: String,
+    pub rates_count: usize,
+}
+
+/// One successful rate update, as kept in the Redis update log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateLogEntry {
+    /// When the rates were stored (RFC 3339, UTC)
+    pub timestamp: String,
+    /// Rate date of the stored table
+    pub date: String,
+    pub rate_count: usize,
+    /// Provider the rates came from (feed URL, or "fallback" for a mirror chain)
+    pub source: String,
+}
+
+/// Query parameters for GET /api/admin/updates
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdatesQuery {
+    /// Entries to return, newest first (DEFAULT_UPDATES_LIMIT when omitted)
+    #[validate(range(min = 1, max = 100))]
+    pub limit: Option<usize>,
+}
+
+/// Entries returned by GET /api/admin/updates unless `limit` is given
+pub const DEFAULT_UPDATES_LIMIT: usize = 20;
+
+/// Response for GET /api/admin/updates
+#[derive(Debug, Serialize)]
+pub struct UpdatesResponse {
+    /// Newest first
+    pub updates: Vec<UpdateLogEntry>,
+}
+
+/// Response for POST /api/admin/import
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    /// Number of days stored in history
+    pub imported: usize,
+    pub days: Vec<ImportedDay>,
+    /// Days rejected by parsing or the rate sanity checks
+    pub skipped: Vec<SkippedDay>,
+}
+
+/// One day of an import left out of history, with why
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkippedDay {
+    pub date: String,
+    pub reason: String,
+}
+
+/// One snapshot stored by an import
+#[derive(Debug, Serialize)]
+pub struct ImportedDay {
+    pub date: String,
+    pub rates_count: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert_query(from: &str, to: &str) -> ConvertQuery {
+        ConvertQuery {
+            from: from.to_string(),
+            to: to.to_string(),
+            amount: "100".to_string(),
+            date: None,
+            round: None,
+            explain: None,
+            mode: None,
+            spread_bps: None,
+            format: None,
+            locale: None,
+            percent: None,
+            rounding: None,
+            via: None,
+            minor_units: None,
+        }
+    }
+
+    #[test]
+    fn test_percent_must_be_within_0_and_100() {
+        let mut query = convert_query("USD", "EUR");
+        assert_eq!(query.parse_percent(), Ok(None));
+
+        for (percent, expected) in [("0", dec!(0)), ("10", dec!(10)), ("100", dec!(100))] {
+            query.percent = Some(percent.to_string());
+            assert_eq!(query.parse_percent(), Ok(Some(expected)));
+        }
+        for percent in ["-1", "100.01", "ten"] {
+            query.percent = Some(percent.to_string());
+            assert!(query.parse_percent().is_err(), "{percent}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_currency_code() {
+        let err = convert_query("USS", "EUR").validate().unwrap_err();
+        assert!(err.to_string().contains("Unknown currency code 'USS'"));
+    }
+
+    #[test]
+    fn test_field_errors_lists_every_field() {
+        let mut query = convert_query("USS", "XX");
+        query.mode = Some("sideways".to_string());
+        let errors = field_errors(&query.validate().unwrap_err());
+
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["from", "mode", "to"]);
+        assert!(errors[0].message.contains("Unknown currency code 'USS'"));
+    }
+
+    #[test]
+    fn test_wrong_length_reported_once() {
+        let err = convert_query("US", "EUR").validate().unwrap_err();
+        let message = err.to_string();
+        assert!(!message.contains("Unknown currency code"));
+        assert!(message.contains("from"));
+    }
+
+    #[test]
+    fn test_round_dp_defaults_to_minor_unit() {
+        assert_eq!(convert_query("USD", "JPY").round_dp(), Some(0));
+        assert_eq!(convert_query("USD", "BHD").round_dp(), Some(3));
+
+        let mut query = convert_query("USD", "JPY");
+        query.round = Some(4);
+        assert_eq!(query.round_dp(), Some(4));
+    }
+
+    #[test]
+    fn test_round_above_decimal_scale_rejected() {
+        let mut query = convert_query("USD", "EUR");
+        query.round = Some(29);
+        assert!(query.validate().is_err());
+    }
+
+    #[test]
+    fn test_latest_precision_above_decimal_scale_rejected() {
+        let query = |precision| LatestRatesQuery {
+            base: None,
+            symbols: None,
+            precision: Some(precision),
+            compare: None,
+            limit: None,
+            offset: None,
+        };
+
+        assert!(query(28).validate().is_ok());
+        assert!(query(29).validate().is_err());
+    }
+
+    #[test]
+    fn test_latest_compare_only_accepts_previous() {
+        let query = |compare: &str| LatestRatesQuery {
+            base: None,
+            symbols: None,
+            precision: None,
+            compare: Some(compare.to_string()),
+            limit: None,
+            offset: None,
+        };
+
+        assert!(query("previous").validate().is_ok());
+        assert!(query("PREVIOUS").validate().is_ok());
+        assert!(query("yesterday").validate().is_err());
+    }
+
+    #[test]
+    fn test_aliases_resolve_before_validation() {
+        let aliases = HashMap::from([("EURO".to_string(), "EUR".to_string())]);
+
+        let mut query = convert_query("euro", "usd");
+        assert!(query.validate().is_err());
+        query.resolve_aliases(&aliases);
+        assert_eq!(query.from, "EUR");
+        assert!(query.validate().is_ok());
+
+        // Unknown codes are left for the regular validation
+        let mut query = convert_query("EUROS", "USD");
+        query.resolve_aliases(&aliases);
+        assert_eq!(query.from, "EUROS");
+
+        let mut symbols = "GBP, euro,JPY".to_string();
+        resolve_symbol_aliases(&mut symbols, &aliases);
+        assert_eq!(parse_symbols(&symbols).unwrap(), vec!["GBP", "EUR", "JPY"]);
+    }
+
+    #[test]
+    fn test_rounding_modes_on_midpoint() {
+        let round =
+            |mode: RoundingMode, value: Decimal| value.round_dp_with_strategy(0, mode.strategy());
+
+        assert_eq!(round(RoundingMode::HalfUp, dec!(2.5)), dec!(3));
+        assert_eq!(round(RoundingMode::HalfEven, dec!(2.5)), dec!(2));
+        assert_eq!(round(RoundingMode::Down, dec!(2.5)), dec!(2));
+
+        assert_eq!(round(RoundingMode::HalfUp, dec!(3.5)), dec!(4));
+        assert_eq!(round(RoundingMode::HalfEven, dec!(3.5)), dec!(4));
+        assert_eq!(round(RoundingMode::Down, dec!(3.5)), dec!(3));
+    }
+
+    #[test]
+    fn test_rounding_param_validation() {
+        let mut query = convert_query("USD", "EUR");
+        assert_eq!(query.rounding_mode(), RoundingMode::HalfUp);
+
+        query.rounding = Some("HALF_EVEN".to_string());
+        assert!(query.validate().is_ok());
+        assert_eq!(query.rounding_mode(), RoundingMode::HalfEven);
+
+        query.rounding = Some("bankers".to_string());
+        assert!(query.validate().is_err());
+    }
+
+    #[test]
+    fn test_latest_limit_must_be_positive() {
+        let query = |limit| LatestRatesQuery {
+            base: None,
+            symbols: None,
+            precision: None,
+            compare: None,
+            limit: Some(limit),
+            offset: Some(10),
+        };
+
+        assert!(query(1).validate().is_ok());
+        assert!(query(0).validate().is_err());
+    }
+
+    #[test]
+    fn test_accepts_lowercase_iso_codes() {
+        assert!(convert_query("usd", "jpy").validate().is_ok());
+    }
+
+    #[test]
+    fn test_amount_accepts_json_string_or_number() {
+        let parse = |body: &str| {
+            serde_json::from_str::<ConvertQuery>(body)
+                .unwrap()
+                .parse_amount(DEFAULT_MAX_CONVERT_AMOUNT, None)
+                .unwrap()
+        };
+
+        assert_eq!(
+            parse(r#"{"from":"EUR","to":"USD","amount":"100.25"}"#),
+            Decimal::from_str("100.25").unwrap()
+        );
+        assert_eq!(
+            parse(r#"{"from":"EUR","to":"USD","amount":100}"#),
+            Decimal::from(100)
+        );
+        assert_eq!(
+            parse(r#"{"from":"EUR","to":"USD","amount":0.1}"#),
+            Decimal::from_str("0.1").unwrap()
+        );
+        // More digits than an f64 holds
+        assert_eq!(
+            parse(r#"{"from":"EUR","to":"USD","amount":1234567.123456789012}"#),
+            Decimal::from_str("1234567.123456789012").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_amount_rejects_negative_number_and_other_types() {
+        let query: ConvertQuery =
+            serde_json::from_str(r#"{"from":"EUR","to":"USD","amount":-5}"#).unwrap();
+        assert!(
+            query
+                .parse_amount(DEFAULT_MAX_CONVERT_AMOUNT, None)
+                .is_err()
+        );
+
+        assert!(
+            serde_json::from_str::<ConvertQuery>(r#"{"from":"EUR","to":"USD","amount":true}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_amount_from_query_string() {
+        let uri = "/api/convert?from=EUR&to=USD&amount=12.5".parse().unwrap();
+        let query = axum::extract::Query::<ConvertQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(query.amount, "12.5");
+    }
+
+    #[test]
+    fn test_amount_upper_bound() {
+        let mut query = convert_query("EUR", "USD");
+
+        query.amount = "1000000000000000".to_string();
+        assert_eq!(
+            query
+                .parse_amount(DEFAULT_MAX_CONVERT_AMOUNT, None)
+                .unwrap(),
+            DEFAULT_MAX_CONVERT_AMOUNT
+        );
+
+        query.amount = "99999999999999999999999999".to_string();
+        let err = query
+            .parse_amount(DEFAULT_MAX_CONVERT_AMOUNT, None)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            AmountError::OutOfRange("Amount must not exceed 1000000000000000".to_string())
+        );
+
+        query.amount = "1,000".to_string();
+        assert!(matches!(
+            query.parse_amount(DEFAULT_MAX_CONVERT_AMOUNT, None),
+            Err(AmountError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_amount_trims_whitespace_and_grouping() {
+        let parse = |text: &str| parse_amount(text, DEFAULT_MAX_CONVERT_AMOUNT, Some(','));
+
+        assert_eq!(parse("1,234.56"), Ok(Decimal::from_str("1234.56").unwrap()));
+        assert_eq!(parse(" 100 "), Ok(Decimal::from(100)));
+        assert_eq!(parse(" 1,234,567 "), Ok(Decimal::from(1_234_567)));
+        assert_eq!(
+            parse_amount("1 234.5", DEFAULT_MAX_CONVERT_AMOUNT, Some(' ')),
+            Ok(Decimal::from_str("1234.5").unwrap())
+        );
+
+        for bad in [
+            "1.2.3", "1,23", "1,,000", "1234,567", ",123", "0.000,1", "12a",
+        ] {
+            assert!(
+                matches!(parse(bad), Err(AmountError::Malformed(_))),
+                "{bad} should be rejected"
+            );
+        }
+        assert!(matches!(parse("-1,000"), Err(AmountError::OutOfRange(_))));
+    }
 }
@@ -0,0 +1,220 @@
+/// ISO 4217 metadata for a single currency
+#[derive(Debug, Clone, Copy)]
+pub struct CurrencyMeta {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub symbol: &'static str,
+    /// Number of digits after the decimal separator (e.g. 2 for USD, 0 for JPY)
+    pub minor_units: u32,
+}
+
+/// Static ISO 4217 table, sorted by code for binary search
+#[rustfmt::skip]
+pub const CURRENCIES: &[CurrencyMeta] = &[
+    CurrencyMeta { code: "AED", name: "UAE Dirham", symbol: "د.إ", minor_units: 2 },
+    CurrencyMeta { code: "AFN", name: "Afghani", symbol: "؋", minor_units: 2 },
+    CurrencyMeta { code: "ALL", name: "Lek", symbol: "L", minor_units: 2 },
+    CurrencyMeta { code: "AMD", name: "Armenian Dram", symbol: "֏", minor_units: 2 },
+    CurrencyMeta { code: "ANG", name: "Netherlands Antillean Guilder", symbol: "ƒ", minor_units: 2 },
+    CurrencyMeta { code: "AOA", name: "Kwanza", symbol: "Kz", minor_units: 2 },
+    CurrencyMeta { code: "ARS", name: "Argentine Peso", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "AUD", name: "Australian Dollar", symbol: "A$", minor_units: 2 },
+    CurrencyMeta { code: "AWG", name: "Aruban Florin", symbol: "ƒ", minor_units: 2 },
+    CurrencyMeta { code: "AZN", name: "Azerbaijan Manat", symbol: "₼", minor_units: 2 },
+    CurrencyMeta { code: "BAM", name: "Convertible Mark", symbol: "KM", minor_units: 2 },
+    CurrencyMeta { code: "BBD", name: "Barbados Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "BDT", name: "Taka", symbol: "৳", minor_units: 2 },
+    CurrencyMeta { code: "BGN", name: "Bulgarian Lev", symbol: "лв", minor_units: 2 },
+    CurrencyMeta { code: "BHD", name: "Bahraini Dinar", symbol: ".د.ب", minor_units: 3 },
+    CurrencyMeta { code: "BIF", name: "Burundi Franc", symbol: "FBu", minor_units: 0 },
+    CurrencyMeta { code: "BMD", name: "Bermudian Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "BND", name: "Brunei Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "BOB", name: "Boliviano", symbol: "Bs.", minor_units: 2 },
+    CurrencyMeta { code: "BRL", name: "Brazilian Real", symbol: "R$", minor_units: 2 },
+    CurrencyMeta { code: "BSD", name: "Bahamian Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "BTN", name: "Ngultrum", symbol: "Nu.", minor_units: 2 },
+    CurrencyMeta { code: "BWP", name: "Pula", symbol: "P", minor_units: 2 },
+    CurrencyMeta { code: "BYN", name: "Belarusian Ruble", symbol: "Br", minor_units: 2 },
+    CurrencyMeta { code: "BZD", name: "Belize Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "CAD", name: "Canadian Dollar", symbol: "CA$", minor_units: 2 },
+    CurrencyMeta { code: "CDF", name: "Congolese Franc", symbol: "FC", minor_units: 2 },
+    CurrencyMeta { code: "CHF", name: "Swiss Franc", symbol: "CHF", minor_units: 2 },
+    CurrencyMeta { code: "CLP", name: "Chilean Peso", symbol: "$", minor_units: 0 },
+    CurrencyMeta { code: "CNY", name: "Yuan Renminbi", symbol: "¥", minor_units: 2 },
+    CurrencyMeta { code: "COP", name: "Colombian Peso", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "CRC", name: "Costa Rican Colon", symbol: "₡", minor_units: 2 },
+    CurrencyMeta { code: "CUP", name: "Cuban Peso", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "CVE", name: "Cabo Verde Escudo", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "CZK", name: "Czech Koruna", symbol: "Kč", minor_units: 2 },
+    CurrencyMeta { code: "DJF", name: "Djibouti Franc", symbol: "Fdj", minor_units: 0 },
+    CurrencyMeta { code: "DKK", name: "Danish Krone", symbol: "kr", minor_units: 2 },
+    CurrencyMeta { code: "DOP", name: "Dominican Peso", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "DZD", name: "Algerian Dinar", symbol: "د.ج", minor_units: 2 },
+    CurrencyMeta { code: "EGP", name: "Egyptian Pound", symbol: "E£", minor_units: 2 },
+    CurrencyMeta { code: "ERN", name: "Nakfa", symbol: "Nfk", minor_units: 2 },
+    CurrencyMeta { code: "ETB", name: "Ethiopian Birr", symbol: "Br", minor_units: 2 },
+    CurrencyMeta { code: "EUR", name: "Euro", symbol: "€", minor_units: 2 },
+    CurrencyMeta { code: "FJD", name: "Fiji Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "FKP", name: "Falkland Islands Pound", symbol: "£", minor_units: 2 },
+    CurrencyMeta { code: "GBP", name: "Pound Sterling", symbol: "£", minor_units: 2 },
+    CurrencyMeta { code: "GEL", name: "Lari", symbol: "₾", minor_units: 2 },
+    CurrencyMeta { code: "GHS", name: "Ghana Cedi", symbol: "GH₵", minor_units: 2 },
+    CurrencyMeta { code: "GIP", name: "Gibraltar Pound", symbol: "£", minor_units: 2 },
+    CurrencyMeta { code: "GMD", name: "Dalasi", symbol: "D", minor_units: 2 },
+    CurrencyMeta { code: "GNF", name: "Guinean Franc", symbol: "FG", minor_units: 0 },
+    CurrencyMeta { code: "GTQ", name: "Quetzal", symbol: "Q", minor_units: 2 },
+    CurrencyMeta { code: "GYD", name: "Guyana Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "HKD", name: "Hong Kong Dollar", symbol: "HK$", minor_units: 2 },
+    CurrencyMeta { code: "HNL", name: "Lempira", symbol: "L", minor_units: 2 },
+    CurrencyMeta { code: "HRK", name: "Kuna", symbol: "kn", minor_units: 2 },
+    CurrencyMeta { code: "HTG", name: "Gourde", symbol: "G", minor_units: 2 },
+    CurrencyMeta { code: "HUF", name: "Forint", symbol: "Ft", minor_units: 2 },
+    CurrencyMeta { code: "IDR", name: "Rupiah", symbol: "Rp", minor_units: 2 },
+    CurrencyMeta { code: "ILS", name: "New Israeli Sheqel", symbol: "₪", minor_units: 2 },
+    CurrencyMeta { code: "INR", name: "Indian Rupee", symbol: "₹", minor_units: 2 },
+    CurrencyMeta { code: "IQD", name: "Iraqi Dinar", symbol: "ع.د", minor_units: 3 },
+    CurrencyMeta { code: "IRR", name: "Iranian Rial", symbol: "﷼", minor_units: 2 },
+    CurrencyMeta { code: "ISK", name: "Iceland Krona", symbol: "kr", minor_units: 0 },
+    CurrencyMeta { code: "JMD", name: "Jamaican Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "JOD", name: "Jordanian Dinar", symbol: "د.ا", minor_units: 3 },
+    CurrencyMeta { code: "JPY", name: "Yen", symbol: "¥", minor_units: 0 },
+    CurrencyMeta { code: "KES", name: "Kenyan Shilling", symbol: "KSh", minor_units: 2 },
+    CurrencyMeta { code: "KGS", name: "Som", symbol: "с", minor_units: 2 },
+    CurrencyMeta { code: "KHR", name: "Riel", symbol: "៛", minor_units: 2 },
+    CurrencyMeta { code: "KMF", name: "Comorian Franc", symbol: "CF", minor_units: 0 },
+    CurrencyMeta { code: "KPW", name: "North Korean Won", symbol: "₩", minor_units: 2 },
+    CurrencyMeta { code: "KRW", name: "Won", symbol: "₩", minor_units: 0 },
+    CurrencyMeta { code: "KWD", name: "Kuwaiti Dinar", symbol: "د.ك", minor_units: 3 },
+    CurrencyMeta { code: "KYD", name: "Cayman Islands Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "KZT", name: "Tenge", symbol: "₸", minor_units: 2 },
+    CurrencyMeta { code: "LAK", name: "Lao Kip", symbol: "₭", minor_units: 2 },
+    CurrencyMeta { code: "LBP", name: "Lebanese Pound", symbol: "ل.ل", minor_units: 2 },
+    CurrencyMeta { code: "LKR", name: "Sri Lanka Rupee", symbol: "Rs", minor_units: 2 },
+    CurrencyMeta { code: "LRD", name: "Liberian Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "LSL", name: "Loti", symbol: "L", minor_units: 2 },
+    CurrencyMeta { code: "LYD", name: "Libyan Dinar", symbol: "ل.د", minor_units: 3 },
+    CurrencyMeta { code: "MAD", name: "Moroccan Dirham", symbol: "د.م.", minor_units: 2 },
+    CurrencyMeta { code: "MDL", name: "Moldovan Leu", symbol: "L", minor_units: 2 },
+    CurrencyMeta { code: "MGA", name: "Malagasy Ariary", symbol: "Ar", minor_units: 2 },
+    CurrencyMeta { code: "MKD", name: "Denar", symbol: "ден", minor_units: 2 },
+    CurrencyMeta { code: "MMK", name: "Kyat", symbol: "K", minor_units: 2 },
+    CurrencyMeta { code: "MNT", name: "Tugrik", symbol: "₮", minor_units: 2 },
+    CurrencyMeta { code: "MOP", name: "Pataca", symbol: "MOP$", minor_units: 2 },
+    CurrencyMeta { code: "MRU", name: "Ouguiya", symbol: "UM", minor_units: 2 },
+    CurrencyMeta { code: "MUR", name: "Mauritius Rupee", symbol: "₨", minor_units: 2 },
+    CurrencyMeta { code: "MVR", name: "Rufiyaa", symbol: "Rf", minor_units: 2 },
+    CurrencyMeta { code: "MWK", name: "Malawi Kwacha", symbol: "MK", minor_units: 2 },
+    CurrencyMeta { code: "MXN", name: "Mexican Peso", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "MYR", name: "Malaysian Ringgit", symbol: "RM", minor_units: 2 },
+    CurrencyMeta { code: "MZN", name: "Mozambique Metical", symbol: "MT", minor_units: 2 },
+    CurrencyMeta { code: "NAD", name: "Namibia Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "NGN", name: "Naira", symbol: "₦", minor_units: 2 },
+    CurrencyMeta { code: "NIO", name: "Cordoba Oro", symbol: "C$", minor_units: 2 },
+    CurrencyMeta { code: "NOK", name: "Norwegian Krone", symbol: "kr", minor_units: 2 },
+    CurrencyMeta { code: "NPR", name: "Nepalese Rupee", symbol: "₨", minor_units: 2 },
+    CurrencyMeta { code: "NZD", name: "New Zealand Dollar", symbol: "NZ$", minor_units: 2 },
+    CurrencyMeta { code: "OMR", name: "Rial Omani", symbol: "ر.ع.", minor_units: 3 },
+    CurrencyMeta { code: "PAB", name: "Balboa", symbol: "B/.", minor_units: 2 },
+    CurrencyMeta { code: "PEN", name: "Sol", symbol: "S/", minor_units: 2 },
+    CurrencyMeta { code: "PGK", name: "Kina", symbol: "K", minor_units: 2 },
+    CurrencyMeta { code: "PHP", name: "Philippine Peso", symbol: "₱", minor_units: 2 },
+    CurrencyMeta { code: "PKR", name: "Pakistan Rupee", symbol: "₨", minor_units: 2 },
+    CurrencyMeta { code: "PLN", name: "Zloty", symbol: "zł", minor_units: 2 },
+    CurrencyMeta { code: "PYG", name: "Guarani", symbol: "₲", minor_units: 0 },
+    CurrencyMeta { code: "QAR", name: "Qatari Rial", symbol: "ر.ق", minor_units: 2 },
+    CurrencyMeta { code: "RON", name: "Romanian Leu", symbol: "lei", minor_units: 2 },
+    CurrencyMeta { code: "RSD", name: "Serbian Dinar", symbol: "дин.", minor_units: 2 },
+    CurrencyMeta { code: "RUB", name: "Russian Ruble", symbol: "₽", minor_units: 2 },
+    CurrencyMeta { code: "RWF", name: "Rwanda Franc", symbol: "FRw", minor_units: 0 },
+    CurrencyMeta { code: "SAR", name: "Saudi Riyal", symbol: "ر.س", minor_units: 2 },
+    CurrencyMeta { code: "SBD", name: "Solomon Islands Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "SCR", name: "Seychelles Rupee", symbol: "₨", minor_units: 2 },
+    CurrencyMeta { code: "SDG", name: "Sudanese Pound", symbol: "ج.س.", minor_units: 2 },
+    CurrencyMeta { code: "SEK", name: "Swedish Krona", symbol: "kr", minor_units: 2 },
+    CurrencyMeta { code: "SGD", name: "Singapore Dollar", symbol: "S$", minor_units: 2 },
+    CurrencyMeta { code: "SHP", name: "Saint Helena Pound", symbol: "£", minor_units: 2 },
+    CurrencyMeta { code: "SLE", name: "Leone", symbol: "Le", minor_units: 2 },
+    CurrencyMeta { code: "SOS", name: "Somali Shilling", symbol: "Sh", minor_units: 2 },
+    CurrencyMeta { code: "SRD", name: "Surinam Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "SSP", name: "South Sudanese Pound", symbol: "£", minor_units: 2 },
+    CurrencyMeta { code: "STN", name: "Dobra", symbol: "Db", minor_units: 2 },
+    CurrencyMeta { code: "SVC", name: "El Salvador Colon", symbol: "₡", minor_units: 2 },
+    CurrencyMeta { code: "SYP", name: "Syrian Pound", symbol: "£", minor_units: 2 },
+    CurrencyMeta { code: "SZL", name: "Lilangeni", symbol: "E", minor_units: 2 },
+    CurrencyMeta { code: "THB", name: "Baht", symbol: "฿", minor_units: 2 },
+    CurrencyMeta { code: "TJS", name: "Somoni", symbol: "SM", minor_units: 2 },
+    CurrencyMeta { code: "TMT", name: "Turkmenistan New Manat", symbol: "m", minor_units: 2 },
+    CurrencyMeta { code: "TND", name: "Tunisian Dinar", symbol: "د.ت", minor_units: 3 },
+    CurrencyMeta { code: "TOP", name: "Pa'anga", symbol: "T$", minor_units: 2 },
+    CurrencyMeta { code: "TRY", name: "Turkish Lira", symbol: "₺", minor_units: 2 },
+    CurrencyMeta { code: "TTD", name: "Trinidad and Tobago Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "TWD", name: "New Taiwan Dollar", symbol: "NT$", minor_units: 2 },
+    CurrencyMeta { code: "TZS", name: "Tanzanian Shilling", symbol: "TSh", minor_units: 2 },
+    CurrencyMeta { code: "UAH", name: "Hryvnia", symbol: "₴", minor_units: 2 },
+    CurrencyMeta { code: "UGX", name: "Uganda Shilling", symbol: "USh", minor_units: 0 },
+    CurrencyMeta { code: "USD", name: "US Dollar", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "UYU", name: "Peso Uruguayo", symbol: "$", minor_units: 2 },
+    CurrencyMeta { code: "UZS", name: "Uzbekistan Sum", symbol: "soʻm", minor_units: 2 },
+    CurrencyMeta { code: "VES", name: "Bolívar Soberano", symbol: "Bs.S", minor_units: 2 },
+    CurrencyMeta { code: "VND", name: "Dong", symbol: "₫", minor_units: 0 },
+    CurrencyMeta { code: "VUV", name: "Vatu", symbol: "VT", minor_units: 0 },
+    CurrencyMeta { code: "WST", name: "Tala", symbol: "WS$", minor_units: 2 },
+    CurrencyMeta { code: "XAF", name: "CFA Franc BEAC", symbol: "FCFA", minor_units: 0 },
+    CurrencyMeta { code: "XCD", name: "East Caribbean Dollar", symbol: "EC$", minor_units: 2 },
+    CurrencyMeta { code: "XCG", name: "Caribbean Guilder", symbol: "Cg", minor_units: 2 },
+    CurrencyMeta { code: "XOF", name: "CFA Franc BCEAO", symbol: "CFA", minor_units: 0 },
+    CurrencyMeta { code: "XPF", name: "CFP Franc", symbol: "₣", minor_units: 0 },
+    CurrencyMeta { code: "YER", name: "Yemeni Rial", symbol: "﷼", minor_units: 2 },
+    CurrencyMeta { code: "ZAR", name: "Rand", symbol: "R", minor_units: 2 },
+    CurrencyMeta { code: "ZMW", name: "Zambian Kwacha", symbol: "ZK", minor_units: 2 },
+    CurrencyMeta { code: "ZWG", name: "Zimbabwe Gold", symbol: "ZiG", minor_units: 2 },
+];
+
+/// Look up ISO 4217 metadata by (case-insensitive) currency code
+pub fn currency_meta(code: &str) -> Option<&'static CurrencyMeta> {
+    let code = code.to_uppercase();
+    CURRENCIES
+        .binary_search_by(|meta| meta.code.cmp(code.as_str()))
+        .ok()
+        .map(|index| &CURRENCIES[index])
+}
+
+/// Whether the code is a known ISO 4217 currency code
+pub fn is_iso_currency(code: &str) -> bool {
+    currency_meta(code).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sorted_and_unique() {
+        // Binary search relies on strictly ascending codes
+        assert!(
+            CURRENCIES
+                .windows(2)
+                .all(|pair| pair[0].code < pair[1].code)
+        );
+    }
+
+    #[test]
+    fn test_currency_meta_lookup() {
+        let usd = currency_meta("usd").unwrap();
+        assert_eq!(usd.name, "US Dollar");
+        assert_eq!(usd.symbol, "$");
+        assert_eq!(usd.minor_units, 2);
+
+        assert_eq!(currency_meta("JPY").unwrap().minor_units, 0);
+        assert_eq!(currency_meta("BHD").unwrap().minor_units, 3);
+        assert!(currency_meta("XXX").is_none());
+    }
+
+    #[test]
+    fn test_is_iso_currency() {
+        assert!(is_iso_currency("EUR"));
+        assert!(is_iso_currency("gbp"));
+        assert!(!is_iso_currency("USS"));
+        assert!(!is_iso_currency("US"));
+    }
+}
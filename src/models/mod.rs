@@ -1,5 +1,7 @@
 pub mod api;
+pub mod currency_meta;
 pub mod rate;
 
 pub use api::*;
+pub use currency_meta::*;
 pub use rate::*;
@@ -3,7 +3,12 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Daily exchange rates with EUR as base currency (from ECB)
+/// Base currency of the ECB reference rates
+pub const ECB_BASE_CURRENCY: &str = "EUR";
+
+/// Daily exchange rates against `base`, set by the provider that fetched them
+/// (EUR for ECB); fetched tables list `base` in `rates` at 1, rebased ones leave it out,
+/// so lookups treat the base as 1 whether or not it is listed
 /// Uses Decimal for precise financial calculations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyRate {
@@ -26,6 +31,20 @@ pub struct EcbOuterCube {
     pub time_cube: EcbTimeCube,
 }
 
+/// ECB multi-day XML envelope (e.g. eurofxref-hist-90d.xml), one time Cube per day
+#[derive(Debug, Deserialize)]
+#[serde(rename = "Envelope")]
+pub struct EcbHistoryEnvelope {
+    #[serde(rename = "Cube")]
+    pub cube: EcbHistoryOuterCube,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EcbHistoryOuterCube {
+    #[serde(rename = "Cube", default)]
+    pub days: Vec<EcbTimeCube>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct EcbTimeCube {
     #[serde(rename = "@time")]
@@ -43,24 +62,52 @@ pub struct EcbRate {
 }
 
 impl DailyRate {
+    /// Build a EUR-based table from one ECB time Cube
     pub fn from_ecb_data(time: String, rates: Vec<EcbRate>) -> Result<Self, String> {
+        Self::from_quotes(ECB_BASE_CURRENCY, time, rates)
+    }
+
+    /// Build a table from quotes of one unit of `base` in each currency
+    /// Codes must be exactly 3 ASCII letters (any case) and appear once, so a
+    /// malformed feed is rejected instead of silently overwriting entries; a quote
+    /// for `base` itself is only accepted at 1
+    pub fn from_quotes(base: &str, time: String, rates: Vec<EcbRate>) -> Result<Self, String> {
         let mut rate_map = HashMap::new();
 
         // Add all rates from ECB
         for rate in rates {
+            if rate.currency.len() != 3 || !rate.currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!(
+                    "Invalid currency code '{}' for {}",
+                    rate.currency, time
+                ));
+            }
+
             let rate_value = rate
                 .rate
                 .parse::<Decimal>()
                 .map_err(|e| format!("Failed to parse rate for {}: {}", rate.currency, e))?;
-            rate_map.insert(rate.currency.to_uppercase(), rate_value);
+
+            let code = rate.currency.to_uppercase();
+            if rate_map.insert(code.clone(), rate_value).is_some() {
+                return Err(format!("Duplicate currency code '{}' for {}", code, time));
+            }
         }
 
-        // Add EUR = 1.0 (ECB doesn't include it since it's the base)
-        rate_map.insert("EUR".to_string(), Decimal::ONE);
+        // Add base = 1.0 (feeds don't quote their own base, and one that does must say 1)
+        let base = base.to_uppercase();
+        if let Some(rate) = rate_map.insert(base.clone(), Decimal::ONE)
+            && rate != Decimal::ONE
+        {
+            return Err(format!(
+                "Rate {} for base currency {} on {}, expected 1",
+                rate, base, time
+            ));
+        }
 
         Ok(DailyRate {
             date: time,
-            base: "EUR".to_string(),
+            base,
             rates: rate_map,
         })
     }
@@ -72,3 +119,86 @@ impl DailyRate {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(currency: &str, rate: &str) -> EcbRate {
+        EcbRate {
+            currency: currency.to_string(),
+            rate: rate.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_from_ecb_data_uppercases_codes() {
+        let daily_rate = DailyRate::from_ecb_data(
+            "2024-12-04".to_string(),
+            vec![rate("usd", "1.05"), rate("JPY", "158.2")],
+        )
+        .unwrap();
+
+        assert_eq!(daily_rate.rates["USD"], Decimal::new(105, 2));
+        assert_eq!(daily_rate.rates["EUR"], Decimal::ONE);
+        assert_eq!(daily_rate.rates.len(), 3);
+    }
+
+    #[test]
+    fn test_from_quotes_on_non_eur_base() {
+        let daily_rate = DailyRate::from_quotes(
+            "usd",
+            "2024-12-04".to_string(),
+            vec![rate("EUR", "0.95"), rate("JPY", "150.5")],
+        )
+        .unwrap();
+
+        assert_eq!(daily_rate.base, "USD");
+        assert_eq!(daily_rate.rates["USD"], Decimal::ONE);
+        assert_eq!(daily_rate.rates["EUR"], Decimal::new(95, 2));
+        assert_eq!(daily_rate.rates.len(), 3);
+    }
+
+    #[test]
+    fn test_from_quotes_accepts_base_only_at_one() {
+        let daily_rate = DailyRate::from_quotes(
+            "USD",
+            "2024-12-04".to_string(),
+            vec![rate("usd", "1.00"), rate("EUR", "0.95")],
+        )
+        .unwrap();
+        assert_eq!(daily_rate.rates["USD"], Decimal::ONE);
+
+        let err = DailyRate::from_quotes(
+            "USD",
+            "2024-12-04".to_string(),
+            vec![rate("USD", "1.05"), rate("EUR", "0.95")],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "Rate 1.05 for base currency USD on 2024-12-04, expected 1"
+        );
+    }
+
+    #[test]
+    fn test_from_ecb_data_rejects_duplicates() {
+        let err = DailyRate::from_ecb_data(
+            "2024-12-04".to_string(),
+            vec![rate("USD", "1.05"), rate("usd", "1.06")],
+        )
+        .unwrap_err();
+
+        assert_eq!(err, "Duplicate currency code 'USD' for 2024-12-04");
+    }
+
+    #[test]
+    fn test_from_ecb_data_rejects_malformed_codes() {
+        for code in ["US", "USDX", "U5D", " USD"] {
+            let err = DailyRate::from_ecb_data("2024-12-04".to_string(), vec![rate(code, "1.05")])
+                .unwrap_err();
+
+            assert!(err.contains("Invalid currency code"), "{code}: {err}");
+        }
+    }
+}
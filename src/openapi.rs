@@ -0,0 +1,98 @@
+use crate::handlers;
+use crate::models::{
+    ConvertResponse, ErrorResponse, FieldError, HealthResponse, LatestRatesResponse, RateResponse,
+};
+use axum::{Json, response::Html};
+use utoipa::OpenApi;
+
+/// Path of the generated OpenAPI document
+pub const OPENAPI_PATH: &str = "/api-docs/openapi.json";
+
+/// Exact swagger-ui-dist release served by /docs; bump deliberately after reviewing it
+const SWAGGER_UI_VERSION: &str = "5.17.14";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Currency Converter API", description = "Exchange rates and conversions based on ECB reference rates"),
+    paths(
+        handlers::convert::convert_handler,
+        handlers::convert::rate_handler,
+        handlers::rates::latest_rates_handler,
+        handlers::health::health_handler
+    ),
+    components(schemas(ConvertResponse, RateResponse, LatestRatesResponse, HealthResponse, ErrorResponse, FieldError)),
+    tags(
+        (name = "rates", description = "Exchange rate tables"),
+        (name = "conversion", description = "Currency conversion"),
+        (name = "monitoring", description = "Health and readiness")
+    )
+)]
+pub struct ApiDoc;
+
+/// OpenAPI JSON document
+pub async fn openapi_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Swagger UI for the OpenAPI document
+/// The page loads a pinned swagger-ui-dist release from the unpkg CDN, so it only
+/// renders when the browser can reach unpkg.com
+pub async fn docs_handler() -> Html<String> {
+    Html(format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Currency Converter API - Docs</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@{version}/swagger-ui.css" crossorigin />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@{version}/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {{
+      window.ui = SwaggerUIBundle({{ url: "{spec}", dom_id: "#swagger-ui" }});
+    }};
+  </script>
+</body>
+</html>"##,
+        version = SWAGGER_UI_VERSION,
+        spec = OPENAPI_PATH
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_documents_handlers() {
+        let doc = ApiDoc::openapi();
+
+        for path in ["/api/convert", "/api/rate", "/api/latest", "/health"] {
+            assert!(doc.paths.paths.contains_key(path), "missing {path}");
+        }
+    }
+
+    #[test]
+    fn test_openapi_reflects_validation_constraints() {
+        let json = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let params = json["paths"]["/api/convert"]["get"]["parameters"]
+            .as_array()
+            .unwrap();
+        let from = params.iter().find(|p| p["name"] == "from").unwrap();
+
+        assert_eq!(from["required"], true);
+        assert_eq!(from["schema"]["minLength"], 3);
+        assert_eq!(from["schema"]["maxLength"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_docs_pin_swagger_ui_release() {
+        let Html(page) = docs_handler().await;
+
+        assert!(page.contains(&format!("swagger-ui-dist@{}/", SWAGGER_UI_VERSION)));
+        assert!(!page.contains("swagger-ui-dist@5/"));
+        assert!(page.contains(OPENAPI_PATH));
+    }
+}
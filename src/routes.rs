@@ -1,48 +1,336 @@
-use crate::handlers::{convert_handler, health_handler, latest_rates_handler};
+use crate::handlers::{
+    change_handler, convert_all_handler, convert_batch_handler, convert_handler,
+    convert_post_handler, currencies_handler, health_handler, import_handler, latest_rates_handler,
+    metrics_handler, pairs_handler, portfolio_handler, rate_handler, readiness_handler,
+    refresh_handler, timeseries_handler, updates_handler, ws_rates_handler,
+};
+use crate::middleware::{
+    ApiKeys, REQUEST_ID_HEADER, RateLimiter, api_key_auth, concurrency_limit, rate_limit,
+    request_span, request_timeout, track_in_flight, track_metrics,
+};
+use crate::openapi::{OPENAPI_PATH, docs_handler, openapi_handler};
 use crate::services::RedisStore;
-use axum::{Json, Router, http::StatusCode, routing::get};
+use crate::state::AppState;
+use axum::{
+    Json, Router,
+    extract::{DefaultBodyLimit, State},
+    http::{HeaderValue, StatusCode},
+    middleware,
+    routing::{get, post},
+};
 use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use tower_http::{
+    CompressionLevel, LatencyUnit,
     compression::CompressionLayer,
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
+    cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::{DefaultOnResponse, TraceLayer},
 };
+use tracing::Level;
+
+/// Service overview with live status, always 200 so it works as a quick human check
+/// Redis or rate lookups that fail are reported as such rather than as an error
+async fn root_handler(State(store): State<RedisStore>) -> (StatusCode, Json<serde_json::Value>) {
+    let redis_healthy = store.health_check().await.is_ok();
+    let rates = if redis_healthy {
+        store.get_rates().await.ok().flatten()
+    } else {
+        None
+    };
 
-async fn root_handler() -> (StatusCode, Json<serde_json::Value>) {
     (
         StatusCode::OK,
         Json(json!({
-            "status": "ok",
+            "status": if rates.is_some() { "ok" } else { "degraded" },
             "service": "Currency Converter API",
             "version": "0.2.0",
+            "redis": if redis_healthy { "healthy" } else { "unhealthy" },
+            "rates": {
+                "loaded": rates.is_some(),
+                "currencies": rates.as_ref().map_or(0, |rates| rates.rates.len()),
+                "date": rates.as_ref().map(|rates| rates.date.as_str()),
+            },
             "endpoints": {
                 "health": "GET /health",
-                "latest_rates": "GET /api/latest?base=<CURRENCY>",
-                "convert": "GET /api/convert?from=<FROM>&to=<TO>&amount=<AMOUNT>"
+                "readiness": "GET /readiness",
+                "metrics": "GET /metrics",
+                "docs": "GET /docs",
+                "openapi": "GET /api-docs/openapi.json",
+                "latest_rates": "GET /api/latest?base=<CURRENCY>&symbols=<CODES>&precision=<N>",
+                "convert": "GET /api/convert?from=<FROM>&to=<TO>&amount=<AMOUNT>",
+                "rate": "GET /api/rate?from=<FROM>&to=<TO>",
+                "convert_json": "POST /api/convert",
+                "convert_batch": "POST /api/convert/batch",
+                "convert_all": "GET /api/convert/all?from=<FROM>&amount=<AMOUNT>&symbols=<CODES>",
+                "portfolio": "POST /api/portfolio",
+                "currencies": "GET /api/currencies",
+                "pairs": "GET /api/pairs?base=<CURRENCY>&limit=<N>&offset=<N>",
+                "refresh": "POST /api/refresh (requires API key)",
+                "import": "POST /api/admin/import (ECB XML body, requires API key)",
+                "updates": "GET /api/admin/updates?limit=<N> (requires API key)",
+                "ws_rates": "GET /ws/rates (WebSocket)",
+                "timeseries": "GET /api/timeseries?start_date=<DATE>&end_date=<DATE>&base=<CURRENCY>&symbols=<CODES>",
+                "change": "GET /api/change?symbol=<CURRENCY>&start=<DATE>&end=<DATE>&base=<CURRENCY>"
             }
         })),
     )
 }
 
-pub fn create_router(store: RedisStore) -> Router {
-    // CORS configuration - adjust origins for production
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+/// CORS for any origin when ALLOWED_ORIGINS is unset, credentialed CORS for the listed ones
+/// Credentials rule out wildcards, so the preflight's method and headers are mirrored instead
+fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    if allowed_origins.is_empty() {
+        return CorsLayer::new()
+            .allow_origin(Any)
+            .allow_methods(Any)
+            .allow_headers(Any);
+    }
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(
+            allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        ))
+        .allow_methods(AllowMethods::mirror_request())
+        .allow_headers(AllowHeaders::mirror_request())
+        .allow_credentials(true)
+}
+
+/// Cap request bodies (batch conversions) at MAX_BODY_BYTES
+/// Layered over every route, so a route's own DefaultBodyLimit (imports) still takes precedence
+fn limit_request_bodies(router: Router, max_body_bytes: usize) -> Router {
+    router.layer(DefaultBodyLimit::max(max_body_bytes))
+}
+
+pub fn create_router(state: AppState) -> Router {
+    let cors = cors_layer(&state.config.allowed_origins);
 
-    Router::new()
+    let rate_limit_per_min = state.config.rate_limit_per_min;
+    let trusted_proxy_hops = state.config.trusted_proxy_hops;
+    let max_body_bytes = state.config.max_body_bytes;
+    let max_import_bytes = state.config.max_import_bytes;
+    let api_keys = ApiKeys::new(state.config.api_keys.iter().cloned());
+    let request_timeout_ms = state.config.request_timeout_ms;
+    let max_concurrent_requests = state.config.max_concurrent_requests;
+    let in_flight = state.in_flight.clone();
+
+    // Health probes get their own, shorter limit (HEALTH_TIMEOUT_MS)
+    let health = match state.config.health_timeout_ms {
+        0 => get(health_handler),
+        ms => get(health_handler).layer(middleware::from_fn_with_state(
+            Duration::from_millis(ms),
+            request_timeout,
+        )),
+    };
+
+    let router = Router::new()
         // Root endpoint
         .route("/", get(root_handler))
         // Health check endpoint
-        .route("/health", get(health_handler))
+        .route("/health", health)
+        // Readiness probe (Redis reachable and rates loaded)
+        .route("/readiness", get(readiness_handler))
+        // Prometheus metrics
+        .route("/metrics", get(metrics_handler))
+        // OpenAPI document and Swagger UI
+        .route(OPENAPI_PATH, get(openapi_handler))
+        .route("/docs", get(docs_handler))
         // API endpoints
         .route("/api/latest", get(latest_rates_handler))
-        .route("/api/convert", get(convert_handler))
+        .route(
+            "/api/convert",
+            get(convert_handler).post(convert_post_handler),
+        )
+        .route("/api/convert/batch", post(convert_batch_handler))
+        .route("/api/convert/all", get(convert_all_handler))
+        .route("/api/rate", get(rate_handler))
+        .route("/api/portfolio", post(portfolio_handler))
+        .route("/api/timeseries", get(timeseries_handler))
+        .route("/api/change", get(change_handler))
+        .route("/api/currencies", get(currencies_handler))
+        .route("/api/pairs", get(pairs_handler))
+        // Live rate updates
+        .route("/ws/rates", get(ws_rates_handler));
+
+    // Abort slow handlers with a 504 (disabled when REQUEST_TIMEOUT_MS is 0)
+    // Only public routes are limited: a refresh may legitimately wait on ECB retries
+    // and the update lock for far longer, and cutting it off would strand the lock
+    let router = if request_timeout_ms > 0 {
+        router.route_layer(middleware::from_fn_with_state(
+            Duration::from_millis(request_timeout_ms),
+            request_timeout,
+        ))
+    } else {
+        router
+    };
+
+    // Admin endpoints are only exposed when API key authentication is enabled
+    let router = if api_keys.is_empty() {
+        router
+    } else {
+        router
+            .route("/api/refresh", post(refresh_handler))
+            // ECB history files outgrow MAX_BODY_BYTES, so imports get their own limit
+            .route(
+                "/api/admin/import",
+                post(import_handler).layer(DefaultBodyLimit::max(max_import_bytes)),
+            )
+            .route("/api/admin/updates", get(updates_handler))
+    };
+
+    // gzip at COMPRESSION_LEVEL, or the library default when unset
+    let compression = match state.config.compression_level {
+        Some(level) => CompressionLayer::new().quality(CompressionLevel::Precise(level as i32)),
+        None => CompressionLayer::new(),
+    };
+
+    let router = router
         // Add shared state
-        .with_state(store)
+        .with_state(state)
         // Add middleware layers
-        .layer(CompressionLayer::new())
-        .layer(TraceLayer::new_for_http())
+        .layer(compression);
+    let router = limit_request_bodies(router, max_body_bytes);
+
+    // API key authentication (opt-in: only when API_KEYS is set)
+    let router = if api_keys.is_empty() {
+        router
+    } else {
+        router.layer(middleware::from_fn_with_state(api_keys, api_key_auth))
+    };
+
+    // Per-IP rate limiting (disabled when RATE_LIMIT_PER_MIN is 0)
+    let router = if rate_limit_per_min > 0 {
+        router.layer(middleware::from_fn_with_state(
+            RateLimiter::new(rate_limit_per_min).with_trusted_proxy_hops(trusted_proxy_hops),
+            rate_limit,
+        ))
+    } else {
+        router
+    };
+
+    // Shed load beyond MAX_CONCURRENT_REQUESTS with a 503 (disabled when 0)
+    let router = if max_concurrent_requests > 0 {
+        router.layer(middleware::from_fn_with_state(
+            Arc::new(Semaphore::new(max_concurrent_requests)),
+            concurrency_limit,
+        ))
+    } else {
+        router
+    };
+
+    router
+        .layer(middleware::from_fn(track_metrics))
+        // Count running requests so shutdown can report those it cuts off
+        .layer(middleware::from_fn_with_state(in_flight, track_in_flight))
+        // Log method, path, status and latency in a span tagged with the request ID
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(request_span)
+                .on_response(
+                    DefaultOnResponse::new()
+                        .level(Level::INFO)
+                        .latency_unit(LatencyUnit::Millis),
+                ),
+        )
+        // Assign a request ID (unless supplied) and echo it in X-Request-Id
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
         .layer(cors)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get};
+    use tower::ServiceExt;
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::options("/api/latest")
+            .header("origin", origin)
+            .header("access-control-request-method", "GET")
+            .header("access-control-request-headers", "authorization")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_allowed_origins_get_credentialed_cors() {
+        let app = Router::new()
+            .route("/api/latest", get(|| async { "ok" }))
+            .layer(cors_layer(&["https://app.example.com".to_string()]));
+
+        let response = app
+            .oneshot(preflight("https://app.example.com"))
+            .await
+            .unwrap();
+        let headers = response.headers();
+
+        assert_eq!(headers["access-control-allow-credentials"], "true");
+        assert_eq!(
+            headers["access-control-allow-origin"],
+            "https://app.example.com"
+        );
+        assert_eq!(headers["access-control-allow-methods"], "GET");
+        assert_eq!(headers["access-control-allow-headers"], "authorization");
+    }
+
+    #[tokio::test]
+    async fn test_unset_allowed_origins_allow_any_without_credentials() {
+        let app = Router::new()
+            .route("/api/latest", get(|| async { "ok" }))
+            .layer(cors_layer(&[]));
+
+        let response = app
+            .oneshot(preflight("https://other.example.com"))
+            .await
+            .unwrap();
+        let headers = response.headers();
+
+        assert_eq!(headers["access-control-allow-origin"], "*");
+        assert!(!headers.contains_key("access-control-allow-credentials"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_batch_body_is_rejected() {
+        use crate::error::ApiError;
+        use crate::models::BatchConvertRequest;
+        use axum::extract::rejection::JsonRejection;
+
+        // Same extraction as convert_batch_handler, without the Redis-backed state,
+        // behind the body limit create_router applies
+        async fn batch(
+            body: Result<Json<BatchConvertRequest>, JsonRejection>,
+        ) -> Result<Json<usize>, ApiError> {
+            let Json(body) = body?;
+            Ok(Json(body.conversions.len()))
+        }
+        let app =
+            || limit_request_bodies(Router::new().route("/api/convert/batch", post(batch)), 256);
+        let request = |body: String| {
+            Request::post("/api/convert/batch")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let entry = r#"{"from":"EUR","to":"USD","amount":"100"}"#;
+        let small = format!(r#"{{"conversions":[{}]}}"#, entry);
+        let large = format!(r#"{{"conversions":[{}]}}"#, vec![entry; 20].join(","));
+
+        let response = app().oneshot(request(small)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app().oneshot(request(large)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "Request body is too large");
+        assert_eq!(body["code"], "PAYLOAD_TOO_LARGE");
+    }
+}
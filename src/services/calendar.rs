@@ -0,0 +1,245 @@
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+/// Days a historical lookup may walk back when HISTORY_LOOKBACK_DAYS is unset
+/// Covers a weekend plus the longest run of TARGET holidays (Good Friday to Easter Monday)
+pub const DEFAULT_LOOKBACK_DAYS: u32 = 7;
+
+/// Earliest date a lookup for `requested` may fall back to
+pub fn earliest_fallback(requested: NaiveDate, lookback_days: u32) -> NaiveDate {
+    requested
+        .checked_sub_days(Days::new(lookback_days as u64))
+        .unwrap_or(NaiveDate::MIN)
+}
+
+/// Most recent of `available` on or before `requested`, at most `lookback_days` earlier
+/// ECB publishes nothing on weekends, so a Saturday or Sunday resolves to Friday
+pub fn nearest_available(
+    requested: NaiveDate,
+    available: impl IntoIterator<Item = NaiveDate>,
+    lookback_days: u32,
+) -> Option<NaiveDate> {
+    let earliest = earliest_fallback(requested, lookback_days);
+
+    available
+        .into_iter()
+        .filter(|date| (earliest..=requested).contains(date))
+        .max()
+}
+
+/// Reject a requested rate date after `today` (UTC), which no source can have published
+/// Today itself is accepted even before the day's publication: lookups then fall back
+/// to the latest business day stored on or before it
+pub fn ensure_not_future(date: NaiveDate, today: NaiveDate) -> Result<(), String> {
+    if date > today {
+        return Err(format!(
+            "{} is in the future (today is {} UTC)",
+            date, today
+        ));
+    }
+
+    Ok(())
+}
+
+/// Easter Sunday of `year` in the Gregorian calendar (anonymous computus)
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year.rem_euclid(19);
+    let (b, c) = (year.div_euclid(100), year.rem_euclid(100));
+    let (d, e) = (b / 4, b % 4);
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let (i, k) = (c / 4, c % 4);
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Whether TARGET is closed on `date`, so ECB publishes no reference rates:
+/// New Year's Day, Good Friday, Easter Monday, 1 May, 25 and 26 December
+pub fn is_target_holiday(date: NaiveDate) -> bool {
+    if matches!(
+        (date.month(), date.day()),
+        (1, 1) | (5, 1) | (12, 25) | (12, 26)
+    ) {
+        return true;
+    }
+
+    easter_sunday(date.year()).is_some_and(|easter| {
+        let offset = (date - easter).num_days();
+        offset == -2 || offset == 1
+    })
+}
+
+/// Whether ECB publishes reference rates on `date` (a weekday outside TARGET holidays)
+pub fn is_business_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !is_target_holiday(date)
+}
+
+/// Business day on or before `date`, the day a lookup for it falls back to
+pub fn business_day_on_or_before(date: NaiveDate) -> NaiveDate {
+    let mut day = date;
+    while !is_business_day(day) {
+        let Some(previous) = day.pred_opt() else {
+            return NaiveDate::MIN;
+        };
+        day = previous;
+    }
+
+    day
+}
+
+/// Reject a requested rate date whose business day is newer than `latest_stored`
+/// A weekend or TARGET holiday after the latest stored day still resolves to it, and today is served
+/// from the previous business day until its own rates are published, provided that
+/// day is stored; an older snapshot would answer for dates that are themselves rejected
+pub fn ensure_published(
+    date: NaiveDate,
+    latest_stored: NaiveDate,
+    today: NaiveDate,
+) -> Result<(), String> {
+    let required = if date == today {
+        today.pred_opt().unwrap_or(NaiveDate::MIN)
+    } else {
+        date
+    };
+
+    if business_day_on_or_before(required) > latest_stored {
+        return Err(format!(
+            "no rates are stored for {} yet (latest is {})",
+            date, latest_stored
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 12, d).unwrap()
+    }
+
+    // Business days around the weekend of 7-8 December 2024
+    fn published() -> Vec<NaiveDate> {
+        vec![day(4), day(5), day(6), day(9)]
+    }
+
+    #[test]
+    fn test_weekend_resolves_to_friday() {
+        assert_eq!(day(7).weekday(), Weekday::Sat);
+        assert_eq!(day(8).weekday(), Weekday::Sun);
+
+        assert_eq!(nearest_available(day(7), published(), 7), Some(day(6)));
+        assert_eq!(nearest_available(day(8), published(), 7), Some(day(6)));
+    }
+
+    #[test]
+    fn test_published_day_resolves_to_itself() {
+        assert_eq!(nearest_available(day(9), published(), 7), Some(day(9)));
+    }
+
+    #[test]
+    fn test_lookback_limit() {
+        // Sunday to Friday is two days back
+        assert_eq!(nearest_available(day(8), published(), 1), None);
+        assert_eq!(nearest_available(day(8), published(), 2), Some(day(6)));
+
+        // Nothing within a week before the first snapshot
+        assert_eq!(nearest_available(day(3), published(), 7), None);
+    }
+
+    #[test]
+    fn test_earliest_fallback() {
+        assert_eq!(earliest_fallback(day(8), 7), day(1));
+        assert_eq!(earliest_fallback(day(8), 0), day(8));
+        assert_eq!(earliest_fallback(NaiveDate::MIN, 7), NaiveDate::MIN);
+    }
+
+    #[test]
+    fn test_ensure_not_future() {
+        assert!(ensure_not_future(day(8), day(9)).is_ok());
+        // Today is served from the previous business day until it is published
+        assert!(ensure_not_future(day(9), day(9)).is_ok());
+        assert_eq!(
+            ensure_not_future(day(10), day(9)).unwrap_err(),
+            "2024-12-10 is in the future (today is 2024-12-09 UTC)"
+        );
+    }
+
+    #[test]
+    fn test_business_day_on_or_before() {
+        assert_eq!(business_day_on_or_before(day(6)), day(6));
+        assert_eq!(business_day_on_or_before(day(7)), day(6));
+        assert_eq!(business_day_on_or_before(day(8)), day(6));
+        assert_eq!(business_day_on_or_before(day(9)), day(9));
+
+        // Christmas 2024 fell on Wednesday and Thursday
+        assert_eq!(business_day_on_or_before(day(26)), day(24));
+        assert_eq!(business_day_on_or_before(day(27)), day(27));
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_target_holidays() {
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+
+        for holiday in [
+            date(2025, 1, 1),
+            date(2025, 4, 18),
+            date(2025, 4, 21),
+            date(2025, 5, 1),
+            date(2025, 12, 25),
+            date(2025, 12, 26),
+        ] {
+            assert!(is_target_holiday(holiday), "{holiday} is a TARGET holiday");
+            assert!(!is_business_day(holiday));
+        }
+        assert!(!is_target_holiday(date(2025, 4, 17)));
+        assert!(!is_target_holiday(date(2025, 12, 24)));
+
+        // Easter 2025: Good Friday to Easter Monday fall back to Maundy Thursday
+        assert_eq!(
+            business_day_on_or_before(date(2025, 4, 21)),
+            date(2025, 4, 17)
+        );
+    }
+
+    #[test]
+    fn test_ensure_published() {
+        // Friday is the latest stored day on Tuesday morning, before Monday's rates landed
+        assert!(ensure_published(day(6), day(6), day(10)).is_ok());
+        assert!(ensure_published(day(8), day(6), day(10)).is_ok());
+        assert_eq!(
+            ensure_published(day(9), day(6), day(10)).unwrap_err(),
+            "no rates are stored for 2024-12-09 yet (latest is 2024-12-06)"
+        );
+
+        // Monday morning before publication falls back to Friday
+        assert!(ensure_published(day(9), day(6), day(9)).is_ok());
+
+        // Tuesday morning still needs Monday's rates, like a request for Monday would
+        assert_eq!(
+            ensure_published(day(10), day(6), day(10)).unwrap_err(),
+            "no rates are stored for 2024-12-10 yet (latest is 2024-12-06)"
+        );
+        assert!(ensure_published(day(10), day(9), day(10)).is_ok());
+
+        // Good Friday 2025 resolves to Thursday, also on the morning after Easter Monday
+        let thursday = date(2025, 4, 17);
+        assert!(ensure_published(date(2025, 4, 18), thursday, date(2025, 4, 18)).is_ok());
+        assert!(ensure_published(date(2025, 4, 18), thursday, date(2025, 4, 22)).is_ok());
+        assert!(ensure_published(date(2025, 4, 22), thursday, date(2025, 4, 22)).is_ok());
+
+        // The morning after Christmas Day and Boxing Day, 24 December is enough
+        assert!(ensure_published(day(27), day(24), day(27)).is_ok());
+    }
+}
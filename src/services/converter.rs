@@ -3,6 +3,9 @@ use crate::models::DailyRate;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
+/// Largest scale a Decimal can carry (digits after the decimal point)
+const MAX_SCALE: u32 = 28;
+
 /// Optimized O(1) currency conversion without full rebase
 /// Directly calculates cross-rate: (Base->To) / (Base->From)
 ///
@@ -18,45 +21,258 @@ pub fn convert_currency(
 ) -> Result<(Decimal, Decimal), ApiError> {
     let from = from.to_uppercase();
     let to = to.to_uppercase();
-    let base = &daily_rate.base;
 
     // Special case: same currency
     if from == to {
         return Ok((amount, Decimal::ONE));
     }
 
+    let conversion_rate = cross_rate(daily_rate, &from, &to)?;
+
+    // 4. Calculate final amount
+    let result = convert_amount(amount, conversion_rate)?;
+
+    Ok((result, conversion_rate))
+}
+
+/// Cross rate from `from` to `to` as used by convert_currency() (1 for the same currency)
+/// Split out so the rate alone can be cached and reused for any amount
+pub fn cross_rate(daily_rate: &DailyRate, from: &str, to: &str) -> Result<Decimal, ApiError> {
+    let from = from.to_uppercase();
+    let to = to.to_uppercase();
+
+    if from == to {
+        return Ok(Decimal::ONE);
+    }
+
     // 1. Get Base -> From rate (e.g., EUR -> USD)
-    let from_rate = if from == *base {
-        Decimal::ONE
-    } else {
-        *daily_rate
-            .rates
-            .get(&from)
-            .ok_or_else(|| ApiError::CurrencyNotFound(from.clone()))?
-    };
+    let from_rate = base_rate(daily_rate, &from)?;
 
     // 2. Get Base -> To rate (e.g., EUR -> JPY)
-    let to_rate = if to == *base {
-        Decimal::ONE
-    } else {
-        *daily_rate
-            .rates
-            .get(&to)
-            .ok_or_else(|| ApiError::CurrencyNotFound(to.clone()))?
-    };
-
-    // 3. Calculate cross-rate: to_rate / from_rate
-    // Example: JPY/USD = (EUR->JPY) / (EUR->USD) = 158.2 / 1.05
+    let to_rate = base_rate(daily_rate, &to)?;
+
+    divide_rates(&from, from_rate, &to, to_rate)
+}
+
+/// Convert `amount` of `from` into every currency in `targets`, looking up `from` once
+/// Each entry, keyed by uppercase code, matches convert_currency() for that target;
+/// the first unknown currency or failed calculation is returned as the error
+pub fn convert_many<S: AsRef<str>>(
+    daily_rate: &DailyRate,
+    from: &str,
+    amount: Decimal,
+    targets: &[S],
+) -> Result<HashMap<String, (Decimal, Decimal)>, ApiError> {
+    let from = from.to_uppercase();
+    let from_rate = base_rate(daily_rate, &from)?;
+
+    targets
+        .iter()
+        .map(|to| {
+            let to = to.as_ref().to_uppercase();
+            if to == from {
+                return Ok((to, (amount, Decimal::ONE)));
+            }
+
+            let rate = divide_rates(&from, from_rate, &to, base_rate(daily_rate, &to)?)?;
+            let result = convert_amount(amount, rate)?;
+            Ok((to, (result, rate)))
+        })
+        .collect()
+}
+
+/// Cross rate `to_rate / from_rate` from two base-relative rates
+/// Example: JPY/USD = (EUR->JPY) / (EUR->USD) = 158.2 / 1.05
+fn divide_rates(
+    from: &str,
+    from_rate: Decimal,
+    to: &str,
+    to_rate: Decimal,
+) -> Result<Decimal, ApiError> {
     let conversion_rate = to_rate.checked_div(from_rate).ok_or_else(|| {
         ApiError::CalculationError("Division by zero or overflow in conversion".to_string())
     })?;
+    if conversion_rate.is_zero() && !to_rate.is_zero() {
+        return Err(ApiError::CalculationError(format!(
+            "Cross rate {}->{} is too small to represent with {} decimal places",
+            from, to, MAX_SCALE
+        )));
+    }
 
-    // 4. Calculate final amount
-    let result = amount
-        .checked_mul(conversion_rate)
-        .ok_or_else(|| ApiError::CalculationError("Overflow in amount calculation".to_string()))?;
+    Ok(conversion_rate)
+}
 
-    Ok((result, conversion_rate))
+/// Multiply an amount by a conversion rate without silently losing the result
+/// Digits beyond the 28th decimal place are rounded away, but a result that
+/// overflows, or a non-zero product that would round to zero, is rejected
+pub fn convert_amount(amount: Decimal, rate: Decimal) -> Result<Decimal, ApiError> {
+    let result = amount.checked_mul(rate).ok_or_else(|| {
+        ApiError::CalculationError(format!(
+            "Result of {} x {} exceeds the largest representable amount",
+            amount, rate
+        ))
+    })?;
+
+    if result.is_zero() && !amount.is_zero() && !rate.is_zero() {
+        return Err(ApiError::CalculationError(format!(
+            "Result of {} x {} is too small to represent with {} decimal places",
+            amount, rate, MAX_SCALE
+        )));
+    }
+
+    Ok(result.round_dp(MAX_SCALE))
+}
+
+/// Source amount needed to receive `target` at `rate` (`target / rate`)
+/// The reverse of `convert_amount`; a zero rate is a CalculationError
+pub fn reverse_amount(target: Decimal, rate: Decimal) -> Result<Decimal, ApiError> {
+    if rate.is_zero() {
+        return Err(ApiError::CalculationError(
+            "Cannot derive a source amount from a zero rate".to_string(),
+        ));
+    }
+
+    let source = target.checked_div(rate).ok_or_else(|| {
+        ApiError::CalculationError(format!(
+            "Source amount for {} at rate {} exceeds the largest representable amount",
+            target, rate
+        ))
+    })?;
+
+    Ok(source.round_dp(MAX_SCALE))
+}
+
+/// Rate from the table's base currency to `code` (1 for the base itself)
+fn base_rate(daily_rate: &DailyRate, code: &str) -> Result<Decimal, ApiError> {
+    if code == daily_rate.base {
+        return Ok(Decimal::ONE);
+    }
+
+    daily_rate
+        .rates
+        .get(code)
+        .copied()
+        .ok_or_else(|| ApiError::CurrencyNotFound(code.to_string()))
+}
+
+/// How a conversion rate was triangulated through the base currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionPath {
+    /// Human-readable legs, e.g. `["USD->EUR (1/1.05)", "EUR->JPY (158.2)"]`
+    pub legs: Vec<String>,
+    /// Rate of the leg into the base currency (`1 / from_rate`), None without a pivot
+    pub intermediate_rate: Option<Decimal>,
+}
+
+/// Explain the legs used by convert_currency() for `from` -> `to`
+/// Same-currency conversions have a single `1` leg; conversions involving
+/// the base currency have a single direct leg and no intermediate rate
+pub fn explain_conversion(
+    daily_rate: &DailyRate,
+    from: &str,
+    to: &str,
+) -> Result<ConversionPath, ApiError> {
+    let from = from.to_uppercase();
+    let to = to.to_uppercase();
+    let base = &daily_rate.base;
+
+    let from_rate = base_rate(daily_rate, &from)?;
+    let to_rate = base_rate(daily_rate, &to)?;
+
+    if from == to {
+        return Ok(ConversionPath {
+            legs: vec![format!("{}->{} (1)", from, to)],
+            intermediate_rate: None,
+        });
+    }
+
+    if from == *base {
+        return Ok(ConversionPath {
+            legs: vec![format!("{}->{} ({})", from, to, to_rate)],
+            intermediate_rate: None,
+        });
+    }
+
+    let into_base = format!("{}->{} (1/{})", from, base, from_rate);
+    if to == *base {
+        return Ok(ConversionPath {
+            legs: vec![into_base],
+            intermediate_rate: None,
+        });
+    }
+
+    Ok(ConversionPath {
+        legs: vec![into_base, format!("{}->{} ({})", base, to, to_rate)],
+        intermediate_rate: Some(inverse_rate(from_rate)?),
+    })
+}
+
+/// Rate from `from` to `to` triangulated through `via` instead of the table's base
+/// Composes convert_currency() for `from`->`via` and `via`->`to`; each leg is divided
+/// separately, so the product can differ from cross_rate() in the last decimal places
+pub fn via_rate(
+    daily_rate: &DailyRate,
+    from: &str,
+    via: &str,
+    to: &str,
+) -> Result<(Decimal, ConversionPath), ApiError> {
+    let from = from.to_uppercase();
+    let via = via.to_uppercase();
+    let to = to.to_uppercase();
+
+    let (_, into_via) = convert_currency(daily_rate, &from, &via, Decimal::ONE)?;
+    let (_, out_of_via) = convert_currency(daily_rate, &via, &to, Decimal::ONE)?;
+    let rate = into_via.checked_mul(out_of_via).ok_or_else(|| {
+        ApiError::CalculationError(format!(
+            "Overflow composing {}->{}->{} rates",
+            from, via, to
+        ))
+    })?;
+
+    Ok((
+        rate,
+        ConversionPath {
+            legs: vec![
+                format!("{}->{} ({})", from, via, into_via),
+                format!("{}->{} ({})", via, to, out_of_via),
+            ],
+            intermediate_rate: Some(into_via),
+        },
+    ))
+}
+
+/// Apply a retail spread in basis points to a market rate
+/// The customer receives `spread_bps / 100` percent less of the target currency
+/// (e.g. 50 bps turns 1.2000 into 1.1940); a zero spread returns the rate unchanged
+pub fn apply_spread(rate: Decimal, spread_bps: u32) -> Result<Decimal, ApiError> {
+    if spread_bps == 0 {
+        return Ok(rate);
+    }
+
+    let factor = Decimal::ONE - Decimal::new(spread_bps as i64, 4);
+    rate.checked_mul(factor)
+        .ok_or_else(|| ApiError::CalculationError("Overflow applying spread".to_string()))
+}
+
+/// Inverse of a conversion rate (e.g. EUR/USD -> USD/EUR)
+/// A zero rate yields a CalculationError instead of panicking
+pub fn inverse_rate(rate: Decimal) -> Result<Decimal, ApiError> {
+    Decimal::ONE.checked_div(rate).ok_or_else(|| {
+        ApiError::CalculationError("Division by zero or overflow in inverse rate".to_string())
+    })
+}
+
+/// Absolute and percentage change from `start` to `end` (e.g. 1.05 -> 1.0626 is +1.2%)
+pub fn rate_change(start: Decimal, end: Decimal) -> Result<(Decimal, Decimal), ApiError> {
+    let change = end - start;
+    let percent = change
+        .checked_div(start)
+        .and_then(|ratio| ratio.checked_mul(Decimal::ONE_HUNDRED))
+        .ok_or_else(|| {
+            ApiError::CalculationError("Division by zero or overflow in rate change".to_string())
+        })?;
+
+    Ok((change, percent))
 }
 
 /// Rebase exchange rates from current base to any other currency
@@ -189,6 +405,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_convert_many_matches_convert_currency() {
+        let rates = create_test_rates();
+        let targets = ["EUR", "jpy", "GBP", "USD"];
+
+        let results = convert_many(&rates, "usd", dec!(250), &targets).unwrap();
+        assert_eq!(results.len(), targets.len());
+        for to in targets {
+            assert_eq!(
+                results[&to.to_uppercase()],
+                convert_currency(&rates, "USD", to, dec!(250)).unwrap()
+            );
+        }
+
+        assert!(matches!(
+            convert_many(&rates, "USD", dec!(1), &["JPY", "XXX"]),
+            Err(ApiError::CurrencyNotFound(code)) if code == "XXX"
+        ));
+        assert!(matches!(
+            convert_many(&rates, "XXX", dec!(1), &["JPY"]),
+            Err(ApiError::CurrencyNotFound(code)) if code == "XXX"
+        ));
+    }
+
+    #[test]
+    fn test_via_rate_composes_two_legs() {
+        let rates = create_test_rates();
+        let (rate, path) = via_rate(&rates, "usd", "GBP", "JPY").unwrap();
+
+        let into_gbp = dec!(0.85) / dec!(1.05);
+        let out_of_gbp = dec!(158.2) / dec!(0.85);
+        assert_eq!(rate, into_gbp * out_of_gbp);
+        assert_eq!(path.intermediate_rate, Some(into_gbp));
+        assert_eq!(path.legs.len(), 2);
+        assert!(path.legs[0].starts_with("USD->GBP"));
+
+        // Each leg is rounded to 28 places on its own, so only the leading digits agree
+        let direct = cross_rate(&rates, "USD", "JPY").unwrap();
+        assert_eq!(rate.round_dp(20), direct.round_dp(20));
+
+        // Through the base currency the legs are the usual ones
+        let (rate, _) = via_rate(&rates, "USD", "EUR", "JPY").unwrap();
+        assert_eq!(rate, (dec!(1) / dec!(1.05)) * dec!(158.2));
+
+        assert!(matches!(
+            via_rate(&rates, "USD", "XXX", "JPY"),
+            Err(ApiError::CurrencyNotFound(code)) if code == "XXX"
+        ));
+    }
+
     #[test]
     fn test_rebase_same_currency() {
         let rates = create_test_rates();
@@ -256,4 +522,170 @@ mod tests {
 
         assert_eq!(usd_to_gbp, expected);
     }
+
+    #[test]
+    fn test_convert_tiny_amount_keeps_precision() {
+        let rates = create_test_rates();
+        let (result, _) = convert_currency(&rates, "EUR", "USD", dec!(0.000000000000001)).unwrap();
+
+        assert_eq!(result, dec!(0.00000000000000105));
+    }
+
+    #[test]
+    fn test_convert_large_amount_overflows_cleanly() {
+        let rates = create_test_rates();
+        let result = convert_currency(&rates, "USD", "JPY", Decimal::MAX);
+
+        assert!(matches!(result, Err(ApiError::CalculationError(_))));
+    }
+
+    #[test]
+    fn test_convert_extreme_cross_rates() {
+        let mut rates = create_test_rates();
+        rates.rates.insert("AAA".to_string(), dec!(0.0000000000001));
+        rates.rates.insert("ZZZ".to_string(), dec!(10000000000000));
+
+        // ZZZ->AAA = 1e-26: representable, but tiny amounts underflow
+        let (result, rate) = convert_currency(&rates, "ZZZ", "AAA", dec!(100)).unwrap();
+        assert_eq!(rate, dec!(0.00000000000000000000000001));
+        assert_eq!(result, dec!(0.000000000000000000000001));
+        assert!(matches!(
+            convert_currency(&rates, "ZZZ", "AAA", dec!(0.0001)),
+            Err(ApiError::CalculationError(_))
+        ));
+
+        // AAA->ZZZ = 1e26: fine for small amounts, overflows for large ones
+        let (result, _) = convert_currency(&rates, "AAA", "ZZZ", dec!(0.5)).unwrap();
+        assert_eq!(result, dec!(50000000000000000000000000));
+        assert!(matches!(
+            convert_currency(&rates, "AAA", "ZZZ", dec!(1000000000)),
+            Err(ApiError::CalculationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_convert_amount_caps_scale() {
+        let result = convert_amount(
+            dec!(0.0000000000000001),
+            dec!(1.2345678901234567890123456789),
+        )
+        .unwrap();
+
+        assert!(result.scale() <= MAX_SCALE);
+        assert_eq!(result, dec!(0.0000000000000001234567890123));
+    }
+
+    #[test]
+    fn test_rate_change() {
+        assert_eq!(
+            rate_change(dec!(1.05), dec!(1.0626)).unwrap(),
+            (dec!(0.0126), dec!(1.2))
+        );
+        assert_eq!(
+            rate_change(dec!(2), dec!(1.5)).unwrap(),
+            (dec!(-0.5), dec!(-25))
+        );
+        assert!(rate_change(Decimal::ZERO, dec!(1)).is_err());
+    }
+
+    #[test]
+    fn test_reverse_amount() {
+        assert_eq!(reverse_amount(dec!(105), dec!(1.05)).unwrap(), dec!(100));
+        assert!(matches!(
+            reverse_amount(dec!(100), Decimal::ZERO),
+            Err(ApiError::CalculationError(_))
+        ));
+        assert!(matches!(
+            reverse_amount(Decimal::MAX, dec!(0.5)),
+            Err(ApiError::CalculationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_inverse_rate() {
+        assert_eq!(inverse_rate(dec!(1.25)).unwrap(), dec!(0.8));
+        assert_eq!(inverse_rate(Decimal::ONE).unwrap(), Decimal::ONE);
+    }
+
+    #[test]
+    fn test_inverse_rate_zero() {
+        assert!(matches!(
+            inverse_rate(Decimal::ZERO),
+            Err(ApiError::CalculationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_explain_cross_conversion() {
+        let rates = create_test_rates();
+
+        let path = explain_conversion(&rates, "usd", "JPY").unwrap();
+
+        assert_eq!(path.legs, vec!["USD->EUR (1/1.05)", "EUR->JPY (158.2)"]);
+        assert_eq!(path.intermediate_rate, Some(dec!(1) / dec!(1.05)));
+    }
+
+    #[test]
+    fn test_explain_trivial_paths() {
+        let rates = create_test_rates();
+
+        let same = explain_conversion(&rates, "USD", "USD").unwrap();
+        assert_eq!(same.legs, vec!["USD->USD (1)"]);
+        assert_eq!(same.intermediate_rate, None);
+
+        let from_base = explain_conversion(&rates, "EUR", "USD").unwrap();
+        assert_eq!(from_base.legs, vec!["EUR->USD (1.05)"]);
+
+        let to_base = explain_conversion(&rates, "USD", "EUR").unwrap();
+        assert_eq!(to_base.legs, vec!["USD->EUR (1/1.05)"]);
+        assert_eq!(to_base.intermediate_rate, None);
+    }
+
+    /// USD-based table as another provider might store it, without the base in `rates`
+    fn create_usd_rates() -> DailyRate {
+        DailyRate {
+            date: "2024-12-04".to_string(),
+            base: "USD".to_string(),
+            rates: HashMap::from([
+                ("EUR".to_string(), dec!(0.95)),
+                ("JPY".to_string(), dec!(150)),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_non_eur_base_conversions() {
+        let rates = create_usd_rates();
+
+        let (result, rate) = convert_currency(&rates, "USD", "JPY", dec!(2)).unwrap();
+        assert_eq!((result, rate), (dec!(300), dec!(150)));
+
+        let (_, rate) = convert_currency(&rates, "EUR", "JPY", dec!(1)).unwrap();
+        assert_eq!(rate, dec!(150) / dec!(0.95));
+
+        let path = explain_conversion(&rates, "EUR", "JPY").unwrap();
+        assert_eq!(path.legs, vec!["EUR->USD (1/0.95)", "USD->JPY (150)"]);
+    }
+
+    #[test]
+    fn test_rebase_non_eur_base() {
+        let rebased = rebase_rates(&create_usd_rates(), "EUR").unwrap();
+
+        assert_eq!(rebased.base, "EUR");
+        assert_eq!(rebased.rates["USD"], dec!(1) / dec!(0.95));
+        assert_eq!(rebased.rates["JPY"], dec!(150) / dec!(0.95));
+        assert!(!rebased.rates.contains_key("EUR"));
+    }
+
+    #[test]
+    fn test_apply_spread() {
+        assert_eq!(apply_spread(dec!(1.2000), 50).unwrap(), dec!(1.1940));
+        assert_eq!(apply_spread(dec!(158.2), 100).unwrap(), dec!(156.618));
+    }
+
+    #[test]
+    fn test_zero_spread_is_identity() {
+        let rate = dec!(0.8570449091532396297565992458);
+        assert_eq!(apply_spread(rate, 0).unwrap(), rate);
+    }
 }
@@ -1,52 +1,330 @@
 use crate::error::ApiError;
-use crate::models::{DailyRate, EcbEnvelope};
+use crate::models::{DailyRate, EcbEnvelope, EcbHistoryEnvelope, EcbTimeCube, SkippedDay};
+use crate::services::{ConditionalFetch, RateProvider};
+use crate::telemetry::ECB_FETCH_ATTEMPTS_TOTAL;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use reqwest::header::{IF_MODIFIED_SINCE, LAST_MODIFIED};
+use reqwest::redirect::Policy;
+use std::sync::Mutex;
 use std::time::Duration;
 
-const USER_AGENT: &str = "Currency-API/0.1.0";
-const TIMEOUT_SECONDS: u64 = 30;
+/// Default User-Agent, versioned with the crate
+pub const DEFAULT_USER_AGENT: &str = concat!("Currency-API/", env!("CARGO_PKG_VERSION"));
+/// Default HTTP request timeout in seconds
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Default number of redirects followed before a request fails
+pub const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+/// Default minimum number of currencies a daily Cube must contain
+pub const DEFAULT_MIN_RATES: usize = 10;
+
+/// HTTP client settings for outbound ECB requests
+#[derive(Debug, Clone)]
+pub struct HttpClientOptions {
+    pub timeout: Duration,
+    pub user_agent: String,
+    /// Proxy for all outbound requests (http, https or socks5 URL)
+    pub proxy: Option<String>,
+    /// Redirects followed per request; with 0 a redirect response fails the fetch
+    pub max_redirects: usize,
+}
+
+impl Default for HttpClientOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+        }
+    }
+}
+
+impl HttpClientOptions {
+    /// Build an HTTP client with these settings
+    /// gzip and deflate bodies are requested and decompressed transparently
+    pub(crate) fn build_client(&self) -> Result<reqwest::Client, ApiError> {
+        let redirect = match self.max_redirects {
+            0 => Policy::none(),
+            max => Policy::limited(max),
+        };
+
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.user_agent.as_str())
+            .timeout(self.timeout)
+            .redirect(redirect)
+            .gzip(true)
+            .deflate(true);
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|e| {
+                ApiError::EcbFetchError(format!("Invalid proxy '{}': {}", proxy, e))
+            })?;
+            builder = builder.proxy(proxy);
+        }
+
+        let client = builder
+            .build()
+            .map_err(|e| ApiError::EcbFetchError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(client)
+    }
+}
+
+/// Retry policy for transient ECB fetch failures (exponential backoff)
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each subsequent retry
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay to wait after the given failed attempt (1-based): base, 2*base, 4*base, ...
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor)
+    }
+
+    /// Longest a fetch can take when every attempt runs into `timeout`: all attempts plus the backoff between them
+    pub fn worst_case(&self, timeout: Duration) -> Duration {
+        (1..self.max_attempts)
+            .map(|attempt| self.delay_for_attempt(attempt))
+            .fold(
+                timeout.saturating_mul(self.max_attempts.max(1)),
+                Duration::saturating_add,
+            )
+    }
+
+    /// Whether another attempt should be made after a failed attempt
+    pub fn should_retry(&self, attempt: u32, retryable: bool) -> bool {
+        retryable && attempt < self.max_attempts
+    }
+}
+
+/// Failure of a single fetch attempt, classified for the retry decision
+struct FetchFailure {
+    error: ApiError,
+    retryable: bool,
+}
+
+/// Server-side and throttling statuses are worth retrying, other 4xx are not
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// Timeouts, connection failures and interrupted bodies are transient
+fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_request() || err.is_body()
+}
+
+/// Outcome of a successful GET
+enum Download {
+    /// Decoded body text and its Last-Modified header, if any
+    Body {
+        text: String,
+        last_modified: Option<String>,
+    },
+    /// 304 answer to a conditional request
+    NotModified,
+}
+
+/// Perform a single HTTP GET, returning the decoded body text
+/// With `if_modified_since` set, a 304 answer is returned as `Download::NotModified`
+async fn fetch_text(
+    client: &reqwest::Client,
+    url: &str,
+    if_modified_since: Option<&str>,
+) -> Result<Download, FetchFailure> {
+    let mut request = client.get(url);
+    if let Some(since) = if_modified_since {
+        request = request.header(IF_MODIFIED_SINCE, since);
+    }
+
+    let response = request.send().await.map_err(|e| FetchFailure {
+        retryable: is_retryable_error(&e),
+        error: ApiError::EcbFetchError(format!("HTTP request failed: {}", e)),
+    })?;
+
+    // A moved feed keeps working, but the configured URL should be updated
+    if response.url().as_str() != url {
+        tracing::warn!(
+            "{} redirected to {}, consider updating the configured URL",
+            url,
+            response.url()
+        );
+    }
+
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED && if_modified_since.is_some() {
+        return Ok(Download::NotModified);
+    }
+    if !status.is_success() {
+        return Err(FetchFailure {
+            retryable: is_retryable_status(status),
+            error: ApiError::EcbFetchError(format!("ECB returned status: {}", status)),
+        });
+    }
+
+    let last_modified = response
+        .headers()
+        .get(LAST_MODIFIED)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let text = response.text().await.map_err(|e| FetchFailure {
+        retryable: is_retryable_error(&e),
+        error: ApiError::EcbFetchError(format!("Failed to read response: {}", e)),
+    })?;
+
+    Ok(Download::Body {
+        text,
+        last_modified,
+    })
+}
+
+/// GET a document as text, retrying transient failures per the retry policy
+pub(crate) async fn fetch_text_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    retry_policy: &RetryPolicy,
+) -> Result<String, ApiError> {
+    match fetch_with_retry(client, url, None, retry_policy).await? {
+        Download::Body { text, .. } => Ok(text),
+        // Only conditional requests are answered with 304
+        Download::NotModified => Err(ApiError::EcbFetchError(
+            "Unexpected 304 Not Modified".to_string(),
+        )),
+    }
+}
+
+/// GET a document, optionally conditional, retrying transient failures per the retry policy
+async fn fetch_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    if_modified_since: Option<&str>,
+    retry_policy: &RetryPolicy,
+) -> Result<Download, ApiError> {
+    let mut attempt = 1;
+    loop {
+        let result = fetch_text(client, url, if_modified_since).await;
+
+        let outcome = match &result {
+            Ok(Download::Body { .. }) => "success",
+            Ok(Download::NotModified) => "not_modified",
+            Err(_) => "failure",
+        };
+        metrics::counter!(ECB_FETCH_ATTEMPTS_TOTAL, "outcome" => outcome).increment(1);
+
+        match result {
+            Ok(download) => return Ok(download),
+            Err(failure) if retry_policy.should_retry(attempt, failure.retryable) => {
+                let delay = retry_policy.delay_for_attempt(attempt);
+                tracing::warn!(
+                    "ECB fetch attempt {}/{} failed, retrying in {:?}: {}",
+                    attempt,
+                    retry_policy.max_attempts,
+                    delay,
+                    failure.error
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(failure) => return Err(failure.error),
+        }
+    }
+}
+
+/// Last-Modified of the previous successful daily fetch and the rate date it carried
+#[derive(Debug, Clone)]
+struct CacheValidator {
+    last_modified: String,
+    date: String,
+}
 
 pub struct EcbFetcher {
     pub(crate) client: reqwest::Client,
     pub(crate) ecb_url: String,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) min_rates: usize,
+    /// Kept across scheduled runs to send If-Modified-Since
+    validator: Mutex<Option<CacheValidator>>,
 }
 
 impl EcbFetcher {
-    pub fn new(ecb_url: String) -> Self {
-        let client = reqwest::Client::builder()
-            .user_agent(USER_AGENT)
-            .timeout(Duration::from_secs(TIMEOUT_SECONDS))
-            .build()
-            .expect("Failed to create HTTP client");
+    pub fn new(
+        ecb_url: String,
+        retry_policy: RetryPolicy,
+        options: &HttpClientOptions,
+    ) -> Result<Self, ApiError> {
+        let client = options.build_client()?;
 
-        Self { client, ecb_url }
+        Ok(Self {
+            client,
+            ecb_url,
+            retry_policy,
+            min_rates: DEFAULT_MIN_RATES,
+            validator: Mutex::new(None),
+        })
     }
 
-    /// Fetch and parse ECB XML data into DailyRate
-    pub async fn fetch_rates(&self) -> Result<DailyRate, ApiError> {
+    /// Reject payloads with fewer than `min_rates` currencies (EUR not counted)
+    pub fn with_min_rates(mut self, min_rates: usize) -> Self {
+        self.min_rates = min_rates;
+        self
+    }
+
+    /// Fetch the daily ECB XML, conditionally when `if_modified_since` is set
+    /// gzip and deflate encoded responses are decompressed transparently
+    async fn fetch_daily(&self, if_modified_since: Option<&str>) -> Result<Download, ApiError> {
         tracing::info!("Fetching exchange rates from ECB: {}", self.ecb_url);
 
-        // Fetch XML
-        let response = self
-            .client
-            .get(&self.ecb_url)
-            .send()
-            .await
-            .map_err(|e| ApiError::EcbFetchError(format!("HTTP request failed: {}", e)))?;
+        fetch_with_retry(
+            &self.client,
+            &self.ecb_url,
+            if_modified_since,
+            &self.retry_policy,
+        )
+        .await
+    }
 
-        if !response.status().is_success() {
-            return Err(ApiError::EcbFetchError(format!(
-                "ECB returned status: {}",
-                response.status()
-            )));
-        }
+    /// Parse a downloaded daily document and remember its Last-Modified
+    fn accept_daily(
+        &self,
+        text: String,
+        last_modified: Option<String>,
+    ) -> Result<(DailyRate, Option<String>), ApiError> {
+        // Parse XML (parse failures are never retried)
+        let rates = self.parse_ecb_xml(&text)?;
 
-        let xml_content = response
-            .text()
-            .await
-            .map_err(|e| ApiError::EcbFetchError(format!("Failed to read response: {}", e)))?;
+        *self.validator.lock().expect("ECB validator lock poisoned") =
+            last_modified.map(|last_modified| CacheValidator {
+                last_modified,
+                date: rates.date.clone(),
+            });
+
+        Ok((rates, Some(text)))
+    }
 
-        // Parse XML
-        self.parse_ecb_xml(&xml_content)
+    /// Fetch and parse a multi-day ECB history document (e.g. the 90-day feed)
+    pub async fn fetch_history(&self, url: &str) -> Result<Vec<DailyRate>, ApiError> {
+        tracing::info!("Fetching exchange rate history from ECB: {}", url);
+
+        let xml_content = fetch_text_with_retry(&self.client, url, &self.retry_policy).await?;
+        self.parse_ecb_history_xml(&xml_content)
     }
 
     /// Parse ECB XML format into DailyRate
@@ -55,14 +333,7 @@ impl EcbFetcher {
         let envelope: EcbEnvelope = quick_xml::de::from_str(xml)
             .map_err(|e| ApiError::XmlParseError(format!("Failed to parse XML: {}", e)))?;
 
-        let time_cube = envelope.cube.time_cube;
-        let daily_rate = DailyRate::from_ecb_data(time_cube.time, time_cube.rates)
-            .map_err(|e| ApiError::XmlParseError(e))?;
-
-        // Validate date format
-        daily_rate
-            .validate_date()
-            .map_err(|e| ApiError::XmlParseError(e))?;
+        let daily_rate = self.parse_time_cube(envelope.cube.time_cube)?;
 
         tracing::info!(
             "Successfully parsed {} exchange rates for {}",
@@ -72,6 +343,135 @@ impl EcbFetcher {
 
         Ok(daily_rate)
     }
+
+    /// Parse multi-day ECB XML into one DailyRate per day, newest first as published
+    fn parse_ecb_history_xml(&self, xml: &str) -> Result<Vec<DailyRate>, ApiError> {
+        parse_ecb_archive(xml, self.min_rates).map(|archive| archive.days)
+    }
+
+    /// Convert a single `<Cube time="...">` element into a validated DailyRate
+    fn parse_time_cube(&self, time_cube: EcbTimeCube) -> Result<DailyRate, ApiError> {
+        parse_time_cube(time_cube, self.min_rates)
+    }
+}
+
+/// Days of an ECB reference-rate document, split into valid and rejected ones
+#[derive(Debug, Default)]
+pub struct EcbArchive {
+    pub days: Vec<DailyRate>,
+    pub rejected: Vec<SkippedDay>,
+}
+
+/// Parse an ECB reference-rate document holding one or more days
+/// Accepts both the daily and the 90-day/full-history files, since each day is
+/// its own `<Cube time="...">`; days failing validation are rejected with their reason
+/// rather than failing the whole document
+pub fn parse_ecb_archive(xml: &str, min_rates: usize) -> Result<EcbArchive, ApiError> {
+    let envelope: EcbHistoryEnvelope = quick_xml::de::from_str(xml)
+        .map_err(|e| ApiError::XmlParseError(format!("Failed to parse XML: {}", e)))?;
+
+    let mut archive = EcbArchive::default();
+    for time_cube in envelope.cube.days {
+        let date = time_cube.time.clone();
+        match parse_time_cube(time_cube, min_rates) {
+            Ok(daily_rate) => archive.days.push(daily_rate),
+            Err(e) => {
+                tracing::warn!("Skipping history day: {}", e);
+                let reason = match e {
+                    ApiError::XmlParseError(msg) => msg,
+                    e => e.to_string(),
+                };
+                archive.rejected.push(SkippedDay { date, reason });
+            }
+        }
+    }
+
+    tracing::info!(
+        "Successfully parsed {} days of exchange rate history",
+        archive.days.len()
+    );
+
+    Ok(archive)
+}
+
+fn parse_time_cube(time_cube: EcbTimeCube, min_rates: usize) -> Result<DailyRate, ApiError> {
+    // A well-formed but empty or partial Cube (seen on holidays) must not replace good data
+    if time_cube.rates.len() < min_rates {
+        return Err(ApiError::XmlParseError(format!(
+            "ECB payload for {} contains {} rates, expected at least {}",
+            time_cube.time,
+            time_cube.rates.len(),
+            min_rates
+        )));
+    }
+
+    let daily_rate = DailyRate::from_ecb_data(time_cube.time, time_cube.rates)
+        .map_err(ApiError::XmlParseError)?;
+
+    // Validate date format
+    daily_rate
+        .validate_date()
+        .map_err(ApiError::XmlParseError)?;
+
+    Ok(daily_rate)
+}
+
+#[async_trait]
+impl RateProvider for EcbFetcher {
+    fn name(&self) -> &str {
+        &self.ecb_url
+    }
+
+    /// Fetch and parse ECB XML data into DailyRate
+    /// Transient failures are retried according to the retry policy
+    async fn fetch_rates(&self) -> Result<DailyRate, ApiError> {
+        self.fetch_rates_with_raw().await.map(|(rates, _)| rates)
+    }
+
+    async fn fetch_rates_with_raw(&self) -> Result<(DailyRate, Option<String>), ApiError> {
+        match self.fetch_daily(None).await? {
+            Download::Body {
+                text,
+                last_modified,
+            } => self.accept_daily(text, last_modified),
+            Download::NotModified => Err(ApiError::EcbFetchError(
+                "Unexpected 304 Not Modified".to_string(),
+            )),
+        }
+    }
+
+    /// Sends If-Modified-Since with the Last-Modified of the previous fetch, so an
+    /// unchanged document is neither downloaded nor parsed again
+    async fn fetch_rates_if_modified(&self) -> Result<ConditionalFetch, ApiError> {
+        let validator = self
+            .validator
+            .lock()
+            .expect("ECB validator lock poisoned")
+            .clone();
+
+        let Some(validator) = validator else {
+            let (rates, raw) = self.fetch_rates_with_raw().await?;
+            return Ok(ConditionalFetch::Fetched(rates, raw));
+        };
+
+        match self.fetch_daily(Some(&validator.last_modified)).await? {
+            Download::Body {
+                text,
+                last_modified,
+            } => {
+                let (rates, raw) = self.accept_daily(text, last_modified)?;
+                Ok(ConditionalFetch::Fetched(rates, raw))
+            }
+            Download::NotModified => {
+                tracing::info!(
+                    "ECB rates unchanged since {} ({})",
+                    validator.last_modified,
+                    validator.date
+                );
+                Ok(ConditionalFetch::NotModified(validator.date))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -91,7 +491,13 @@ mod tests {
     </Cube>
 </gesmes:Envelope>"#;
 
-        let fetcher = EcbFetcher::new("http://example.com".to_string());
+        let fetcher = EcbFetcher::new(
+            "http://example.com".to_string(),
+            RetryPolicy::default(),
+            &HttpClientOptions::default(),
+        )
+        .unwrap()
+        .with_min_rates(3);
         let result = fetcher.parse_ecb_xml(xml).unwrap();
 
         use rust_decimal_macros::dec;
@@ -103,4 +509,298 @@ mod tests {
         assert_eq!(result.rates["JPY"], dec!(158.23));
         assert_eq!(result.rates["EUR"], dec!(1.0)); // EUR added automatically
     }
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(500));
+        assert_eq!(policy.delay_for_attempt(2), Duration::from_millis(1000));
+        assert_eq!(policy.delay_for_attempt(3), Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_retry_worst_case() {
+        let policy = RetryPolicy::default();
+        assert_eq!(
+            policy.worst_case(Duration::from_secs(30)),
+            Duration::from_millis(91_500)
+        );
+
+        let single = RetryPolicy {
+            max_attempts: 1,
+            base_delay: Duration::from_millis(500),
+        };
+        assert_eq!(
+            single.worst_case(Duration::from_secs(30)),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_should_retry_respects_max_attempts() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry(1, true));
+        assert!(policy.should_retry(2, true));
+        assert!(!policy.should_retry(3, true));
+        // Non-retryable failures are never retried
+        assert!(!policy.should_retry(1, false));
+    }
+
+    #[test]
+    fn test_retryable_status_classification() {
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn test_parse_ecb_xml_rejects_partial_cube() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <Cube>
+        <Cube time="2024-12-25">
+            <Cube currency="USD" rate="1.0534"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+
+        let fetcher = EcbFetcher::new(
+            "http://example.com".to_string(),
+            RetryPolicy::default(),
+            &HttpClientOptions::default(),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            fetcher.parse_ecb_xml(xml),
+            Err(ApiError::XmlParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_ecb_history_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <Cube>
+        <Cube time="2024-12-04">
+            <Cube currency="USD" rate="1.0534"/>
+            <Cube currency="JPY" rate="158.23"/>
+        </Cube>
+        <Cube time="2024-12-03">
+            <Cube currency="USD" rate="1.0501"/>
+        </Cube>
+        <Cube time="2024-12-02">
+            <Cube currency="USD" rate="1.0497"/>
+            <Cube currency="JPY" rate="157.80"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+
+        let fetcher = EcbFetcher::new(
+            "http://example.com".to_string(),
+            RetryPolicy::default(),
+            &HttpClientOptions::default(),
+        )
+        .unwrap()
+        .with_min_rates(2);
+        let days = fetcher.parse_ecb_history_xml(xml).unwrap();
+
+        use rust_decimal_macros::dec;
+
+        // The partial 2024-12-03 Cube is skipped
+        let dates: Vec<&str> = days.iter().map(|day| day.date.as_str()).collect();
+        assert_eq!(dates, vec!["2024-12-04", "2024-12-02"]);
+        assert_eq!(days[1].rates["JPY"], dec!(157.80));
+        assert_eq!(days[1].rates["EUR"], dec!(1.0));
+    }
+
+    #[test]
+    fn test_parse_ecb_archive_reports_rejected_days() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <Cube>
+        <Cube time="2024-12-04">
+            <Cube currency="USD" rate="1.0534"/>
+            <Cube currency="JPY" rate="158.23"/>
+        </Cube>
+        <Cube time="2024-12-03">
+            <Cube currency="USD" rate="1.0501"/>
+        </Cube>
+        <Cube time="2024-12-02">
+            <Cube currency="USD" rate="1.0497"/>
+            <Cube currency="usd" rate="1.0497"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+
+        let archive = parse_ecb_archive(xml, 2).unwrap();
+
+        assert_eq!(archive.days.len(), 1);
+        let dates: Vec<&str> = archive
+            .rejected
+            .iter()
+            .map(|day| day.date.as_str())
+            .collect();
+        assert_eq!(dates, vec!["2024-12-03", "2024-12-02"]);
+        assert_eq!(
+            archive.rejected[0].reason,
+            "ECB payload for 2024-12-03 contains 1 rates, expected at least 2"
+        );
+        assert!(!archive.rejected[1].reason.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ecb_archive_accepts_daily_file() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <Cube>
+        <Cube time="2024-12-04">
+            <Cube currency="USD" rate="1.0534"/>
+            <Cube currency="JPY" rate="158.23"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+
+        let archive = parse_ecb_archive(xml, 2).unwrap();
+
+        assert_eq!(archive.days.len(), 1);
+        assert_eq!(archive.days[0].date, "2024-12-04");
+        assert_eq!(archive.days[0].rates.len(), 3);
+        assert!(archive.rejected.is_empty());
+
+        assert!(matches!(
+            parse_ecb_archive("not xml", 2),
+            Err(ApiError::XmlParseError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_conditional_fetch_sends_last_modified_back() {
+        use axum::{Router, http::HeaderMap, http::header, response::IntoResponse, routing::get};
+
+        const LAST_MODIFIED_AT: &str = "Wed, 04 Dec 2024 15:00:00 GMT";
+
+        async fn daily(headers: HeaderMap) -> impl IntoResponse {
+            if headers.get(header::IF_MODIFIED_SINCE).map(|v| v.as_bytes())
+                == Some(LAST_MODIFIED_AT.as_bytes())
+            {
+                return StatusCode::NOT_MODIFIED.into_response();
+            }
+
+            let xml = r#"<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <Cube>
+        <Cube time="2024-12-04">
+            <Cube currency="USD" rate="1.0534"/>
+            <Cube currency="JPY" rate="158.23"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+            ([(header::LAST_MODIFIED, LAST_MODIFIED_AT)], xml).into_response()
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, Router::new().route("/daily.xml", get(daily)))
+                .await
+                .unwrap();
+        });
+
+        let fetcher = EcbFetcher::new(
+            format!("http://{}/daily.xml", addr),
+            RetryPolicy::default(),
+            &HttpClientOptions::default(),
+        )
+        .unwrap()
+        .with_min_rates(2);
+
+        match fetcher.fetch_rates_if_modified().await.unwrap() {
+            ConditionalFetch::Fetched(rates, raw) => {
+                assert_eq!(rates.date, "2024-12-04");
+                assert!(raw.is_some());
+            }
+            other => panic!("Expected fetched rates, got {:?}", other),
+        }
+
+        match fetcher.fetch_rates_if_modified().await.unwrap() {
+            ConditionalFetch::NotModified(date) => assert_eq!(date, "2024-12-04"),
+            other => panic!("Expected not modified, got {:?}", other),
+        }
+
+        // Unconditional fetches still download the document
+        assert_eq!(fetcher.fetch_rates().await.unwrap().date, "2024-12-04");
+    }
+
+    #[tokio::test]
+    async fn test_follows_redirect_to_gzip_encoded_feed() {
+        use axum::{
+            Router,
+            http::{HeaderMap, header},
+            response::{IntoResponse, Redirect},
+            routing::get,
+        };
+        use tower_http::compression::CompressionLayer;
+
+        async fn moved() -> Redirect {
+            Redirect::permanent("/new/daily.xml")
+        }
+
+        async fn daily(headers: HeaderMap) -> impl IntoResponse {
+            let accepts_gzip = headers
+                .get(header::ACCEPT_ENCODING)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.contains("gzip"));
+            if !accepts_gzip {
+                return StatusCode::NOT_ACCEPTABLE.into_response();
+            }
+
+            let xml = r#"<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <Cube>
+        <Cube time="2024-12-04">
+            <Cube currency="USD" rate="1.0534"/>
+            <Cube currency="JPY" rate="158.23"/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+            ([(header::CONTENT_TYPE, "application/xml")], xml).into_response()
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/daily.xml", get(moved))
+            .route("/new/daily.xml", get(daily))
+            .layer(CompressionLayer::new());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let fetcher = |max_redirects| {
+            EcbFetcher::new(
+                format!("http://{}/daily.xml", addr),
+                RetryPolicy::default(),
+                &HttpClientOptions {
+                    max_redirects,
+                    ..HttpClientOptions::default()
+                },
+            )
+            .unwrap()
+            .with_min_rates(2)
+        };
+
+        let (rates, raw) = fetcher(DEFAULT_MAX_REDIRECTS)
+            .fetch_rates_with_raw()
+            .await
+            .unwrap();
+        assert_eq!(rates.date, "2024-12-04");
+        assert!(raw.unwrap().starts_with("<gesmes:Envelope"));
+
+        // Redirect responses are not retried
+        match fetcher(0).fetch_rates().await {
+            Err(ApiError::EcbFetchError(msg)) => assert!(msg.contains("308"), "{}", msg),
+            other => panic!("Expected EcbFetchError, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,88 @@
+use crate::error::ApiError;
+use crate::services::HttpClientOptions;
+use std::time::Duration;
+
+/// Upper bound on the /health ECB probe, kept under the default HEALTH_TIMEOUT_MS
+pub const ECB_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Reachability check of the ECB feed for /health, telling upstream issues apart from Redis ones
+#[derive(Clone)]
+pub struct EcbProbe {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl EcbProbe {
+    /// Uses the outbound client settings (proxy, User-Agent), capped at ECB_PROBE_TIMEOUT
+    pub fn new(url: String, options: &HttpClientOptions) -> Result<Self, ApiError> {
+        let options = HttpClientOptions {
+            timeout: options.timeout.min(ECB_PROBE_TIMEOUT),
+            ..options.clone()
+        };
+
+        Ok(Self {
+            client: options.build_client()?,
+            url,
+        })
+    }
+
+    /// Whether a HEAD request to the feed succeeds within the timeout
+    /// Only the status is checked; the document itself is not downloaded
+    pub async fn is_reachable(&self) -> bool {
+        match self.client.head(&self.url).send().await {
+            Ok(response) if response.status().is_success() => true,
+            Ok(response) => {
+                tracing::debug!("ECB probe of {} returned {}", self.url, response.status());
+                false
+            }
+            Err(e) => {
+                tracing::debug!("ECB probe of {} failed: {}", self.url, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, http::StatusCode, routing::get};
+
+    #[tokio::test]
+    async fn test_probe_reports_reachability() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/daily.xml", get(|| async { "<Envelope/>" }))
+            .route(
+                "/down.xml",
+                get(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let probe = |path: &str| {
+            EcbProbe::new(
+                format!("http://{}{}", addr, path),
+                &HttpClientOptions::default(),
+            )
+            .unwrap()
+        };
+
+        assert!(probe("/daily.xml").is_reachable().await);
+        assert!(!probe("/down.xml").is_reachable().await);
+    }
+
+    #[tokio::test]
+    async fn test_probe_unreachable_host() {
+        // Bind then drop a listener so nothing accepts on the port
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let probe =
+            EcbProbe::new(format!("http://{}/", addr), &HttpClientOptions::default()).unwrap();
+        assert!(!probe.is_reachable().await);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::ApiError;
+use crate::models::currency_meta;
+use rust_decimal::Decimal;
+
+/// Locale used when `format=true` is given without `locale`
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Display conventions for a locale: separators and where the symbol goes
+#[derive(Debug, Clone, Copy)]
+struct LocaleFormat {
+    tag: &'static str,
+    decimal: char,
+    group: char,
+    /// Symbol after the number ("103,50 €") instead of before ("$103.50")
+    symbol_after: bool,
+}
+
+#[rustfmt::skip]
+const LOCALES: &[LocaleFormat] = &[
+    LocaleFormat { tag: "en-US", decimal: '.', group: ',', symbol_after: false },
+    LocaleFormat { tag: "en-GB", decimal: '.', group: ',', symbol_after: false },
+    LocaleFormat { tag: "ja-JP", decimal: '.', group: ',', symbol_after: false },
+    LocaleFormat { tag: "de-DE", decimal: ',', group: '.', symbol_after: true },
+    LocaleFormat { tag: "es-ES", decimal: ',', group: '.', symbol_after: true },
+    LocaleFormat { tag: "it-IT", decimal: ',', group: '.', symbol_after: true },
+    LocaleFormat { tag: "fr-FR", decimal: ',', group: ' ', symbol_after: true },
+];
+
+/// Look up a locale by tag, case-insensitively and accepting `_` for `-`
+/// A bare language ("de") matches the first locale for that language
+fn find_locale(tag: &str) -> Option<&'static LocaleFormat> {
+    let tag = tag.trim().replace('_', "-");
+
+    LOCALES
+        .iter()
+        .find(|locale| locale.tag.eq_ignore_ascii_case(&tag))
+        .or_else(|| {
+            LOCALES.iter().find(|locale| {
+                locale
+                    .tag
+                    .split('-')
+                    .next()
+                    .is_some_and(|language| language.eq_ignore_ascii_case(&tag))
+            })
+        })
+}
+
+/// Format an amount for display in `currency` using `locale`'s conventions
+/// The amount is printed at its own scale, so round it first; currencies
+/// missing from the ISO table are shown with their code instead of a symbol
+pub fn format_amount(amount: Decimal, currency: &str, locale: &str) -> Result<String, ApiError> {
+    let locale = find_locale(locale).ok_or_else(|| {
+        ApiError::Unprocessable(format!(
+            "Unsupported locale '{}', expected one of: {}",
+            locale,
+            LOCALES
+                .iter()
+                .map(|locale| locale.tag)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })?;
+
+    let currency = currency.to_uppercase();
+    let symbol = currency_meta(&currency).map_or(currency.as_str(), |meta| meta.symbol);
+
+    let number = group_digits(amount, locale);
+    let sign = if amount.is_sign_negative() && !amount.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+
+    Ok(if locale.symbol_after {
+        format!("{}{} {}", sign, number, symbol)
+    } else if symbol.chars().all(|c| c.is_ascii_alphabetic()) {
+        // Code-like symbols ("CHF", "USD") read better with a space
+        format!("{}{} {}", sign, symbol, number)
+    } else {
+        format!("{}{}{}", sign, symbol, number)
+    })
+}
+
+/// Render the absolute value of `amount` with the locale's separators
+fn group_digits(amount: Decimal, locale: &LocaleFormat) -> String {
+    let text = amount.abs().to_string();
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (text.as_str(), None),
+    };
+
+    let mut grouped = String::with_capacity(text.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(locale.group);
+        }
+        grouped.push(digit);
+    }
+
+    if let Some(fraction) = fraction {
+        grouped.push(locale.decimal);
+        grouped.push_str(fraction);
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_format_major_currencies() {
+        assert_eq!(
+            format_amount(dec!(103.50), "USD", "en-US").unwrap(),
+            "$103.50"
+        );
+        assert_eq!(
+            format_amount(dec!(103.50), "EUR", "de-DE").unwrap(),
+            "103,50 €"
+        );
+        assert_eq!(
+            format_amount(dec!(1234567.89), "GBP", "en-GB").unwrap(),
+            "£1,234,567.89"
+        );
+        assert_eq!(
+            format_amount(dec!(15025), "JPY", "ja-JP").unwrap(),
+            "¥15,025"
+        );
+    }
+
+    #[test]
+    fn test_format_grouping() {
+        assert_eq!(
+            format_amount(dec!(1234567.5), "EUR", "fr-FR").unwrap(),
+            "1 234 567,5 €"
+        );
+        assert_eq!(format_amount(dec!(999), "USD", "en-US").unwrap(), "$999");
+        assert_eq!(format_amount(dec!(0.00), "USD", "en-US").unwrap(), "$0.00");
+    }
+
+    #[test]
+    fn test_locale_lookup_is_lenient() {
+        assert_eq!(
+            format_amount(dec!(1000), "EUR", "de_de").unwrap(),
+            "1.000 €"
+        );
+        assert_eq!(format_amount(dec!(5), "EUR", "de").unwrap(), "5 €");
+    }
+
+    #[test]
+    fn test_code_like_symbol_is_spaced() {
+        assert_eq!(
+            format_amount(dec!(10.00), "CHF", "en-US").unwrap(),
+            "CHF 10.00"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_locale() {
+        let err = format_amount(dec!(1), "USD", "xx-YY").unwrap_err();
+
+        assert!(matches!(err, ApiError::Unprocessable(msg) if msg.contains("xx-YY")));
+    }
+}
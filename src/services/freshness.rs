@@ -0,0 +1,91 @@
+use super::calendar::business_day_on_or_before;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+
+/// Hour (UTC) at which ECB publishes the reference rates for a given date
+/// (around 16:00 CET). Age is measured from this point rather than midnight,
+/// so rates published today are not considered hours old on arrival.
+const PUBLICATION_HOUR_UTC: u32 = 15;
+
+/// Age in whole hours of rates dated `date` (YYYY-MM-DD) at time `now`
+/// Returns None when the date cannot be parsed
+pub fn rate_age_hours(date: &str, now: DateTime<Utc>) -> Option<i64> {
+    let published = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(PUBLICATION_HOUR_UTC, 0, 0)?
+        .and_utc();
+
+    Some((now - published).num_hours().max(0))
+}
+
+/// Latest business day whose rates ECB has published by `at`
+fn expected_business_day(at: DateTime<Utc>) -> NaiveDate {
+    let date = at.date_naive();
+    let published = if at.hour() >= PUBLICATION_HOUR_UTC {
+        Some(date)
+    } else {
+        date.pred_opt()
+    };
+
+    published.map_or(NaiveDate::MIN, business_day_on_or_before)
+}
+
+/// Whether rates dated `date` (YYYY-MM-DD) are stale at time `now`: a newer business day
+/// was published at least `threshold_hours` ago. ECB publishes nothing on weekends and
+/// TARGET holidays, so Friday's rates stay fresh until the threshold past Monday's publication
+/// Unparseable dates are never stale
+pub fn is_stale(date: &str, now: DateTime<Utc>, threshold_hours: i64) -> bool {
+    let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+        return false;
+    };
+
+    now.checked_sub_signed(Duration::hours(threshold_hours))
+        .is_some_and(|at| expected_business_day(at) > date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_rate_age_from_publication_time() {
+        let now = Utc.with_ymd_and_hms(2024, 12, 5, 9, 0, 0).unwrap();
+        // Published 2024-12-04 15:00 UTC -> 18 hours old
+        assert_eq!(rate_age_hours("2024-12-04", now), Some(18));
+    }
+
+    #[test]
+    fn test_rate_age_never_negative() {
+        let now = Utc.with_ymd_and_hms(2024, 12, 4, 14, 0, 0).unwrap();
+        assert_eq!(rate_age_hours("2024-12-04", now), Some(0));
+    }
+
+    #[test]
+    fn test_rate_age_invalid_date() {
+        assert_eq!(rate_age_hours("not-a-date", Utc::now()), None);
+    }
+
+    #[test]
+    fn test_is_stale() {
+        // Wednesday's rates, replaced by Thursday's at 2024-12-05 15:00 UTC
+        let at = |d, h| Utc.with_ymd_and_hms(2024, 12, d, h, 0, 0).unwrap();
+        assert!(!is_stale("2024-12-04", at(5, 9), 26));
+        assert!(!is_stale("2024-12-04", at(6, 16), 26));
+        assert!(is_stale("2024-12-04", at(6, 17), 26));
+        assert!(!is_stale("not-a-date", at(6, 17), 26));
+    }
+
+    #[test]
+    fn test_is_stale_skips_closed_days() {
+        // Friday 2024-12-06 stays fresh over the weekend, until Monday 15:00 UTC plus 26 hours
+        let at = |d, h| Utc.with_ymd_and_hms(2024, 12, d, h, 0, 0).unwrap();
+        assert!(!is_stale("2024-12-06", at(8, 23), 26));
+        assert!(!is_stale("2024-12-06", at(10, 16), 26));
+        assert!(is_stale("2024-12-06", at(10, 17), 26));
+
+        // Maundy Thursday 2024-03-28 is followed by Good Friday and Easter Monday
+        let at = |m, d, h| Utc.with_ymd_and_hms(2024, m, d, h, 0, 0).unwrap();
+        assert!(!is_stale("2024-03-28", at(4, 2, 9), 26));
+        assert!(is_stale("2024-03-28", at(4, 3, 17), 26));
+    }
+}
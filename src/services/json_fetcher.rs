@@ -0,0 +1,259 @@
+use crate::error::ApiError;
+use crate::models::{DailyRate, ECB_BASE_CURRENCY, EcbRate};
+use crate::services::{
+    DEFAULT_MIN_RATES, HttpClientOptions, RateProvider, RetryPolicy, fetch_text_with_retry,
+};
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, de};
+use std::fmt;
+use std::str::FromStr;
+
+/// Frankfurter-style JSON payload, e.g.
+/// `{"base":"EUR","date":"2024-12-04","rates":{"USD":1.0534,"JPY":158.23}}`
+#[derive(Debug, Deserialize)]
+struct JsonRatesPayload {
+    date: String,
+    /// ECB_BASE_CURRENCY (EUR) when omitted
+    base: Option<String>,
+    /// Kept as JSON numbers so rates are parsed from their decimal text, not through f64
+    /// Every entry is kept, so a code listed twice is rejected rather than overwritten
+    #[serde(deserialize_with = "rate_entries")]
+    rates: Vec<(String, serde_json::Number)>,
+}
+
+/// Collect the `rates` object as listed, duplicate keys included
+fn rate_entries<'de, D>(deserializer: D) -> Result<Vec<(String, serde_json::Number)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct EntriesVisitor;
+
+    impl<'de> de::Visitor<'de> for EntriesVisitor {
+        type Value = Vec<(String, serde_json::Number)>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an object of currency codes to rates")
+        }
+
+        fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = Vec::new();
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+    }
+
+    deserializer.deserialize_map(EntriesVisitor)
+}
+
+/// Fetches daily rates from a JSON mirror of the ECB data
+pub struct JsonRateFetcher {
+    client: reqwest::Client,
+    url: String,
+    retry_policy: RetryPolicy,
+    min_rates: usize,
+}
+
+impl JsonRateFetcher {
+    pub fn new(
+        url: String,
+        retry_policy: RetryPolicy,
+        options: &HttpClientOptions,
+    ) -> Result<Self, ApiError> {
+        Ok(Self {
+            client: options.build_client()?,
+            url,
+            retry_policy,
+            min_rates: DEFAULT_MIN_RATES,
+        })
+    }
+
+    /// Reject payloads with fewer than `min_rates` currencies (base not counted)
+    pub fn with_min_rates(mut self, min_rates: usize) -> Self {
+        self.min_rates = min_rates;
+        self
+    }
+
+    /// Parse a JSON payload into a validated DailyRate
+    fn parse_json(&self, json: &str) -> Result<DailyRate, ApiError> {
+        let payload: JsonRatesPayload = serde_json::from_str(json)
+            .map_err(|e| ApiError::EcbFetchError(format!("Failed to parse JSON rates: {}", e)))?;
+
+        if payload.rates.len() < self.min_rates {
+            return Err(ApiError::EcbFetchError(format!(
+                "JSON payload for {} contains {} rates, expected at least {}",
+                payload.date,
+                payload.rates.len(),
+                self.min_rates
+            )));
+        }
+
+        let base = payload
+            .base
+            .unwrap_or_else(|| ECB_BASE_CURRENCY.to_string());
+
+        // Scientific notation is spelled out so every rate reaches from_quotes as plain decimal text
+        let quotes = payload
+            .rates
+            .into_iter()
+            .map(|(currency, rate)| {
+                Decimal::from_str(&rate.to_string())
+                    .or_else(|_| Decimal::from_scientific(&rate.to_string()))
+                    .map(|rate| EcbRate {
+                        currency: currency.clone(),
+                        rate: rate.to_string(),
+                    })
+                    .map_err(|e| {
+                        ApiError::EcbFetchError(format!(
+                            "Failed to parse rate for {}: {}",
+                            currency, e
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Same code checks as the ECB feed; the base is implied at 1 like EUR there
+        let daily_rate =
+            DailyRate::from_quotes(&base, payload.date, quotes).map_err(ApiError::EcbFetchError)?;
+        daily_rate
+            .validate_date()
+            .map_err(ApiError::EcbFetchError)?;
+
+        tracing::info!(
+            "Successfully parsed {} exchange rates for {}",
+            daily_rate.rates.len(),
+            daily_rate.date
+        );
+
+        Ok(daily_rate)
+    }
+}
+
+#[async_trait]
+impl RateProvider for JsonRateFetcher {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    async fn fetch_rates(&self) -> Result<DailyRate, ApiError> {
+        self.fetch_rates_with_raw().await.map(|(rates, _)| rates)
+    }
+
+    async fn fetch_rates_with_raw(&self) -> Result<(DailyRate, Option<String>), ApiError> {
+        tracing::info!("Fetching exchange rates from JSON source: {}", self.url);
+
+        let json = fetch_text_with_retry(&self.client, &self.url, &self.retry_policy).await?;
+        let rates = self.parse_json(&json)?;
+
+        Ok((rates, Some(json)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn fetcher(min_rates: usize) -> JsonRateFetcher {
+        JsonRateFetcher::new(
+            "http://example.com/latest".to_string(),
+            RetryPolicy::default(),
+            &HttpClientOptions::default(),
+        )
+        .unwrap()
+        .with_min_rates(min_rates)
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let json = r#"{"amount":1.0,"base":"EUR","date":"2024-12-04","rates":{"USD":1.0534,"JPY":158.23,"GBP":0.8345}}"#;
+
+        let rates = fetcher(3).parse_json(json).unwrap();
+
+        assert_eq!(rates.date, "2024-12-04");
+        assert_eq!(rates.base, "EUR");
+        assert_eq!(rates.rates.len(), 4);
+        assert_eq!(rates.rates["USD"], dec!(1.0534));
+        assert_eq!(rates.rates["JPY"], dec!(158.23));
+        assert_eq!(rates.rates["EUR"], Decimal::ONE);
+    }
+
+    #[test]
+    fn test_parse_json_keeps_full_precision() {
+        // An f64 round trip would collapse this to 1.0534
+        let json = r#"{"date":"2024-12-04","rates":{"USD":1.05340000000000000001}}"#;
+
+        let rates = fetcher(1).parse_json(json).unwrap();
+
+        assert_eq!(rates.rates["USD"], dec!(1.05340000000000000001));
+    }
+
+    #[test]
+    fn test_parse_json_defaults_base_to_eur() {
+        let json = r#"{"date":"2024-12-04","rates":{"USD":1.05}}"#;
+
+        let rates = fetcher(1).parse_json(json).unwrap();
+
+        assert_eq!(rates.base, "EUR");
+        assert_eq!(rates.rates["EUR"], Decimal::ONE);
+    }
+
+    #[test]
+    fn test_parse_json_rejects_partial_and_malformed_payloads() {
+        let partial = r#"{"date":"2024-12-04","rates":{"USD":1.05}}"#;
+        assert!(fetcher(10).parse_json(partial).is_err());
+
+        let bad_date = r#"{"date":"04/12/2024","rates":{"USD":1.05}}"#;
+        assert!(fetcher(1).parse_json(bad_date).is_err());
+
+        assert!(fetcher(1).parse_json("<xml/>").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_rejects_duplicate_codes() {
+        for json in [
+            r#"{"date":"2024-12-04","rates":{"usd":1.05,"USD":1.06}}"#,
+            r#"{"date":"2024-12-04","rates":{"USD":1.05,"USD":1.06}}"#,
+        ] {
+            let err = fetcher(1).parse_json(json).unwrap_err();
+            assert!(
+                err.to_string().contains("Duplicate currency code 'USD'"),
+                "{json}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_json_rejects_non_iso_codes() {
+        for code in ["US", "USDX", "U5D", "$$$"] {
+            let json = format!(r#"{{"date":"2024-12-04","rates":{{"{}":1.05}}}}"#, code);
+            let err = fetcher(1).parse_json(&json).unwrap_err();
+            assert!(
+                err.to_string().contains("Invalid currency code"),
+                "{code}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_json_checks_listed_base() {
+        let listed = r#"{"base":"USD","date":"2024-12-04","rates":{"USD":1,"EUR":0.95}}"#;
+        let rates = fetcher(1).parse_json(listed).unwrap();
+        assert_eq!(rates.rates["USD"], Decimal::ONE);
+
+        let wrong = r#"{"base":"USD","date":"2024-12-04","rates":{"USD":1.05,"EUR":0.95}}"#;
+        let err = fetcher(1).parse_json(wrong).unwrap_err();
+        assert!(err.to_string().contains("expected 1"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_json_accepts_scientific_notation() {
+        let json = r#"{"date":"2024-12-04","rates":{"IDR":1.7E4}}"#;
+
+        let rates = fetcher(1).parse_json(json).unwrap();
+
+        assert_eq!(rates.rates["IDR"], dec!(17000));
+    }
+}
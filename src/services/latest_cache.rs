@@ -0,0 +1,151 @@
+use crate::models::DailyRate;
+use arc_swap::ArcSwapOption;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long the latest rates are served from memory when RATES_CACHE_TTL_SECS is unset
+pub const DEFAULT_LATEST_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Latest rates together with when they were last confirmed against Redis
+struct Entry {
+    rates: Arc<DailyRate>,
+    checked_at: Instant,
+}
+
+/// In-process copy of the latest rates, read lock-free on every request
+/// Within the TTL requests skip Redis entirely; afterwards the stored date is
+/// checked and the copy reused while it still matches
+#[derive(Clone)]
+pub struct LatestCache {
+    entry: Arc<ArcSwapOption<Entry>>,
+    ttl: Duration,
+}
+
+impl LatestCache {
+    /// A zero `ttl` checks Redis on every read
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entry: Arc::new(ArcSwapOption::empty()),
+            ttl,
+        }
+    }
+
+    /// Rates confirmed within the TTL, if any
+    pub fn fresh(&self) -> Option<Arc<DailyRate>> {
+        self.fresh_at(Instant::now())
+    }
+
+    /// Cached rates if they are for `date`, confirmed again for another TTL
+    pub fn revalidate(&self, date: &str) -> Option<Arc<DailyRate>> {
+        self.revalidate_at(date, Instant::now())
+    }
+
+    /// Replace the cached rates (None after Redis reported no rates)
+    pub fn set(&self, rates: Option<Arc<DailyRate>>) {
+        self.set_at(rates, Instant::now());
+    }
+
+    fn fresh_at(&self, now: Instant) -> Option<Arc<DailyRate>> {
+        self.entry
+            .load()
+            .as_ref()
+            .filter(|entry| now.saturating_duration_since(entry.checked_at) < self.ttl)
+            .map(|entry| Arc::clone(&entry.rates))
+    }
+
+    fn revalidate_at(&self, date: &str, now: Instant) -> Option<Arc<DailyRate>> {
+        let rates = self
+            .entry
+            .load()
+            .as_ref()
+            .filter(|entry| entry.rates.date == date)
+            .map(|entry| Arc::clone(&entry.rates))?;
+
+        self.set_at(Some(Arc::clone(&rates)), now);
+        Some(rates)
+    }
+
+    fn set_at(&self, rates: Option<Arc<DailyRate>>, now: Instant) {
+        self.entry.store(rates.map(|rates| {
+            Arc::new(Entry {
+                rates,
+                checked_at: now,
+            })
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn rates_for(date: &str) -> Arc<DailyRate> {
+        Arc::new(DailyRate {
+            date: date.to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::new(),
+        })
+    }
+
+    #[test]
+    fn test_hit_within_ttl() {
+        let cache = LatestCache::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let rates = rates_for("2024-12-04");
+
+        assert!(cache.fresh_at(start).is_none());
+
+        cache.set_at(Some(Arc::clone(&rates)), start);
+
+        let hit = cache.fresh_at(start + Duration::from_secs(59)).unwrap();
+        assert!(Arc::ptr_eq(&hit, &rates));
+    }
+
+    #[test]
+    fn test_miss_after_ttl() {
+        let cache = LatestCache::new(Duration::from_secs(60));
+        let start = Instant::now();
+        cache.set_at(Some(rates_for("2024-12-04")), start);
+
+        assert!(cache.fresh_at(start + Duration::from_secs(60)).is_none());
+    }
+
+    #[test]
+    fn test_revalidate_requires_same_date() {
+        let cache = LatestCache::new(Duration::from_secs(60));
+        let start = Instant::now();
+        cache.set_at(Some(rates_for("2024-12-04")), start);
+        let later = start + Duration::from_secs(90);
+
+        // A newer stored date must be loaded from Redis
+        assert!(cache.revalidate_at("2024-12-05", later).is_none());
+        assert!(cache.fresh_at(later).is_none());
+
+        // An unchanged date keeps the copy for another TTL
+        assert!(cache.revalidate_at("2024-12-04", later).is_some());
+        assert!(cache.fresh_at(later + Duration::from_secs(30)).is_some());
+    }
+
+    #[test]
+    fn test_zero_ttl_never_fresh() {
+        let cache = LatestCache::new(Duration::ZERO);
+        let now = Instant::now();
+        cache.set_at(Some(rates_for("2024-12-04")), now);
+
+        assert!(cache.fresh_at(now).is_none());
+        assert!(cache.revalidate_at("2024-12-04", now).is_some());
+    }
+
+    #[test]
+    fn test_cleared_when_rates_disappear() {
+        let cache = LatestCache::new(Duration::from_secs(60));
+        let now = Instant::now();
+        cache.set_at(Some(rates_for("2024-12-04")), now);
+
+        cache.set_at(None, now);
+
+        assert!(cache.fresh_at(now).is_none());
+        assert!(cache.revalidate_at("2024-12-04", now).is_none());
+    }
+}
@@ -1,9 +1,31 @@
+pub mod calendar;
 pub mod converter;
 pub mod ecb_fetcher;
+pub mod ecb_probe;
+pub mod formatter;
+pub mod freshness;
+pub mod json_fetcher;
+pub mod latest_cache;
+pub mod provider;
+pub mod rate_alert;
+pub mod rate_cache;
+pub mod rebase_cache;
+pub mod redis_connection;
 pub mod redis_store;
 pub mod scheduler;
 
+pub use calendar::*;
 pub use converter::*;
 pub use ecb_fetcher::*;
+pub use ecb_probe::*;
+pub use formatter::*;
+pub use freshness::*;
+pub use json_fetcher::*;
+pub use latest_cache::*;
+pub use provider::*;
+pub use rate_alert::*;
+pub use rate_cache::*;
+pub use rebase_cache::*;
+pub use redis_connection::*;
 pub use redis_store::*;
 pub use scheduler::*;
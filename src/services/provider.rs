@@ -0,0 +1,193 @@
+use crate::error::ApiError;
+use crate::models::DailyRate;
+use async_trait::async_trait;
+
+/// Result of a conditional fetch
+#[derive(Debug)]
+pub enum ConditionalFetch {
+    /// Rates and, for providers that have one, the raw payload they were parsed from
+    Fetched(DailyRate, Option<String>),
+    /// The source reports no change since the fetch that returned rates for this date
+    NotModified(String),
+}
+
+/// A source of daily exchange rates (e.g. the ECB XML feed or a mirror)
+#[async_trait]
+pub trait RateProvider: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// Fetch the latest daily rates
+    async fn fetch_rates(&self) -> Result<DailyRate, ApiError>;
+
+    /// Fetch the latest daily rates along with the raw payload they were parsed from,
+    /// for providers that have one (None otherwise)
+    async fn fetch_rates_with_raw(&self) -> Result<(DailyRate, Option<String>), ApiError> {
+        Ok((self.fetch_rates().await?, None))
+    }
+
+    /// Fetch the latest daily rates unless the source reports them unchanged since
+    /// the previous fetch; providers without conditional requests always fetch
+    async fn fetch_rates_if_modified(&self) -> Result<ConditionalFetch, ApiError> {
+        let (rates, raw) = self.fetch_rates_with_raw().await?;
+        Ok(ConditionalFetch::Fetched(rates, raw))
+    }
+}
+
+/// Tries each provider in order until one succeeds
+pub struct FallbackFetcher {
+    providers: Vec<Box<dyn RateProvider>>,
+}
+
+impl FallbackFetcher {
+    pub fn new(providers: Vec<Box<dyn RateProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait]
+impl RateProvider for FallbackFetcher {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    async fn fetch_rates(&self) -> Result<DailyRate, ApiError> {
+        self.fetch_rates_with_raw().await.map(|(rates, _)| rates)
+    }
+
+    async fn fetch_rates_with_raw(&self) -> Result<(DailyRate, Option<String>), ApiError> {
+        let mut last_error = None;
+
+        for provider in &self.providers {
+            match provider.fetch_rates_with_raw().await {
+                Ok(fetched) => {
+                    tracing::info!("Fetched exchange rates from provider {}", provider.name());
+                    return Ok(fetched);
+                }
+                Err(e) => {
+                    tracing::warn!("Provider {} failed: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| ApiError::EcbFetchError("No rate providers configured".to_string())))
+    }
+
+    /// A "not modified" answer from a provider counts as success; later providers
+    /// are only tried when it fails
+    async fn fetch_rates_if_modified(&self) -> Result<ConditionalFetch, ApiError> {
+        let mut last_error = None;
+
+        for provider in &self.providers {
+            match provider.fetch_rates_if_modified().await {
+                Ok(fetched) => {
+                    tracing::info!("Fetched exchange rates from provider {}", provider.name());
+                    return Ok(fetched);
+                }
+                Err(e) => {
+                    tracing::warn!("Provider {} failed: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| ApiError::EcbFetchError("No rate providers configured".to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct StaticProvider {
+        name: &'static str,
+        date: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl RateProvider for StaticProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn fetch_rates(&self) -> Result<DailyRate, ApiError> {
+            match self.date {
+                Some(date) => Ok(DailyRate {
+                    date: date.to_string(),
+                    base: "EUR".to_string(),
+                    rates: HashMap::new(),
+                }),
+                None => Err(ApiError::EcbFetchError(format!("{} is down", self.name))),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_used_when_primary_fails() {
+        let fetcher = FallbackFetcher::new(vec![
+            Box::new(StaticProvider {
+                name: "primary",
+                date: None,
+            }),
+            Box::new(StaticProvider {
+                name: "secondary",
+                date: Some("2024-12-04"),
+            }),
+        ]);
+
+        let rates = fetcher.fetch_rates().await.unwrap();
+        assert_eq!(rates.date, "2024-12-04");
+    }
+
+    #[tokio::test]
+    async fn test_primary_preferred_when_healthy() {
+        let fetcher = FallbackFetcher::new(vec![
+            Box::new(StaticProvider {
+                name: "primary",
+                date: Some("2024-12-04"),
+            }),
+            Box::new(StaticProvider {
+                name: "secondary",
+                date: Some("2024-12-03"),
+            }),
+        ]);
+
+        let rates = fetcher.fetch_rates().await.unwrap();
+        assert_eq!(rates.date, "2024-12-04");
+    }
+
+    #[tokio::test]
+    async fn test_all_providers_failing_returns_last_error() {
+        let fetcher = FallbackFetcher::new(vec![
+            Box::new(StaticProvider {
+                name: "primary",
+                date: None,
+            }),
+            Box::new(StaticProvider {
+                name: "secondary",
+                date: None,
+            }),
+        ]);
+
+        match fetcher.fetch_rates().await {
+            Err(ApiError::EcbFetchError(msg)) => assert_eq!(msg, "secondary is down"),
+            _ => panic!("Expected EcbFetchError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_providers_without_payload_return_no_raw() {
+        let provider = StaticProvider {
+            name: "primary",
+            date: Some("2024-12-04"),
+        };
+
+        let (rates, raw) = provider.fetch_rates_with_raw().await.unwrap();
+        assert_eq!(rates.date, "2024-12-04");
+        assert!(raw.is_none());
+    }
+}
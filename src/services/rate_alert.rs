@@ -0,0 +1,277 @@
+use crate::error::ApiError;
+use crate::models::DailyRate;
+use crate::services::HttpClientOptions;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+/// Day-over-day change that triggers an alert when ALERT_THRESHOLD_PCT is unset
+pub const DEFAULT_ALERT_THRESHOLD_PCT: Decimal = Decimal::TEN;
+
+/// Currency whose rate changed by more than the threshold between two updates
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RateMove {
+    pub currency: String,
+    pub previous: Decimal,
+    pub current: Decimal,
+    /// Signed change relative to `previous`, in percent (2 decimal places)
+    pub change_pct: Decimal,
+}
+
+/// JSON body POSTed to ALERT_WEBHOOK_URL
+#[derive(Debug, Clone, Serialize)]
+pub struct RateAlert {
+    pub base: String,
+    pub previous_date: String,
+    pub date: String,
+    pub threshold_pct: Decimal,
+    pub moves: Vec<RateMove>,
+}
+
+/// Currencies present in both tables whose rate moved more than `threshold_pct`,
+/// sorted by code; tables quoted against different bases are not comparable
+pub fn rate_moves(
+    previous: &DailyRate,
+    current: &DailyRate,
+    threshold_pct: Decimal,
+) -> Vec<RateMove> {
+    if previous.base != current.base {
+        return Vec::new();
+    }
+
+    let mut moves: Vec<RateMove> = current
+        .rates
+        .iter()
+        .filter_map(|(code, rate)| {
+            let before = *previous.rates.get(code)?;
+            if before <= Decimal::ZERO {
+                return None;
+            }
+
+            let change_pct = (*rate - before) / before * Decimal::ONE_HUNDRED;
+            (change_pct.abs() > threshold_pct).then(|| RateMove {
+                currency: code.clone(),
+                previous: before,
+                current: *rate,
+                change_pct: change_pct.round_dp(2),
+            })
+        })
+        .collect();
+    moves.sort_by(|a, b| a.currency.cmp(&b.currency));
+
+    moves
+}
+
+/// Posts an alert to a webhook when an update moves any rate past the threshold
+/// Delivery runs in the background so a slow or failing webhook never affects updates
+#[derive(Debug, Clone)]
+pub struct RateAlerter {
+    client: reqwest::Client,
+    url: String,
+    threshold_pct: Decimal,
+}
+
+impl RateAlerter {
+    /// Uses the outbound client settings (proxy, User-Agent, timeout)
+    pub fn new(
+        url: String,
+        threshold_pct: Decimal,
+        options: &HttpClientOptions,
+    ) -> Result<Self, ApiError> {
+        Ok(Self {
+            client: options.build_client()?,
+            url,
+            threshold_pct,
+        })
+    }
+
+    /// Compare `current` with the `previous` stored rates and send an alert if needed
+    /// Returns the spawned delivery task, or None when no rate crossed the threshold
+    pub fn check(&self, previous: &DailyRate, current: &DailyRate) -> Option<JoinHandle<()>> {
+        let moves = rate_moves(previous, current, self.threshold_pct);
+        if moves.is_empty() {
+            return None;
+        }
+
+        tracing::warn!(
+            "{} rate(s) moved more than {}% from {} to {}: {}",
+            moves.len(),
+            self.threshold_pct,
+            previous.date,
+            current.date,
+            moves
+                .iter()
+                .map(|m| format!("{} {:+}%", m.currency, m.change_pct))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let alert = RateAlert {
+            base: current.base.clone(),
+            previous_date: previous.date.clone(),
+            date: current.date.clone(),
+            threshold_pct: self.threshold_pct,
+            moves,
+        };
+        let body = match serde_json::to_vec(&alert) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!("Failed to serialize rate alert: {}", e);
+                return None;
+            }
+        };
+        let client = self.client.clone();
+        let url = self.url.clone();
+
+        Some(tokio::spawn(async move {
+            let request = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body);
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    tracing::info!("Sent rate alert for {} to {}", alert.date, url);
+                }
+                Ok(response) => {
+                    tracing::warn!("Rate alert webhook {} returned {}", url, response.status());
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to send rate alert to {}: {}", url, e);
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::State, routing::post};
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    fn table(date: &str, rates: &[(&str, Decimal)]) -> DailyRate {
+        DailyRate {
+            date: date.to_string(),
+            base: "EUR".to_string(),
+            rates: rates
+                .iter()
+                .map(|(code, rate)| (code.to_string(), *rate))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_rate_moves_above_threshold() {
+        let previous = table(
+            "2024-12-03",
+            &[("USD", dec!(1.00)), ("JPY", dec!(160)), ("GBP", dec!(0.80))],
+        );
+        let current = table(
+            "2024-12-04",
+            &[
+                ("USD", dec!(1.05)),
+                ("JPY", dec!(120)),
+                ("GBP", dec!(0.90)),
+                ("CHF", dec!(0.93)),
+            ],
+        );
+
+        let moves = rate_moves(&previous, &current, dec!(10));
+        assert_eq!(
+            moves,
+            vec![
+                RateMove {
+                    currency: "GBP".to_string(),
+                    previous: dec!(0.80),
+                    current: dec!(0.90),
+                    change_pct: dec!(12.50),
+                },
+                RateMove {
+                    currency: "JPY".to_string(),
+                    previous: dec!(160),
+                    current: dec!(120),
+                    change_pct: dec!(-25.00),
+                },
+            ]
+        );
+
+        // A change exactly at the threshold does not alert
+        assert!(rate_moves(&previous, &current, dec!(25)).is_empty());
+
+        let mut rebased = current.clone();
+        rebased.base = "USD".to_string();
+        assert!(rate_moves(&previous, &rebased, dec!(1)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_alerter_posts_moves_to_webhook() {
+        let received = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route(
+                "/alert",
+                post(
+                    |State(received): State<Arc<Mutex<Vec<serde_json::Value>>>>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        received.lock().unwrap().push(body);
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let alerter = RateAlerter::new(
+            format!("http://{}/alert", addr),
+            dec!(10),
+            &HttpClientOptions::default(),
+        )
+        .unwrap();
+        let previous = table("2024-12-03", &[("USD", dec!(1.00))]);
+
+        assert!(
+            alerter
+                .check(&previous, &table("2024-12-04", &[("USD", dec!(1.05))]))
+                .is_none()
+        );
+
+        alerter
+            .check(&previous, &table("2024-12-04", &[("USD", dec!(1.50))]))
+            .unwrap()
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["date"], "2024-12-04");
+        assert_eq!(received[0]["moves"][0]["currency"], "USD");
+        assert_eq!(received[0]["moves"][0]["change_pct"], "50.00");
+    }
+
+    #[tokio::test]
+    async fn test_alerter_tolerates_unreachable_webhook() {
+        // Bind then drop a listener so nothing accepts on the port
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let alerter = RateAlerter::new(
+            format!("http://{}/alert", addr),
+            dec!(10),
+            &HttpClientOptions::default(),
+        )
+        .unwrap();
+        let handle = alerter
+            .check(
+                &table("2024-12-03", &[("USD", dec!(1.00))]),
+                &table("2024-12-04", &[("USD", dec!(2.00))]),
+            )
+            .unwrap();
+
+        assert!(handle.await.is_ok());
+    }
+}
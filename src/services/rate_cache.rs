@@ -0,0 +1,164 @@
+use crate::error::ApiError;
+use crate::models::DailyRate;
+use lru::LruCache;
+use rust_decimal::Decimal;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Cross rates keyed by (from, to, date)
+type Entries = LruCache<(String, String, String), Decimal>;
+
+/// Least-recently-used cache of computed cross rates, keyed by (from, to, date)
+/// Only rates are cached, never converted amounts; a zero size disables it
+#[derive(Clone, Default)]
+pub struct RateCache {
+    entries: Option<Arc<Mutex<Entries>>>,
+}
+
+impl RateCache {
+    /// Cache up to `capacity` pairs (0 disables caching)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: NonZeroUsize::new(capacity)
+                .map(|cap| Arc::new(Mutex::new(LruCache::new(cap)))),
+        }
+    }
+
+    /// Rate from `from` to `to` in `rates`, computed by `compute` on a miss
+    /// Errors are returned as is and not cached
+    pub fn get_or_compute(
+        &self,
+        rates: &DailyRate,
+        from: &str,
+        to: &str,
+        compute: impl FnOnce() -> Result<Decimal, ApiError>,
+    ) -> Result<Decimal, ApiError> {
+        let Some(entries) = &self.entries else {
+            return compute();
+        };
+
+        let key = (from.to_uppercase(), to.to_uppercase(), rates.date.clone());
+        if let Some(rate) = lock(entries).get(&key) {
+            return Ok(*rate);
+        }
+
+        let rate = compute()?;
+        lock(entries).put(key, rate);
+
+        Ok(rate)
+    }
+
+    /// Drop all cached rates (called when new rates are stored)
+    pub fn invalidate(&self) {
+        if let Some(entries) = &self.entries {
+            lock(entries).clear();
+        }
+    }
+
+    /// Number of cached rates
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries
+            .as_ref()
+            .map_or(0, |entries| lock(entries).len())
+    }
+}
+
+// A poisoned lock only means a panic mid-insert; the cache itself is still usable
+fn lock(entries: &Mutex<Entries>) -> MutexGuard<'_, Entries> {
+    entries.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::cross_rate;
+    use rust_decimal_macros::dec;
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    fn rates(date: &str) -> DailyRate {
+        DailyRate {
+            date: date.to_string(),
+            base: "EUR".to_string(),
+            rates: HashMap::from([
+                ("USD".to_string(), dec!(1.05)),
+                ("JPY".to_string(), dec!(158.2)),
+                ("GBP".to_string(), dec!(0.85)),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_hot_pair_hits_skip_recomputation() {
+        let cache = RateCache::new(16);
+        let rates = rates("2024-12-04");
+        let computed = Cell::new(0);
+        let lookup = || {
+            cache.get_or_compute(&rates, "USD", "JPY", || {
+                computed.set(computed.get() + 1);
+                cross_rate(&rates, "USD", "JPY")
+            })
+        };
+
+        let first = lookup().unwrap();
+        for _ in 0..10_000 {
+            assert_eq!(lookup().unwrap(), first);
+        }
+
+        assert_eq!(computed.get(), 1);
+        assert_eq!(first, dec!(158.2) / dec!(1.05));
+    }
+
+    #[test]
+    fn test_disabled_cache_always_computes() {
+        let cache = RateCache::new(0);
+        let rates = rates("2024-12-04");
+        let computed = Cell::new(0);
+
+        for _ in 0..3 {
+            cache
+                .get_or_compute(&rates, "USD", "JPY", || {
+                    computed.set(computed.get() + 1);
+                    cross_rate(&rates, "USD", "JPY")
+                })
+                .unwrap();
+        }
+
+        assert_eq!(computed.get(), 3);
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_keyed_by_date_and_evicts_least_recently_used() {
+        let cache = RateCache::new(2);
+        let compute = |rates: &DailyRate, from: &str, to: &str| {
+            cache
+                .get_or_compute(rates, from, to, || cross_rate(rates, from, to))
+                .unwrap()
+        };
+
+        compute(&rates("2024-12-04"), "usd", "jpy");
+        compute(&rates("2024-12-05"), "USD", "JPY");
+        assert_eq!(cache.len(), 2);
+
+        // A third pair evicts the least recently used one
+        compute(&rates("2024-12-05"), "GBP", "USD");
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate();
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let cache = RateCache::new(4);
+        let rates = rates("2024-12-04");
+
+        let result =
+            cache.get_or_compute(&rates, "USD", "XXX", || cross_rate(&rates, "USD", "XXX"));
+
+        assert!(matches!(result, Err(ApiError::CurrencyNotFound(_))));
+        assert_eq!(cache.len(), 0);
+    }
+}
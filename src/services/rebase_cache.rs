@@ -0,0 +1,174 @@
+use crate::error::ApiError;
+use crate::models::DailyRate;
+use crate::services::rebase_rates;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Rebased tables keyed by (base, date)
+type Entries = HashMap<(String, String), Arc<DailyRate>>;
+
+/// In-memory cache of rebased rate tables, keyed by (base, date)
+/// Only one date is kept at a time: caching a table for a newer date drops the rest
+#[derive(Clone, Default)]
+pub struct RebaseCache {
+    entries: Arc<RwLock<Entries>>,
+}
+
+impl RebaseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebase `rates` to `base`, reusing a previously computed table when available
+    /// Cached tables are shared, so a hit costs a reference count rather than a copy
+    pub fn get_or_rebase(&self, rates: &DailyRate, base: &str) -> Result<Arc<DailyRate>, ApiError> {
+        let key = (base.to_uppercase(), rates.date.clone());
+
+        if let Some(cached) = self.read().get(&key) {
+            return Ok(Arc::clone(cached));
+        }
+
+        let rebased = Arc::new(rebase_rates(rates, base)?);
+
+        let mut entries = self.write();
+        if entries.keys().any(|(_, date)| *date != rates.date) {
+            entries.clear();
+        }
+        entries.insert(key, Arc::clone(&rebased));
+
+        Ok(rebased)
+    }
+
+    /// Precompute tables for `bases` so their first request after an update is a cache hit
+    /// Bases equal to the table's own or missing from it are skipped; returns how many were cached
+    pub fn warm(&self, rates: &DailyRate, bases: &[String]) -> usize {
+        bases
+            .iter()
+            .filter(|base| !base.eq_ignore_ascii_case(&rates.base))
+            .filter(|base| match self.get_or_rebase(rates, base) {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::warn!("Skipping warmup of {} base: {}", base, e);
+                    false
+                }
+            })
+            .count()
+    }
+
+    /// Drop all cached tables (called when new rates are stored)
+    pub fn invalidate(&self) {
+        self.write().clear();
+    }
+
+    /// Number of cached tables
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    // A poisoned lock only means a panic mid-insert; the map itself is still usable
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Entries> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Entries> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn rates_for(date: &str) -> DailyRate {
+        let mut rates = HashMap::new();
+        rates.insert("USD".to_string(), dec!(1.05));
+        rates.insert("GBP".to_string(), dec!(0.85));
+        rates.insert("JPY".to_string(), dec!(158.2));
+
+        DailyRate {
+            date: date.to_string(),
+            base: "EUR".to_string(),
+            rates,
+        }
+    }
+
+    #[test]
+    fn test_cached_matches_fresh_rebase() {
+        let cache = RebaseCache::new();
+        let rates = rates_for("2024-12-04");
+        let fresh = rebase_rates(&rates, "USD").unwrap();
+
+        let first = cache.get_or_rebase(&rates, "usd").unwrap();
+        let second = cache.get_or_rebase(&rates, "USD").unwrap();
+
+        assert_eq!(first.rates, fresh.rates);
+        assert_eq!(second.rates, fresh.rates);
+        assert_eq!(second.base, "USD");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_invalidate_clears_entries() {
+        let cache = RebaseCache::new();
+        let rates = rates_for("2024-12-04");
+        cache.get_or_rebase(&rates, "USD").unwrap();
+        cache.get_or_rebase(&rates, "GBP").unwrap();
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate();
+
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_new_date_replaces_old_entries() {
+        let cache = RebaseCache::new();
+        cache
+            .get_or_rebase(&rates_for("2024-12-04"), "USD")
+            .unwrap();
+
+        let mut updated = rates_for("2024-12-05");
+        updated.rates.insert("USD".to_string(), dec!(1.10));
+        let rebased = cache.get_or_rebase(&updated, "USD").unwrap();
+
+        assert_eq!(rebased.date, "2024-12-05");
+        assert_eq!(
+            rebased.rates.get("EUR"),
+            rebase_rates(&updated, "USD").unwrap().rates.get("EUR")
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_warm_matches_on_demand_rebase() {
+        let cache = RebaseCache::new();
+        let rates = rates_for("2024-12-04");
+        let bases = vec!["USD".to_string(), "GBP".to_string(), "EUR".to_string()];
+
+        assert_eq!(cache.warm(&rates, &bases), 2);
+        assert_eq!(cache.len(), 2);
+
+        for base in ["USD", "GBP"] {
+            let warmed = cache.get_or_rebase(&rates, base).unwrap();
+            let on_demand = rebase_rates(&rates, base).unwrap();
+            assert_eq!(warmed.rates, on_demand.rates);
+            assert_eq!(warmed.base, on_demand.base);
+        }
+        // Served from the warmed entries, nothing new computed
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_warm_skips_unknown_bases() {
+        let cache = RebaseCache::new();
+
+        assert_eq!(
+            cache.warm(&rates_for("2024-12-04"), &["CHF".to_string()]),
+            0
+        );
+        assert_eq!(cache.len(), 0);
+    }
+}
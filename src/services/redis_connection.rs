@@ -0,0 +1,156 @@
+use crate::error::is_connection_error;
+use redis::aio::{ConnectionLike, ConnectionManager};
+use redis::{Cmd, Pipeline, RedisFuture, RedisResult, Value};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Stored in place of a ping latency until the first PING succeeds
+const NO_PING: u64 = u64::MAX;
+
+/// Snapshot of the Redis connection's health, surfaced by /health and /metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedisStats {
+    /// Round trip of the last successful PING, None until one succeeds
+    pub last_ping: Option<Duration>,
+    /// Commands that found the connection lost; ConnectionManager reconnects after each
+    pub reconnects: u64,
+}
+
+/// Counters shared by every clone of a connection
+#[derive(Debug)]
+struct ConnectionCounters {
+    reconnects: AtomicU64,
+    last_ping_micros: AtomicU64,
+}
+
+impl Default for ConnectionCounters {
+    fn default() -> Self {
+        Self {
+            reconnects: AtomicU64::new(0),
+            last_ping_micros: AtomicU64::new(NO_PING),
+        }
+    }
+}
+
+impl ConnectionCounters {
+    /// Count a lost connection; rejected commands leave the connection intact
+    fn observe<T>(&self, result: &RedisResult<T>) {
+        if let Err(e) = result
+            && is_connection_error(e)
+        {
+            self.reconnects.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn record_ping(&self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(NO_PING - 1);
+        self.last_ping_micros.store(micros, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> RedisStats {
+        let micros = self.last_ping_micros.load(Ordering::Relaxed);
+        RedisStats {
+            last_ping: (micros != NO_PING).then(|| Duration::from_micros(micros)),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The store's multiplexed ConnectionManager, with every command it sends
+/// checked for a lost connection so reconnects can be reported
+#[derive(Clone)]
+pub struct TrackedConnection {
+    inner: ConnectionManager,
+    counters: Arc<ConnectionCounters>,
+}
+
+impl TrackedConnection {
+    pub fn new(inner: ConnectionManager) -> Self {
+        Self {
+            inner,
+            counters: Arc::new(ConnectionCounters::default()),
+        }
+    }
+
+    /// Live handles on the connection, this one included; it closes when the last is dropped
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.counters)
+    }
+
+    /// Send a PING, recording its round trip when it succeeds
+    pub async fn ping(&self) -> RedisResult<Duration> {
+        let mut conn = self.clone();
+        let started = Instant::now();
+        redis::cmd("PING").query_async::<()>(&mut conn).await?;
+
+        let latency = started.elapsed();
+        self.counters.record_ping(latency);
+        Ok(latency)
+    }
+
+    pub fn stats(&self) -> RedisStats {
+        self.counters.stats()
+    }
+}
+
+impl ConnectionLike for TrackedConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let result = self.inner.req_packed_command(cmd).await;
+            self.counters.observe(&result);
+            result
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let result = self.inner.req_packed_commands(cmd, offset, count).await;
+            self.counters.observe(&result);
+            result
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.inner.get_db()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::{ErrorKind, RedisError};
+    use std::io;
+
+    #[test]
+    fn test_counts_only_lost_connections() {
+        let counters = ConnectionCounters::default();
+
+        counters.observe(&Ok::<_, RedisError>(()));
+        counters.observe(&Err::<(), _>(RedisError::from((
+            ErrorKind::TypeError,
+            "WRONGTYPE",
+        ))));
+        assert_eq!(counters.stats().reconnects, 0);
+
+        counters.observe(&Err::<(), _>(RedisError::from(io::Error::from(
+            io::ErrorKind::ConnectionReset,
+        ))));
+        assert_eq!(counters.stats().reconnects, 1);
+    }
+
+    #[test]
+    fn test_records_last_ping() {
+        let counters = ConnectionCounters::default();
+        assert_eq!(counters.stats().last_ping, None);
+
+        counters.record_ping(Duration::from_micros(1500));
+        counters.record_ping(Duration::from_micros(800));
+        assert_eq!(counters.stats().last_ping, Some(Duration::from_micros(800)));
+    }
+}
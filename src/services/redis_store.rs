@@ -1,30 +1,246 @@
-use crate::error::ApiError;
-use crate::models::DailyRate;
+use crate::error::{ApiError, is_connection_error};
+use crate::models::{DailyRate, UpdateLogEntry};
+use crate::services::{
+    DEFAULT_LATEST_CACHE_TTL, DEFAULT_LOOKBACK_DAYS, LatestCache, RateCache, RebaseCache,
+    RedisStats, TrackedConnection, earliest_fallback, nearest_available,
+};
+use chrono::{Datelike, Days, NaiveDate, Utc};
 use redis::aio::ConnectionManager;
-use redis::{AsyncCommands, Client};
+use redis::{
+    AsyncCommands, Client, ExistenceCheck, RedisResult, Script, SetExpiry, SetOptions,
+    TlsCertificates,
+};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
 const RATES_KEY: &str = "exchange:rates:latest";
 const DATE_KEY: &str = "exchange:rates:date";
+const HISTORY_KEY_PREFIX: &str = "exchange:rates:";
+const HISTORY_INDEX_KEY: &str = "exchange:rates:history";
+const RAW_KEY_PREFIX: &str = "exchange:rates:raw:";
+const UPDATE_LOCK_KEY: &str = "exchange:rates:update-lock";
+const UPDATE_LOG_KEY: &str = "exchange:rates:updates";
+
+/// Entries kept in the update log; older ones are trimmed on each append
+pub const UPDATE_LOG_CAPACITY: usize = 100;
+
+/// Delete the lock only while it still holds our token, so an expired lock
+/// re-acquired by another replica is never released by us
+const RELEASE_LOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// Redis keys used by the store, all carrying the configured namespace prefix
+#[derive(Debug, Clone)]
+struct RedisKeys {
+    prefix: String,
+    latest: String,
+    date: String,
+    history_index: String,
+    update_lock: String,
+    update_log: String,
+}
+
+impl RedisKeys {
+    fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            latest: format!("{}{}", prefix, RATES_KEY),
+            date: format!("{}{}", prefix, DATE_KEY),
+            history_index: format!("{}{}", prefix, HISTORY_INDEX_KEY),
+            update_lock: format!("{}{}", prefix, UPDATE_LOCK_KEY),
+            update_log: format!("{}{}", prefix, UPDATE_LOG_KEY),
+        }
+    }
+
+    /// Key holding the snapshot for a single date (e.g. `exchange:rates:2024-12-04`)
+    fn history(&self, date: &str) -> String {
+        format!("{}{}{}", self.prefix, HISTORY_KEY_PREFIX, date)
+    }
+
+    /// Key holding the raw ECB payload for a date (e.g. `exchange:rates:raw:2024-12-04`)
+    fn raw(&self, date: &str) -> String {
+        format!("{}{}{}", self.prefix, RAW_KEY_PREFIX, date)
+    }
+}
+
+/// Pause before retrying a read whose connection dropped, giving ConnectionManager time to reconnect
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Run a read, retrying it once after RECONNECT_BACKOFF if the connection was lost
+/// Rejected commands fail at once; writes never go through here, since a dropped
+/// reply leaves it unknown whether they were applied
+async fn retry_once<T, F, Fut>(command: &str, mut run: F) -> RedisResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = RedisResult<T>>,
+{
+    match run().await {
+        Err(e) if is_connection_error(&e) => {
+            tracing::warn!("Redis connection lost during {}, retrying: {}", command, e);
+            tokio::time::sleep(RECONNECT_BACKOFF).await;
+            run().await
+        }
+        result => result,
+    }
+}
+
+/// Marker every PEM-encoded certificate starts with
+const PEM_CERTIFICATE_MARKER: &str = "-----BEGIN CERTIFICATE-----";
+
+/// Build the Redis client, trusting the CAs in `ca_cert` instead of the system store
+/// An unreadable bundle or one without certificates is rejected up front rather than
+/// surfacing later as a handshake failure
+fn build_client(redis_url: &str, ca_cert: Option<&Path>) -> Result<Client, ApiError> {
+    let Some(path) = ca_cert else {
+        return Client::open(redis_url).map_err(ApiError::RedisError);
+    };
+
+    let root_cert = std::fs::read(path).map_err(|e| {
+        ApiError::InternalError(format!(
+            "Cannot read REDIS_CA_CERT '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    if !String::from_utf8_lossy(&root_cert).contains(PEM_CERTIFICATE_MARKER) {
+        return Err(ApiError::InternalError(format!(
+            "REDIS_CA_CERT '{}' contains no PEM certificates",
+            path.display()
+        )));
+    }
+
+    Client::build_with_tls(
+        redis_url,
+        TlsCertificates {
+            client_tls: None,
+            root_cert: Some(root_cert),
+        },
+    )
+    .map_err(ApiError::RedisError)
+}
+
+/// Sorted set score for a date, so the history index orders chronologically
+fn date_score(date: NaiveDate) -> i32 {
+    date.num_days_from_ce()
+}
+
+/// Unix timestamp at which a snapshot for `date` falls out of the retention window
+/// Returns None when retention is disabled (0 days)
+fn history_expiry(date: NaiveDate, retention_days: u32) -> Option<i64> {
+    if retention_days == 0 {
+        return None;
+    }
+
+    date.checked_add_days(Days::new(retention_days as u64))
+        .and_then(|expiry| expiry.and_hms_opt(0, 0, 0))
+        .map(|expiry| expiry.and_utc().timestamp())
+}
+
+/// Deserialize a stored rates table
+fn parse_rates(data: &str) -> Result<DailyRate, ApiError> {
+    serde_json::from_str(data)
+        .map_err(|e| ApiError::InternalError(format!("Failed to deserialize rates: {}", e)))
+}
 
 #[derive(Clone)]
 pub struct RedisStore {
-    manager: ConnectionManager,
+    manager: TrackedConnection,
+    keys: RedisKeys,
+    history_retention_days: u32,
+    rates_ttl_secs: u64,
+    lookback_days: u32,
+    rebase_cache: RebaseCache,
+    rate_cache: RateCache,
+    latest: LatestCache,
 }
 
 impl RedisStore {
     /// Create a new Redis store with connection manager
-    pub async fn new(redis_url: &str) -> Result<Self, ApiError> {
+    /// Dated snapshots are kept for `history_retention_days` (0 keeps them forever)
+    /// All keys are prefixed with `key_prefix` (empty for none)
+    /// `ca_cert` is a PEM bundle trusted for rediss:// URLs instead of the system roots
+    pub async fn new(
+        redis_url: &str,
+        key_prefix: &str,
+        history_retention_days: u32,
+        ca_cert: Option<&Path>,
+    ) -> Result<Self, ApiError> {
         tracing::info!("Connecting to Redis at: {}", redis_url);
 
-        let client = Client::open(redis_url).map_err(|e| ApiError::RedisError(e))?;
+        let client = build_client(redis_url, ca_cert)?;
 
-        let manager = ConnectionManager::new(client)
-            .await
-            .map_err(|e| ApiError::RedisError(e))?;
+        let manager = TrackedConnection::new(ConnectionManager::new(client).await?);
 
         tracing::info!("Successfully connected to Redis");
 
-        Ok(Self { manager })
+        Ok(Self {
+            manager,
+            keys: RedisKeys::new(key_prefix),
+            history_retention_days,
+            rates_ttl_secs: 0,
+            lookback_days: DEFAULT_LOOKBACK_DAYS,
+            rebase_cache: RebaseCache::new(),
+            rate_cache: RateCache::default(),
+            latest: LatestCache::new(DEFAULT_LATEST_CACHE_TTL),
+        })
+    }
+
+    /// Expire the latest rates after `rates_ttl_secs` (0 keeps them forever)
+    /// Each successful update resets the expiry, including updates that find the stored
+    /// rates still current, so the keys only lapse when updates stop
+    pub fn with_rates_ttl(mut self, rates_ttl_secs: u64) -> Self {
+        self.rates_ttl_secs = rates_ttl_secs;
+        self
+    }
+
+    /// Serve the latest rates from memory for `ttl` before checking Redis again (zero checks every time)
+    pub fn with_latest_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.latest = LatestCache::new(ttl);
+        self
+    }
+
+    /// Cache up to `size` computed cross rates for conversions (0 disables the cache)
+    pub fn with_rate_cache_size(mut self, size: usize) -> Self {
+        self.rate_cache = RateCache::new(size);
+        self
+    }
+
+    /// Let historical lookups fall back at most `lookback_days` before the requested date
+    pub fn with_history_lookback(mut self, lookback_days: u32) -> Self {
+        self.lookback_days = lookback_days;
+        self
+    }
+
+    /// Drop the last handle on the Redis connection, closing it
+    /// Every clone shares one connection, so drop the other holders (app state, updater,
+    /// scheduler) first; one still held elsewhere, such as a detached refresh task, keeps
+    /// the connection open until the process exits, which is logged as a warning
+    pub fn close(self) {
+        let others = self.manager.handle_count().saturating_sub(1);
+        if others > 0 {
+            tracing::warn!(
+                "{} other Redis handle(s) still open, the connection closes when the process exits",
+                others
+            );
+        } else {
+            tracing::info!("Closing Redis connection");
+        }
+    }
+
+    /// Cache of rebased tables for the latest rates
+    pub fn rebase_cache(&self) -> &RebaseCache {
+        &self.rebase_cache
+    }
+
+    /// Cache of cross rates used by conversions
+    pub fn rate_cache(&self) -> &RateCache {
+        &self.rate_cache
     }
 
     /// Store exchange rates in Redis
@@ -35,51 +251,826 @@ impl RedisStore {
         let json = serde_json::to_string(rates)
             .map_err(|e| ApiError::InternalError(format!("Failed to serialize rates: {}", e)))?;
 
-        // Store both the rates and the date
-        conn.set::<_, _, ()>(RATES_KEY, json).await?;
-        conn.set::<_, _, ()>(DATE_KEY, &rates.date).await?;
+        // Store both the rates and the date, expiring them if updates stop arriving.
+        // MULTI/EXEC applies the two SETs as one step, and readers fetch both keys with a
+        // single MGET, so no reader (on this or another replica) can see the new rates
+        // with the old date or the other way round
+        let options = match self.rates_ttl_secs {
+            0 => SetOptions::default(),
+            ttl => SetOptions::default().with_expiration(SetExpiry::EX(ttl)),
+        };
+        redis::pipe()
+            .atomic()
+            .set_options(&self.keys.latest, &json, options)
+            .set_options(&self.keys.date, &rates.date, options)
+            .query_async::<()>(&mut conn)
+            .await?;
+
+        // Keep a dated snapshot for historical lookups
+        self.store_history(&mut conn, rates, &json).await?;
+
+        // Rebased tables were computed from the previous rates
+        self.rebase_cache.invalidate();
+        self.rate_cache.invalidate();
+        self.latest.set(Some(Arc::new(rates.clone())));
 
         tracing::info!("Stored exchange rates for {} in Redis", rates.date);
 
         Ok(())
     }
 
-    /// Retrieve exchange rates from Redis
-    pub async fn get_rates(&self) -> Result<Option<DailyRate>, ApiError> {
+    /// Restart the RATES_TTL_SECONDS expiry of the latest rates and their date without
+    /// rewriting them, for updates that find the stored rates still current
+    pub async fn touch_latest_rates(&self) -> Result<(), ApiError> {
+        if self.rates_ttl_secs == 0 {
+            return Ok(());
+        }
+
+        let mut conn = self.manager.clone();
+        let ttl = self.rates_ttl_secs as i64;
+        redis::pipe()
+            .atomic()
+            .expire(&self.keys.latest, ttl)
+            .expire(&self.keys.date, ttl)
+            .query_async::<()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store a dated snapshot only, leaving the latest rates untouched (used for backfills and imports)
+    pub async fn store_snapshot(&self, rates: &DailyRate) -> Result<(), ApiError> {
         let mut conn = self.manager.clone();
 
-        let json: Option<String> = conn.get(RATES_KEY).await?;
+        let json = serde_json::to_string(rates)
+            .map_err(|e| ApiError::InternalError(format!("Failed to serialize rates: {}", e)))?;
+
+        self.store_history(&mut conn, rates, &json).await?;
+
+        // Cross rates cached for this date were computed from the snapshot just replaced
+        self.rate_cache.invalidate();
 
-        match json {
-            Some(data) => {
-                let rates: DailyRate = serde_json::from_str(&data).map_err(|e| {
+        Ok(())
+    }
+
+    /// SET options expiring a dated key with the retention window
+    /// None when `date` has already left the window and should not be stored
+    fn retention_options(&self, date: NaiveDate) -> Option<SetOptions> {
+        match history_expiry(date, self.history_retention_days) {
+            Some(expiry) if expiry <= Utc::now().timestamp() => None,
+            Some(expiry) => {
+                Some(SetOptions::default().with_expiration(SetExpiry::EXAT(expiry as u64)))
+            }
+            None => Some(SetOptions::default()),
+        }
+    }
+
+    /// Try to take the cross-replica update lock, held for at most `ttl`
+    /// Returns false when another holder has it
+    pub async fn try_acquire_update_lock(
+        &self,
+        token: &str,
+        ttl: Duration,
+    ) -> Result<bool, ApiError> {
+        let mut conn = self.manager.clone();
+
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::PX(ttl.as_millis().max(1) as u64));
+        let acquired: Option<String> = conn
+            .set_options(&self.keys.update_lock, token, options)
+            .await?;
+
+        Ok(acquired.is_some())
+    }
+
+    /// Release the update lock if `token` still holds it
+    pub async fn release_update_lock(&self, token: &str) -> Result<(), ApiError> {
+        let mut conn = self.manager.clone();
+
+        Script::new(RELEASE_LOCK_SCRIPT)
+            .key(&self.keys.update_lock)
+            .arg(token)
+            .invoke_async::<()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether any replica currently holds the update lock
+    pub async fn is_update_locked(&self) -> Result<bool, ApiError> {
+        let mut conn = self.manager.clone();
+        let locked: bool = conn.exists(&self.keys.update_lock).await?;
+        Ok(locked)
+    }
+
+    /// Store the raw payload the rates for `date` were parsed from, so it can be re-parsed later
+    /// Raw payloads follow the same retention window as dated snapshots
+    pub async fn store_raw_xml(&self, date: &str, xml: &str) -> Result<(), ApiError> {
+        let mut conn = self.manager.clone();
+
+        let parsed = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| ApiError::InternalError(format!("Invalid rate date: {}", e)))?;
+
+        let Some(options) = self.retention_options(parsed) else {
+            tracing::debug!(
+                "Skipping raw payload for {}: outside retention window",
+                date
+            );
+            return Ok(());
+        };
+        conn.set_options::<_, _, ()>(self.keys.raw(date), xml, options)
+            .await?;
+
+        tracing::debug!("Stored raw ECB payload for {}", date);
+
+        Ok(())
+    }
+
+    /// Store a dated snapshot and register it in the history index
+    /// Snapshots expire once their date leaves the retention window
+    async fn store_history(
+        &self,
+        conn: &mut TrackedConnection,
+        rates: &DailyRate,
+        json: &str,
+    ) -> Result<(), ApiError> {
+        let date = NaiveDate::parse_from_str(&rates.date, "%Y-%m-%d")
+            .map_err(|e| ApiError::InternalError(format!("Invalid rate date: {}", e)))?;
+
+        let Some(options) = self.retention_options(date) else {
+            tracing::debug!("Skipping snapshot for {}: outside retention window", date);
+            return Ok(());
+        };
+
+        conn.set_options::<_, _, ()>(self.keys.history(&rates.date), json, options)
+            .await?;
+        conn.zadd::<_, _, _, ()>(&self.keys.history_index, &rates.date, date_score(date))
+            .await?;
+
+        // Drop index entries whose snapshots have expired
+        if let Some(cutoff) = self.retention_cutoff() {
+            conn.zrembyscore::<_, _, _, ()>(&self.keys.history_index, "-inf", cutoff)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Lowest date score still inside the retention window
+    fn retention_cutoff(&self) -> Option<i32> {
+        if self.history_retention_days == 0 {
+            return None;
+        }
+
+        Utc::now()
+            .date_naive()
+            .checked_sub_days(Days::new(self.history_retention_days as u64))
+            .map(date_score)
+    }
+
+    /// List the dates (YYYY-MM-DD) with a stored snapshot, oldest first
+    pub async fn list_available_dates(&self) -> Result<Vec<String>, ApiError> {
+        // Skip entries that already fell out of the retention window
+        let min = match self.retention_cutoff() {
+            Some(cutoff) => format!("({}", cutoff),
+            None => "-inf".to_string(),
+        };
+
+        let dates: Vec<String> = retry_once("ZRANGEBYSCORE", || {
+            let mut conn = self.manager.clone();
+            let min = min.clone();
+            async move {
+                conn.zrangebyscore(&self.keys.history_index, min, "+inf")
+                    .await
+            }
+        })
+        .await?;
+        Ok(dates)
+    }
+
+    /// Retrieve all stored snapshots between two dates (inclusive), oldest first
+    /// Days without a snapshot are skipped
+    pub async fn get_rates_range(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyRate>, ApiError> {
+        let dates: Vec<String> = retry_once("ZRANGEBYSCORE", || {
+            let mut conn = self.manager.clone();
+            async move {
+                conn.zrangebyscore(&self.keys.history_index, date_score(start), date_score(end))
+                    .await
+            }
+        })
+        .await?;
+
+        if dates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys: Vec<String> = dates.iter().map(|date| self.keys.history(date)).collect();
+        let snapshots: Vec<Option<String>> = retry_once("MGET", || {
+            let mut conn = self.manager.clone();
+            let keys = keys.clone();
+            async move { conn.mget(keys).await }
+        })
+        .await?;
+
+        snapshots
+            .into_iter()
+            .flatten()
+            .map(|data| {
+                serde_json::from_str(&data).map_err(|e| {
                     ApiError::InternalError(format!("Failed to deserialize rates: {}", e))
-                })?;
+                })
+            })
+            .collect()
+    }
 
+    /// Retrieve exchange rates from Redis
+    /// Served from memory for the cache TTL; after that only the stored date is read
+    /// while it matches the in-memory copy, and the table itself is fetched (together
+    /// with its date, see `read_latest`) and deserialized again only after it changes
+    pub async fn get_rates(&self) -> Result<Option<Arc<DailyRate>>, ApiError> {
+        if let Some(latest) = self.latest.fresh() {
+            return Ok(Some(latest));
+        }
+
+        let Some(date) = self.get_last_update_date().await? else {
+            tracing::warn!("No exchange rates found in Redis");
+            self.latest.set(None);
+            return Ok(None);
+        };
+
+        if let Some(latest) = self.latest.revalidate(&date) {
+            return Ok(Some(latest));
+        }
+
+        let (date, rates) = self.read_latest().await?;
+        let rates = rates.map(Arc::new);
+
+        match &rates {
+            Some(rates) if date.as_deref() != Some(rates.date.as_str()) => {
+                tracing::warn!(
+                    "Latest exchange rates for {} stored with date {:?}",
+                    rates.date,
+                    date
+                );
+            }
+            Some(rates) => {
                 tracing::debug!("Retrieved exchange rates for {} from Redis", rates.date);
-                Ok(Some(rates))
             }
             None => {
                 tracing::warn!("No exchange rates found in Redis");
+            }
+        }
+
+        self.latest.set(rates.clone());
+
+        Ok(rates)
+    }
+
+    /// Read the latest table and the stored date in one MGET, so both come from the
+    /// same point between two `store_rates` transactions and always agree
+    async fn read_latest(&self) -> Result<(Option<String>, Option<DailyRate>), ApiError> {
+        let (json, date): (Option<String>, Option<String>) = retry_once("MGET", || {
+            let mut conn = self.manager.clone();
+            async move { conn.mget(&[&self.keys.latest, &self.keys.date]).await }
+        })
+        .await?;
+
+        Ok((date, json.as_deref().map(parse_rates).transpose()?))
+    }
+
+    /// Retrieve the most recent snapshot dated on or before `date`, looking back
+    /// at most the configured number of days; the snapshot's `date` is the one used
+    /// ECB doesn't publish on weekends and TARGET holidays, so a Saturday request
+    /// is served the preceding Friday's rates
+    pub async fn get_rates_on_or_before(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<DailyRate>, ApiError> {
+        let earliest = earliest_fallback(date, self.lookback_days);
+        let dates: Vec<String> = retry_once("ZRANGEBYSCORE", || {
+            let mut conn = self.manager.clone();
+            async move {
+                conn.zrangebyscore(
+                    &self.keys.history_index,
+                    date_score(earliest),
+                    date_score(date),
+                )
+                .await
+            }
+        })
+        .await?;
+        let available = dates
+            .iter()
+            .filter_map(|found| NaiveDate::parse_from_str(found, "%Y-%m-%d").ok());
+
+        match nearest_available(date, available, self.lookback_days) {
+            Some(found) => {
+                self.get_snapshot(&self.keys.history(&found.format("%Y-%m-%d").to_string()))
+                    .await
+            }
+            None => {
+                tracing::debug!(
+                    "No exchange rates snapshot found within {} days before {}",
+                    self.lookback_days,
+                    date
+                );
                 Ok(None)
             }
         }
     }
 
+    /// Read and deserialize a rates snapshot stored under the given key
+    async fn get_snapshot(&self, key: &str) -> Result<Option<DailyRate>, ApiError> {
+        let json: Option<String> = retry_once("GET", || {
+            let mut conn = self.manager.clone();
+            async move { conn.get(key).await }
+        })
+        .await?;
+
+        json.as_deref().map(parse_rates).transpose()
+    }
+
+    /// Whether latest exchange rates are currently stored
+    pub async fn has_rates(&self) -> Result<bool, ApiError> {
+        let key = &self.keys.latest;
+        let exists: bool = retry_once("EXISTS", || {
+            let mut conn = self.manager.clone();
+            async move { conn.exists(key).await }
+        })
+        .await?;
+        Ok(exists)
+    }
+
+    /// Prepend an entry to the update log, trimming it to UPDATE_LOG_CAPACITY
+    pub async fn append_update_log(&self, entry: &UpdateLogEntry) -> Result<(), ApiError> {
+        let mut conn = self.manager.clone();
+
+        let json = serde_json::to_string(entry).map_err(|e| {
+            ApiError::InternalError(format!("Failed to serialize update log entry: {}", e))
+        })?;
+
+        redis::pipe()
+            .atomic()
+            .lpush(&self.keys.update_log, json)
+            .ltrim(&self.keys.update_log, 0, UPDATE_LOG_CAPACITY as isize - 1)
+            .query_async::<()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The newest `limit` update log entries, newest first
+    /// Entries that no longer deserialize are skipped rather than failing the listing
+    pub async fn get_update_log(&self, limit: usize) -> Result<Vec<UpdateLogEntry>, ApiError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let entries: Vec<String> = retry_once("LRANGE", || {
+            let mut conn = self.manager.clone();
+            async move {
+                conn.lrange(&self.keys.update_log, 0, limit as isize - 1)
+                    .await
+            }
+        })
+        .await?;
+
+        Ok(entries
+            .iter()
+            .filter_map(|json| match serde_json::from_str(json) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    tracing::warn!("Skipping malformed update log entry: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
     /// Get the date of last update
     pub async fn get_last_update_date(&self) -> Result<Option<String>, ApiError> {
-        let mut conn = self.manager.clone();
-        let date: Option<String> = conn.get(DATE_KEY).await?;
+        let date: Option<String> = retry_once("GET", || {
+            let mut conn = self.manager.clone();
+            async move { conn.get(&self.keys.date).await }
+        })
+        .await?;
         Ok(date)
     }
 
-    /// Health check for Redis connection
+    /// Health check for Redis connection; a successful PING's round trip shows in connection_stats()
     pub async fn health_check(&self) -> Result<(), ApiError> {
-        let mut conn = self.manager.clone();
-        redis::cmd("PING")
-            .query_async::<()>(&mut conn)
-            .await
-            .map_err(|e| ApiError::RedisError(e))?;
+        self.manager.ping().await?;
         Ok(())
     }
+
+    /// Last PING latency and reconnect count of the shared connection
+    pub fn connection_stats(&self) -> RedisStats {
+        self.manager.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+
+    fn create_test_rates(date: &str) -> DailyRate {
+        let mut rates = HashMap::new();
+        rates.insert("USD".to_string(), dec!(1.05));
+        rates.insert("EUR".to_string(), dec!(1.0));
+
+        DailyRate {
+            date: date.to_string(),
+            base: "EUR".to_string(),
+            rates,
+        }
+    }
+
+    #[test]
+    fn test_history_key() {
+        assert_eq!(
+            RedisKeys::new("").history("2024-12-04"),
+            "exchange:rates:2024-12-04"
+        );
+    }
+
+    #[test]
+    fn test_keys_carry_prefix() {
+        let keys = RedisKeys::new("app1:");
+
+        assert_eq!(keys.latest, "app1:exchange:rates:latest");
+        assert_eq!(keys.date, "app1:exchange:rates:date");
+        assert_eq!(keys.history_index, "app1:exchange:rates:history");
+        assert_eq!(keys.history("2024-12-04"), "app1:exchange:rates:2024-12-04");
+        assert_eq!(keys.raw("2024-12-04"), "app1:exchange:rates:raw:2024-12-04");
+        assert_eq!(keys.update_lock, "app1:exchange:rates:update-lock");
+    }
+
+    #[test]
+    fn test_date_score_is_chronological() {
+        let earlier = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let later = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        assert_eq!(date_score(later) - date_score(earlier), 1);
+    }
+
+    #[test]
+    fn test_history_expiry() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 4).unwrap();
+
+        // 2024-12-14T00:00:00Z
+        assert_eq!(history_expiry(date, 10), Some(1734134400));
+        // Retention disabled
+        assert_eq!(history_expiry(date, 0), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_once_after_dropped_connection() {
+        let mut attempts = 0;
+
+        let result = retry_once("GET", || {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt == 1 {
+                    Err(redis::RedisError::from(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionReset,
+                        "connection reset",
+                    )))
+                } else {
+                    Ok("2024-12-04")
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "2024-12-04");
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_once_gives_up_after_second_failure() {
+        let mut attempts = 0;
+
+        let result: RedisResult<()> = retry_once("GET", || {
+            attempts += 1;
+            async {
+                Err(redis::RedisError::from(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionRefused,
+                    "connection refused",
+                )))
+            }
+        })
+        .await;
+
+        assert!(matches!(
+            result.map_err(ApiError::from),
+            Err(ApiError::RedisUnavailable(_))
+        ));
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_once_skips_command_errors() {
+        let mut attempts = 0;
+
+        let result: RedisResult<()> = retry_once("GET", || {
+            attempts += 1;
+            async {
+                Err(redis::RedisError::from((
+                    redis::ErrorKind::TypeError,
+                    "WRONGTYPE",
+                )))
+            }
+        })
+        .await;
+
+        assert!(matches!(
+            result.map_err(ApiError::from),
+            Err(ApiError::RedisError(_))
+        ));
+        assert_eq!(attempts, 1);
+    }
+
+    fn write_ca_cert(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.pem", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_build_client_without_ca_cert() {
+        assert!(build_client("redis://localhost:6379", None).is_ok());
+    }
+
+    #[test]
+    fn test_build_client_rejects_missing_ca_cert() {
+        let path = Path::new("/nonexistent/redis-ca.pem");
+
+        let err = build_client("rediss://localhost:6380", Some(path)).unwrap_err();
+
+        assert!(matches!(err, ApiError::InternalError(msg) if msg.contains("Cannot read")));
+    }
+
+    #[test]
+    fn test_build_client_rejects_unparseable_ca_cert() {
+        let empty = write_ca_cert("redis-ca-empty", "not a certificate\n");
+        let corrupt = write_ca_cert(
+            "redis-ca-corrupt",
+            "-----BEGIN CERTIFICATE-----\n!!!!\n-----END CERTIFICATE-----\n",
+        );
+
+        let empty_err = build_client("rediss://localhost:6380", Some(&empty)).unwrap_err();
+        let corrupt_err = build_client("rediss://localhost:6380", Some(&corrupt)).unwrap_err();
+        std::fs::remove_file(empty).unwrap();
+        std::fs::remove_file(corrupt).unwrap();
+
+        assert!(
+            matches!(empty_err, ApiError::InternalError(msg) if msg.contains("no PEM certificates"))
+        );
+        assert!(matches!(corrupt_err, ApiError::RedisError(_)));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_store_keeps_history() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let store = RedisStore::new(&redis_url, "test-history:", 0, None)
+            .await
+            .unwrap();
+
+        store
+            .store_rates(&create_test_rates("2024-12-03"))
+            .await
+            .unwrap();
+        store
+            .store_rates(&create_test_rates("2024-12-04"))
+            .await
+            .unwrap();
+
+        // Latest is overwritten, but both days remain available
+        let latest = store.get_rates().await.unwrap().unwrap();
+        assert_eq!(latest.date, "2024-12-04");
+
+        let dates = store.list_available_dates().await.unwrap();
+        let position = |date: &str| dates.iter().position(|d| d == date).unwrap();
+        assert!(position("2024-12-03") < position("2024-12-04"));
+
+        let previous = NaiveDate::from_ymd_opt(2024, 12, 3).unwrap();
+        let snapshot = store
+            .get_rates_on_or_before(previous)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.date, "2024-12-03");
+        assert_eq!(snapshot.rates["USD"], dec!(1.05));
+
+        // A weekend date is served the preceding business day's snapshot
+        let saturday = NaiveDate::from_ymd_opt(2024, 12, 7).unwrap();
+        let snapshot = store
+            .get_rates_on_or_before(saturday)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.date, "2024-12-04");
+
+        // ...but only within the lookback window
+        let store = store.with_history_lookback(2);
+        let sunday = NaiveDate::from_ymd_opt(2024, 12, 8).unwrap();
+        assert!(
+            store
+                .get_rates_on_or_before(sunday)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_rates_and_date_swap_together() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let store = RedisStore::new(&redis_url, "test-swap:", 0, None)
+            .await
+            .unwrap();
+        let reader = RedisStore::new(&redis_url, "test-swap:", 0, None)
+            .await
+            .unwrap()
+            .with_latest_cache_ttl(Duration::ZERO);
+        store
+            .store_rates(&create_test_rates("2024-12-03"))
+            .await
+            .unwrap();
+
+        let writer = {
+            let store = store.clone();
+            tokio::spawn(async move {
+                for i in 0..200 {
+                    let date = if i % 2 == 0 {
+                        "2024-12-03"
+                    } else {
+                        "2024-12-04"
+                    };
+                    store.store_rates(&create_test_rates(date)).await.unwrap();
+                }
+            })
+        };
+
+        // The date read alongside the table must be the table's own, mid-swap or not
+        while !writer.is_finished() {
+            let (date, rates) = reader.read_latest().await.unwrap();
+            assert_eq!(date, rates.map(|rates| rates.date));
+        }
+        writer.await.unwrap();
+
+        let rates = reader.get_rates().await.unwrap().unwrap();
+        assert_eq!(rates.date, "2024-12-04");
+        assert_eq!(
+            reader.get_last_update_date().await.unwrap().as_deref(),
+            Some("2024-12-04")
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_update_log_is_capped_newest_first() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let store = RedisStore::new(&redis_url, "test-update-log:", 0, None)
+            .await
+            .unwrap();
+
+        for i in 0..UPDATE_LOG_CAPACITY + 5 {
+            store
+                .append_update_log(&UpdateLogEntry {
+                    timestamp: format!("2024-12-04T15:{:02}:00Z", i % 60),
+                    date: "2024-12-04".to_string(),
+                    rate_count: i,
+                    source: "test".to_string(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let log = store.get_update_log(UPDATE_LOG_CAPACITY * 2).await.unwrap();
+        assert_eq!(log.len(), UPDATE_LOG_CAPACITY);
+        assert_eq!(log[0].rate_count, UPDATE_LOG_CAPACITY + 4);
+        assert_eq!(store.get_update_log(2).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_prefixed_stores_are_isolated() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let first = RedisStore::new(&redis_url, "test-a:", 0, None)
+            .await
+            .unwrap();
+        let second = RedisStore::new(&redis_url, "test-b:", 0, None)
+            .await
+            .unwrap();
+
+        first
+            .store_rates(&create_test_rates("2024-12-04"))
+            .await
+            .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2024, 12, 4).unwrap();
+        assert!(first.get_rates().await.unwrap().is_some());
+        assert!(second.get_rates().await.unwrap().is_none());
+        assert!(second.get_rates_on_or_before(date).await.unwrap().is_none());
+        assert!(second.list_available_dates().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_latest_rates_expire() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let store = RedisStore::new(&redis_url, "test-ttl:", 0, None)
+            .await
+            .unwrap()
+            .with_rates_ttl(1)
+            .with_latest_cache_ttl(Duration::ZERO);
+
+        store
+            .store_rates(&create_test_rates("2024-12-04"))
+            .await
+            .unwrap();
+        assert!(store.get_rates().await.unwrap().is_some());
+
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+
+        assert!(store.get_rates().await.unwrap().is_none());
+        assert!(store.get_last_update_date().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_latest_rates_reused_until_date_changes() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let writer = RedisStore::new(&redis_url, "test-latest:", 0, None)
+            .await
+            .unwrap();
+        let reader = RedisStore::new(&redis_url, "test-latest:", 0, None)
+            .await
+            .unwrap()
+            .with_latest_cache_ttl(Duration::ZERO);
+
+        writer
+            .store_rates(&create_test_rates("2024-12-04"))
+            .await
+            .unwrap();
+
+        let first = reader.get_rates().await.unwrap().unwrap();
+        let second = reader.get_rates().await.unwrap().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Another replica storing a newer date is picked up on the next read
+        writer
+            .store_rates(&create_test_rates("2024-12-05"))
+            .await
+            .unwrap();
+
+        let third = reader.get_rates().await.unwrap().unwrap();
+        assert_eq!(third.date, "2024-12-05");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis at REDIS_URL"]
+    async fn test_reimported_snapshot_drops_cached_cross_rates() {
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        let store = RedisStore::new(&redis_url, "test-import:", 0, None)
+            .await
+            .unwrap()
+            .with_rate_cache_size(8);
+        let snapshot = create_test_rates("2024-12-03");
+
+        store.store_snapshot(&snapshot).await.unwrap();
+        let cached = store
+            .rate_cache()
+            .get_or_compute(&snapshot, "EUR", "USD", || Ok(dec!(1.05)))
+            .unwrap();
+        assert_eq!(cached, dec!(1.05));
+
+        // An import rewriting the same date must not keep serving the old cross rate
+        let mut corrected = snapshot.clone();
+        corrected.rates.insert("USD".to_string(), dec!(1.06));
+        store.store_snapshot(&corrected).await.unwrap();
+        let rate = store
+            .rate_cache()
+            .get_or_compute(&corrected, "EUR", "USD", || Ok(dec!(1.06)))
+            .unwrap();
+        assert_eq!(rate, dec!(1.06));
+    }
 }
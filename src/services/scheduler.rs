@@ -1,41 +1,285 @@
-use crate::services::{EcbFetcher, RedisStore};
+use crate::error::ApiError;
+use crate::models::{DailyRate, UpdateLogEntry};
+use crate::services::{ConditionalFetch, EcbFetcher, RateAlerter, RateProvider, RedisStore};
+use crate::telemetry::{MISSING_REQUIRED_CURRENCY_TOTAL, RATE_UPDATES_TOTAL, RATES_LOADED};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use croner::{Cron, errors::CronError, parser::CronParser};
+use rust_decimal::Decimal;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{Mutex, broadcast};
 use tokio_cron_scheduler::{Job, JobScheduler};
 
+/// Field names of a six-field cron expression, in order
+const CRON_FIELDS: [&str; 6] = [
+    "second",
+    "minute",
+    "hour",
+    "day of month",
+    "month",
+    "day of week",
+];
+
+/// Parse a cron expression with the same rules as the job scheduler
+/// (six fields, seconds required); errors name the offending field
+pub fn parse_cron(expression: &str) -> Result<Cron, String> {
+    parse_cron_raw(expression).map_err(|e| describe_cron_error(expression, e))
+}
+
+fn parse_cron_raw(expression: &str) -> Result<Cron, CronError> {
+    CronParser::builder()
+        .seconds(croner::parser::Seconds::Required)
+        .dom_and_dow(true)
+        .build()
+        .parse(expression)
+}
+
+/// Locate the first field that fails to parse on its own (others set to `*`)
+fn describe_cron_error(expression: &str, error: CronError) -> String {
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    if fields.len() != CRON_FIELDS.len() {
+        return format!(
+            "expected {} fields (second minute hour day month weekday), got {}",
+            CRON_FIELDS.len(),
+            fields.len()
+        );
+    }
+
+    for (index, field) in fields.iter().enumerate() {
+        let mut probe = ["*"; 6];
+        probe[index] = field;
+        if parse_cron_raw(&probe.join(" ")).is_err() {
+            return format!(
+                "{} field '{}' is out of range or malformed",
+                CRON_FIELDS[index], field
+            );
+        }
+    }
+
+    error.to_string()
+}
+
+/// Next `count` fire times strictly after `after`
+pub fn next_fire_times(cron: &Cron, after: DateTime<Utc>, count: usize) -> Vec<DateTime<Utc>> {
+    cron.iter_after(after).take(count).collect()
+}
+
+/// Stored updates buffered per subscriber before slow subscribers start skipping
+const UPDATE_CHANNEL_CAPACITY: usize = 16;
+
+/// Runs rate updates one at a time; shared by the scheduler and manual refreshes
+/// Every stored update is broadcast to subscribers (e.g. WebSocket clients)
+#[derive(Clone)]
+pub struct RateUpdater {
+    provider: Arc<dyn RateProvider>,
+    store: RedisStore,
+    policy: Arc<UpdatePolicy>,
+    missing_required: Arc<RwLock<Vec<String>>>,
+    in_progress: Arc<Mutex<()>>,
+    updates: broadcast::Sender<Arc<DailyRate>>,
+    /// Date of the rates this replica last stored or broadcast
+    last_seen: Arc<RwLock<Option<String>>>,
+}
+
+impl RateUpdater {
+    pub fn new(provider: Arc<dyn RateProvider>, store: RedisStore) -> Self {
+        let (updates, _) = broadcast::channel(UPDATE_CHANNEL_CAPACITY);
+
+        Self {
+            provider,
+            store,
+            policy: Arc::new(UpdatePolicy::default()),
+            missing_required: Arc::new(RwLock::new(Vec::new())),
+            in_progress: Arc::new(Mutex::new(())),
+            updates,
+            last_seen: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Checks and extras applied to every update
+    pub fn with_policy(mut self, policy: UpdatePolicy) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// Required currencies missing from the last fetched rates (empty once an update succeeds)
+    pub fn missing_required(&self) -> Vec<String> {
+        self.missing_required
+            .read()
+            .map(|missing| missing.clone())
+            .unwrap_or_default()
+    }
+
+    /// Fetch and store rates now, failing fast if another update is running
+    pub async fn update(&self) -> Result<DailyRate, ApiError> {
+        let _guard = self
+            .in_progress
+            .try_lock()
+            .map_err(|_| ApiError::RefreshInProgress)?;
+
+        let last_seen = self.last_seen.read().ok().and_then(|date| date.clone());
+        let result = update_rates(
+            self.provider.as_ref(),
+            &self.store,
+            &self.policy,
+            last_seen.as_deref(),
+        )
+        .await;
+
+        // Other failures say nothing about the feed's contents, so keep the last known state
+        let missing = match &result {
+            Ok(_) => Some(Vec::new()),
+            Err(ApiError::MissingRequiredCurrencies(codes)) => Some(codes.clone()),
+            Err(_) => None,
+        };
+        if let (Some(missing), Ok(mut current)) = (missing, self.missing_required.write()) {
+            *current = missing;
+        }
+
+        let UpdateOutcome { rates, changed } = result?;
+        if let Ok(mut last_seen) = self.last_seen.write() {
+            *last_seen = Some(rates.date.clone());
+        }
+
+        // Precompute popular bases so their first request skips the rebase
+        let warmed = self
+            .store
+            .rebase_cache()
+            .warm(&rates, &self.policy.warmup_bases);
+        if warmed > 0 {
+            tracing::debug!("Warmed {} rebased tables for {}", warmed, rates.date);
+        }
+
+        // Subscribers already have rates for this date; sending only fails when nobody is subscribed
+        if changed {
+            let _ = self.updates.send(Arc::new(rates.clone()));
+        }
+
+        Ok(rates)
+    }
+
+    /// Wait for an in-flight update to finish (returns at once when idle)
+    pub async fn wait_idle(&self) {
+        let _guard = self.in_progress.lock().await;
+    }
+
+    /// Receive every rate update stored from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<DailyRate>> {
+        self.updates.subscribe()
+    }
+}
+
+/// How long past its due time a scheduled run may take before /health flags it
+const SCHEDULER_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// When the scheduled job last completed an update, shared with /health so a
+/// scheduler that stopped firing shows up instead of failing silently
+#[derive(Debug, Clone)]
+pub struct SchedulerLiveness {
+    cron: Cron,
+    started: DateTime<Utc>,
+    last_run: Arc<RwLock<Option<DateTime<Utc>>>>,
+}
+
+impl SchedulerLiveness {
+    pub fn new(cron: Cron, started: DateTime<Utc>) -> Self {
+        Self {
+            cron,
+            started,
+            last_run: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Time of the last successful (or refresh-overlapped) scheduled update, None until one succeeds
+    pub fn last_run(&self) -> Option<DateTime<Utc>> {
+        self.last_run.read().ok().and_then(|last_run| *last_run)
+    }
+
+    fn record_run(&self, at: DateTime<Utc>) {
+        if let Ok(mut last_run) = self.last_run.write() {
+            *last_run = Some(at);
+        }
+    }
+
+    /// Record the outcome of a scheduled update finished at `at`
+    /// A run that found a manual refresh already updating still counts: the job fired
+    /// and the rates are being brought up to date
+    fn record_outcome(&self, result: &Result<DailyRate, ApiError>, at: DateTime<Utc>) {
+        match result {
+            Ok(_) => {
+                self.record_run(at);
+                tracing::info!("Successfully completed scheduled exchange rate update");
+            }
+            Err(ApiError::RefreshInProgress) => {
+                self.record_run(at);
+                tracing::info!("Skipped scheduled exchange rate update, a refresh is running");
+            }
+            Err(e) => {
+                tracing::error!("Scheduled update failed: {}", e);
+            }
+        }
+    }
+
+    /// Whether the run due after the last success (or startup) is more than
+    /// SCHEDULER_GRACE late, i.e. the job stopped firing or keeps failing
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        let since = self.last_run().unwrap_or(self.started);
+        next_fire_times(&self.cron, since, 1)
+            .first()
+            .is_some_and(|due| {
+                (now - *due)
+                    .to_std()
+                    .is_ok_and(|late| late > SCHEDULER_GRACE)
+            })
+    }
+}
+
 pub struct RateScheduler {
     scheduler: JobScheduler,
+    liveness: SchedulerLiveness,
 }
 
 impl RateScheduler {
     /// Create a new scheduler for updating exchange rates
     pub async fn new(
         cron_expression: String,
-        fetcher: EcbFetcher,
-        store: RedisStore,
+        updater: RateUpdater,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let scheduler = JobScheduler::new().await?;
 
+        // Log upcoming runs so operators can confirm the schedule
+        let cron = parse_cron(&cron_expression)?;
+        for time in next_fire_times(&cron, Utc::now(), 3) {
+            tracing::info!("Next scheduled rate update: {}", time.to_rfc3339());
+        }
+        let liveness = SchedulerLiveness::new(cron, Utc::now());
+
         // Create the scheduled job
+        let job_liveness = liveness.clone();
         let job = Job::new_async(cron_expression.as_str(), move |_uuid, _lock| {
-            let fetcher = fetcher.clone();
-            let store = store.clone();
+            let updater = updater.clone();
+            let liveness = job_liveness.clone();
 
             Box::pin(async move {
                 tracing::info!("Starting scheduled exchange rate update");
 
-                match update_rates(&fetcher, &store).await {
-                    Ok(_) => {
-                        tracing::info!("Successfully completed scheduled exchange rate update");
-                    }
-                    Err(e) => {
-                        tracing::error!("Scheduled update failed: {}", e);
-                    }
-                }
+                let result = updater.update().await;
+                liveness.record_outcome(&result, Utc::now());
             })
         })?;
 
         scheduler.add(job).await?;
 
-        Ok(Self { scheduler })
+        Ok(Self {
+            scheduler,
+            liveness,
+        })
+    }
+
+    /// Handle on the job's last successful run, for /health
+    pub fn liveness(&self) -> SchedulerLiveness {
+        self.liveness.clone()
     }
 
     /// Start the scheduler
@@ -53,14 +297,257 @@ impl RateScheduler {
     }
 }
 
+/// Checks and extras applied by update_rates()
+#[derive(Debug, Clone, Default)]
+pub struct UpdatePolicy {
+    /// Currencies every accepted update must contain
+    pub required_currencies: Vec<String>,
+    /// Keep the raw payload of each accepted update for later re-parsing
+    pub store_raw_xml: bool,
+    /// Let only one replica fetch at a time (None updates unconditionally)
+    pub lock: Option<UpdateLock>,
+    /// Bases whose rebased tables are precomputed after each update
+    pub warmup_bases: Vec<String>,
+    /// Webhook alerted when an update moves a rate past its threshold
+    pub alert: Option<RateAlerter>,
+}
+
+/// Rates produced by a successful update_rates() run
+#[derive(Debug, Clone)]
+pub struct UpdateOutcome {
+    pub rates: DailyRate,
+    /// False when rates for the fetched date were already stored, so nothing was written
+    pub changed: bool,
+}
+
+/// Cross-replica lock taken around each update
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateLock {
+    /// Expiry of the lock, in case its holder dies mid-update
+    pub ttl: Duration,
+    /// How long a replica that lost the race waits for the holder before reading the store
+    pub wait: Duration,
+}
+
+/// Interval between checks whether another replica's update has finished
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Destination for validated rate updates
+#[async_trait]
+pub trait RateSink: Send + Sync {
+    async fn store_rates(&self, rates: &DailyRate) -> Result<(), ApiError>;
+
+    /// Keep the raw payload the rates for `date` were parsed from
+    async fn store_raw(&self, date: &str, raw: &str) -> Result<(), ApiError>;
+
+    /// Append a successful update to the audit log
+    async fn record_update(&self, entry: &UpdateLogEntry) -> Result<(), ApiError>;
+
+    /// Renew the expiry of the latest rates when an update finds them still current
+    async fn touch_latest(&self) -> Result<(), ApiError>;
+
+    /// Latest stored rates, read by replicas that skipped the fetch
+    async fn load_rates(&self) -> Result<Option<DailyRate>, ApiError>;
+
+    /// Date of the latest stored rates, compared against each fetch to skip repeats
+    async fn last_update_date(&self) -> Result<Option<String>, ApiError>;
+
+    /// Take the update lock for `token`; false when another replica holds it
+    async fn try_lock(&self, token: &str, ttl: Duration) -> Result<bool, ApiError>;
+
+    /// Release the update lock if `token` still holds it
+    async fn unlock(&self, token: &str) -> Result<(), ApiError>;
+
+    /// Whether any replica holds the update lock
+    async fn is_locked(&self) -> Result<bool, ApiError>;
+}
+
+#[async_trait]
+impl RateSink for RedisStore {
+    async fn store_rates(&self, rates: &DailyRate) -> Result<(), ApiError> {
+        RedisStore::store_rates(self, rates).await
+    }
+
+    async fn store_raw(&self, date: &str, raw: &str) -> Result<(), ApiError> {
+        self.store_raw_xml(date, raw).await
+    }
+
+    async fn record_update(&self, entry: &UpdateLogEntry) -> Result<(), ApiError> {
+        self.append_update_log(entry).await
+    }
+
+    async fn touch_latest(&self) -> Result<(), ApiError> {
+        self.touch_latest_rates().await
+    }
+
+    async fn load_rates(&self) -> Result<Option<DailyRate>, ApiError> {
+        Ok(self.get_rates().await?.map(Arc::unwrap_or_clone))
+    }
+
+    async fn last_update_date(&self) -> Result<Option<String>, ApiError> {
+        self.get_last_update_date().await
+    }
+
+    async fn try_lock(&self, token: &str, ttl: Duration) -> Result<bool, ApiError> {
+        self.try_acquire_update_lock(token, ttl).await
+    }
+
+    async fn unlock(&self, token: &str) -> Result<(), ApiError> {
+        self.release_update_lock(token).await
+    }
+
+    async fn is_locked(&self) -> Result<bool, ApiError> {
+        self.is_update_locked().await
+    }
+}
+
+/// Check fetched rates before they may replace the stored ones
+pub fn validate_rates(rates: &DailyRate) -> Result<(), String> {
+    rates.validate_date()?;
+
+    if !rates.rates.keys().any(|code| *code != rates.base) {
+        return Err(format!(
+            "no rates besides {} for {}",
+            rates.base, rates.date
+        ));
+    }
+
+    if let Some((code, rate)) = rates.rates.iter().find(|(_, rate)| **rate <= Decimal::ZERO) {
+        return Err(format!("non-positive rate {} for {}", rate, code));
+    }
+
+    Ok(())
+}
+
+/// Required currency codes absent from `rates`, in the order they were configured
+pub fn missing_currencies(rates: &DailyRate, required: &[String]) -> Vec<String> {
+    required
+        .iter()
+        .filter(|code| !rates.rates.contains_key(*code))
+        .cloned()
+        .collect()
+}
+
 /// Perform an immediate update of exchange rates (used for initial fetch and scheduled updates)
+/// Invalid data is rejected before storing, so the last good rates stay in place
+/// Rates lacking any of the policy's required currencies are rejected the same way
+/// Rates for a date that is already stored are not written again, so UPDATE_CRON
+/// can fire several times around the ECB publication without redundant updates;
+/// a provider reporting the source unchanged skips the download altogether
+/// With a lock configured, only the replica holding it fetches; the others wait for
+/// it to finish and return whatever is then stored, changed only when its date differs
+/// from `last_seen`, the date this replica last stored or broadcast
 pub async fn update_rates(
-    fetcher: &EcbFetcher,
-    store: &RedisStore,
-) -> Result<(), Box<dyn std::error::Error>> {
-    tracing::info!("Fetching latest exchange rates from ECB");
+    provider: &dyn RateProvider,
+    store: &dyn RateSink,
+    policy: &UpdatePolicy,
+    last_seen: Option<&str>,
+) -> Result<UpdateOutcome, ApiError> {
+    let Some(lock) = policy.lock else {
+        return record_outcome(fetch_and_store(provider, store, policy).await);
+    };
 
-    let rates = fetcher.fetch_rates().await?;
+    let token = lock_token();
+    if store.try_lock(&token, lock.ttl).await? {
+        let result = record_outcome(fetch_and_store(provider, store, policy).await);
+
+        // The lock expires on its own, so a failed release only delays the next update
+        if let Err(e) = store.unlock(&token).await {
+            tracing::warn!("Failed to release rate update lock: {}", e);
+        }
+
+        return result;
+    }
+
+    tracing::info!("Another replica is updating rates, waiting for it to finish");
+    metrics::counter!(RATE_UPDATES_TOTAL, "outcome" => "skipped").increment(1);
+
+    wait_for_unlock(store, lock.wait).await;
+    let rates = store
+        .load_rates()
+        .await?
+        .ok_or(ApiError::NoRatesAvailable)?;
+
+    // The holder may have stored new rates, which this replica's subscribers have not seen
+    let changed = last_seen != Some(rates.date.as_str());
+    Ok(UpdateOutcome { rates, changed })
+}
+
+fn record_outcome(result: Result<UpdateOutcome, ApiError>) -> Result<UpdateOutcome, ApiError> {
+    let outcome = match &result {
+        Ok(UpdateOutcome { changed: true, .. }) => "success",
+        Ok(UpdateOutcome { changed: false, .. }) => "unchanged",
+        Err(_) => "failure",
+    };
+    metrics::counter!(RATE_UPDATES_TOTAL, "outcome" => outcome).increment(1);
+
+    result
+}
+
+/// Identifies this process as the lock holder
+fn lock_token() -> String {
+    format!(
+        "{}-{}",
+        std::process::id(),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    )
+}
+
+/// Poll until the lock is released or `wait` elapses; a timeout is not an error,
+/// the caller then falls back to whatever is in the store
+async fn wait_for_unlock(store: &dyn RateSink, wait: Duration) {
+    let deadline = tokio::time::Instant::now() + wait;
+
+    loop {
+        match store.is_locked().await {
+            Ok(false) => return,
+            Ok(true) => {}
+            Err(e) => {
+                tracing::warn!("Failed to check rate update lock: {}", e);
+                return;
+            }
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            tracing::warn!(
+                "Rate update lock still held after {:?}, reading stored rates",
+                wait
+            );
+            return;
+        }
+
+        tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+    }
+}
+
+async fn fetch_and_store(
+    provider: &dyn RateProvider,
+    store: &dyn RateSink,
+    policy: &UpdatePolicy,
+) -> Result<UpdateOutcome, ApiError> {
+    tracing::info!("Fetching latest exchange rates from {}", provider.name());
+
+    let (rates, raw) = match provider.fetch_rates_if_modified().await? {
+        ConditionalFetch::Fetched(rates, raw) => (rates, raw),
+        ConditionalFetch::NotModified(date) => {
+            if let Some(rates) = stored_rates_for(store, &date).await? {
+                tracing::info!("Rates for {} are unchanged at the source", date);
+                keep_latest(store, &date).await;
+                return Ok(UpdateOutcome {
+                    rates,
+                    changed: false,
+                });
+            }
+
+            // The unchanged document was never stored (failed write, flushed Redis)
+            tracing::warn!(
+                "Source unchanged since {} but those rates are not stored, fetching again",
+                date
+            );
+            provider.fetch_rates_with_raw().await?
+        }
+    };
+    let raw = raw.filter(|_| policy.store_raw_xml);
 
     tracing::info!(
         "Fetched {} exchange rates for {}",
@@ -68,19 +555,786 @@ pub async fn update_rates(
         rates.date
     );
 
+    if let Err(reason) = validate_rates(&rates) {
+        tracing::error!("Rejected rates from {}: {}", provider.name(), reason);
+        return Err(ApiError::EcbFetchError(format!(
+            "Rejected invalid rates: {}",
+            reason
+        )));
+    }
+
+    let missing = missing_currencies(&rates, &policy.required_currencies);
+    if !missing.is_empty() {
+        tracing::error!(
+            "Rejected rates from {}: missing required currencies {}",
+            provider.name(),
+            missing.join(", ")
+        );
+        for code in &missing {
+            metrics::counter!(MISSING_REQUIRED_CURRENCY_TOTAL, "currency" => code.clone())
+                .increment(1);
+        }
+        return Err(ApiError::MissingRequiredCurrencies(missing));
+    }
+
+    metrics::gauge!(RATES_LOADED).set(rates.rates.len() as f64);
+
+    // YYYY-MM-DD dates sort chronologically, so a lagging mirror never replaces newer rates
+    if let Some(stored_date) = store
+        .last_update_date()
+        .await?
+        .filter(|stored| rates.date.as_str() <= stored.as_str())
+    {
+        let rates = if rates.date == stored_date {
+            tracing::info!(
+                "Rates for {} are already stored, skipping update",
+                rates.date
+            );
+            rates
+        } else {
+            tracing::warn!(
+                "{} returned rates for {}, older than the stored {}; keeping the stored rates",
+                provider.name(),
+                rates.date,
+                stored_date
+            );
+            store.load_rates().await?.unwrap_or(rates)
+        };
+        keep_latest(store, &stored_date).await;
+        return Ok(UpdateOutcome {
+            rates,
+            changed: false,
+        });
+    }
+
+    // Only read for the alert comparison, so a failed read just skips it
+    let previous = match &policy.alert {
+        Some(_) => store.load_rates().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load previous rates for alerting: {}", e);
+            None
+        }),
+        None => None,
+    };
+
     store.store_rates(&rates).await?;
 
+    if let (Some(alerter), Some(previous)) = (&policy.alert, &previous) {
+        alerter.check(previous, &rates);
+    }
+
+    // The parsed rates are already stored, so a lost raw copy only warrants a warning
+    if let Some(raw) = raw
+        && let Err(e) = store.store_raw(&rates.date, &raw).await
+    {
+        tracing::warn!("Failed to store raw payload for {}: {}", rates.date, e);
+    }
+
+    // The audit trail is best effort, like the raw copy
+    let entry = UpdateLogEntry {
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        date: rates.date.clone(),
+        rate_count: rates.rates.len(),
+        source: provider.name().to_string(),
+    };
+    if let Err(e) = store.record_update(&entry).await {
+        tracing::warn!("Failed to record update of {}: {}", rates.date, e);
+    }
+
     tracing::info!("Exchange rates updated successfully");
 
+    Ok(UpdateOutcome {
+        rates,
+        changed: true,
+    })
+}
+
+/// Renew the expiry of still-current latest rates, so a daily cron keeps Friday's rates
+/// alive over the weekend; they are still stored, so a failure only warrants a warning
+async fn keep_latest(store: &dyn RateSink, date: &str) {
+    if let Err(e) = store.touch_latest().await {
+        tracing::warn!("Failed to renew expiry of rates for {}: {}", date, e);
+    }
+}
+
+/// The latest stored rates, if they are for `date`
+async fn stored_rates_for(store: &dyn RateSink, date: &str) -> Result<Option<DailyRate>, ApiError> {
+    if store.last_update_date().await?.as_deref() != Some(date) {
+        return Ok(None);
+    }
+
+    store.load_rates().await
+}
+
+/// Run a startup backfill under the update lock, so replicas booting together
+/// download the history feed once; a replica that loses the race waits for the
+/// holder to finish and skips its own backfill (None)
+pub async fn backfill_locked(
+    store: &dyn RateSink,
+    lock: Option<UpdateLock>,
+    backfill: impl Future<Output = Result<usize, ApiError>>,
+) -> Result<Option<usize>, ApiError> {
+    let Some(lock) = lock else {
+        return backfill.await.map(Some);
+    };
+
+    let token = lock_token();
+    if !store.try_lock(&token, lock.ttl).await? {
+        tracing::info!("Another replica holds the rate update lock, skipping the backfill");
+        wait_for_unlock(store, lock.wait).await;
+        return Ok(None);
+    }
+
+    let result = backfill.await;
+    if let Err(e) = store.unlock(&token).await {
+        tracing::warn!("Failed to release rate update lock: {}", e);
+    }
+
+    result.map(Some)
+}
+
+/// Store every day of a multi-day ECB feed as a dated snapshot
+/// Used on cold start so historical lookups work before days accumulate;
+/// the latest rates are left untouched and invalid days are skipped
+pub async fn backfill_history(
+    fetcher: &EcbFetcher,
+    history_url: &str,
+    store: &RedisStore,
+) -> Result<usize, ApiError> {
+    let days = fetcher.fetch_history(history_url).await?;
+    let stored = store_snapshots(&days, store).await?;
+
+    tracing::info!("Backfilled {} historical rate snapshots", stored);
+
+    Ok(stored)
+}
+
+/// Re-import the whole ECB 90-day window at startup (BACKFILL_ON_START)
+/// Unlike the cold-start backfill, this runs even when history exists: every
+/// snapshot is overwritten and the newest day replaces the latest rates unless
+/// newer ones are stored, so data written by a faulty parser is corrected even
+/// though the daily update skips a date it has already stored
+pub async fn backfill_90d(
+    fetcher: &EcbFetcher,
+    history_url: &str,
+    store: &RedisStore,
+    required_currencies: &[String],
+) -> Result<usize, ApiError> {
+    let days = fetcher.fetch_history(history_url).await?;
+    let stored = store_snapshots(&days, store).await?;
+    promote_newest_day(&days, store, required_currencies).await?;
+
+    tracing::info!("Refreshed {} days of rate history", stored);
+
+    Ok(stored)
+}
+
+/// Store the newest valid day as the latest rates unless newer ones are stored
+/// A day lacking required currencies is kept as a snapshot only, as a daily update
+/// would reject it, so the latest rates never lose a currency the SLA depends on
+async fn promote_newest_day(
+    days: &[DailyRate],
+    store: &dyn RateSink,
+    required_currencies: &[String],
+) -> Result<(), ApiError> {
+    let Some(newest) = newest_valid_day(days) else {
+        return Ok(());
+    };
+
+    let missing = missing_currencies(newest, required_currencies);
+    if !missing.is_empty() {
+        tracing::warn!(
+            "Keeping the latest rates: history for {} is missing required currencies {}",
+            newest.date,
+            missing.join(", ")
+        );
+        return Ok(());
+    }
+
+    let current = store.last_update_date().await?;
+    if current.is_none_or(|current| current <= newest.date) {
+        store.store_rates(newest).await?;
+    }
+
     Ok(())
 }
 
-// Make EcbFetcher cloneable for async job
-impl Clone for EcbFetcher {
-    fn clone(&self) -> Self {
-        Self {
-            client: self.client.clone(),
-            ecb_url: self.ecb_url.clone(),
+/// Store each valid day as a dated snapshot, returning how many were stored
+async fn store_snapshots(days: &[DailyRate], store: &RedisStore) -> Result<usize, ApiError> {
+    let mut stored = 0;
+    for day in days {
+        if let Err(reason) = validate_rates(day) {
+            tracing::warn!("Skipping history snapshot for {}: {}", day.date, reason);
+            continue;
+        }
+
+        store.store_snapshot(day).await?;
+        stored += 1;
+    }
+
+    Ok(stored)
+}
+
+/// Most recent day that passes validation (YYYY-MM-DD dates sort chronologically)
+fn newest_valid_day(days: &[DailyRate]) -> Option<&DailyRate> {
+    days.iter()
+        .filter(|day| validate_rates(day).is_ok())
+        .max_by(|a, b| a.date.cmp(&b.date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+    use std::collections::HashMap;
+    use std::sync::Mutex as StdMutex;
+
+    const CORE_CURRENCIES: [&str; 4] = ["USD", "GBP", "JPY", "CHF"];
+
+    fn required() -> Vec<String> {
+        CORE_CURRENCIES
+            .iter()
+            .map(|code| code.to_string())
+            .collect()
+    }
+
+    fn requiring_core() -> UpdatePolicy {
+        UpdatePolicy {
+            required_currencies: required(),
+            ..UpdatePolicy::default()
+        }
+    }
+
+    fn daily_rate(date: &str, codes: &[&str]) -> DailyRate {
+        let mut rates: HashMap<String, Decimal> = codes
+            .iter()
+            .map(|code| (code.to_string(), dec!(1.5)))
+            .collect();
+        rates.insert("EUR".to_string(), Decimal::ONE);
+
+        DailyRate {
+            date: date.to_string(),
+            base: "EUR".to_string(),
+            rates,
+        }
+    }
+
+    struct FixedProvider(DailyRate);
+
+    #[async_trait]
+    impl RateProvider for FixedProvider {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn fetch_rates(&self) -> Result<DailyRate, ApiError> {
+            Ok(self.0.clone())
+        }
+
+        async fn fetch_rates_with_raw(&self) -> Result<(DailyRate, Option<String>), ApiError> {
+            Ok((
+                self.0.clone(),
+                Some(format!("<Cube time=\"{}\"/>", self.0.date)),
+            ))
+        }
+    }
+
+    /// Reports the source unchanged since `not_modified` and counts full fetches
+    struct UnchangedProvider {
+        not_modified: String,
+        full: DailyRate,
+        full_fetches: StdMutex<usize>,
+    }
+
+    #[async_trait]
+    impl RateProvider for UnchangedProvider {
+        fn name(&self) -> &str {
+            "unchanged"
+        }
+
+        async fn fetch_rates(&self) -> Result<DailyRate, ApiError> {
+            *self.full_fetches.lock().unwrap() += 1;
+            Ok(self.full.clone())
+        }
+
+        async fn fetch_rates_if_modified(&self) -> Result<ConditionalFetch, ApiError> {
+            Ok(ConditionalFetch::NotModified(self.not_modified.clone()))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        stored: StdMutex<Vec<String>>,
+        raw: StdMutex<Vec<(String, String)>>,
+        updates: StdMutex<Vec<UpdateLogEntry>>,
+        latest: StdMutex<Option<DailyRate>>,
+        lock_holder: StdMutex<Option<String>>,
+        touches: StdMutex<usize>,
+    }
+
+    #[async_trait]
+    impl RateSink for RecordingSink {
+        async fn store_rates(&self, rates: &DailyRate) -> Result<(), ApiError> {
+            self.stored.lock().unwrap().push(rates.date.clone());
+            *self.latest.lock().unwrap() = Some(rates.clone());
+            Ok(())
+        }
+
+        async fn store_raw(&self, date: &str, raw: &str) -> Result<(), ApiError> {
+            self.raw
+                .lock()
+                .unwrap()
+                .push((date.to_string(), raw.to_string()));
+            Ok(())
+        }
+
+        async fn record_update(&self, entry: &UpdateLogEntry) -> Result<(), ApiError> {
+            self.updates.lock().unwrap().push(entry.clone());
+            Ok(())
         }
+
+        async fn touch_latest(&self) -> Result<(), ApiError> {
+            *self.touches.lock().unwrap() += 1;
+            Ok(())
+        }
+
+        async fn load_rates(&self) -> Result<Option<DailyRate>, ApiError> {
+            Ok(self.latest.lock().unwrap().clone())
+        }
+
+        async fn last_update_date(&self) -> Result<Option<String>, ApiError> {
+            Ok(self
+                .latest
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|rates| rates.date.clone()))
+        }
+
+        async fn try_lock(&self, token: &str, _ttl: Duration) -> Result<bool, ApiError> {
+            let mut holder = self.lock_holder.lock().unwrap();
+            if holder.is_some() {
+                return Ok(false);
+            }
+            *holder = Some(token.to_string());
+            Ok(true)
+        }
+
+        async fn unlock(&self, token: &str) -> Result<(), ApiError> {
+            let mut holder = self.lock_holder.lock().unwrap();
+            if holder.as_deref() == Some(token) {
+                *holder = None;
+            }
+            Ok(())
+        }
+
+        async fn is_locked(&self) -> Result<bool, ApiError> {
+            Ok(self.lock_holder.lock().unwrap().is_some())
+        }
+    }
+
+    fn locking() -> UpdatePolicy {
+        UpdatePolicy {
+            lock: Some(UpdateLock {
+                ttl: Duration::from_secs(60),
+                wait: Duration::ZERO,
+            }),
+            ..UpdatePolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_rates() {
+        assert!(validate_rates(&daily_rate("2024-12-04", &CORE_CURRENCIES)).is_ok());
+        assert!(validate_rates(&daily_rate("2024-12-04", &[])).is_err());
+        assert!(validate_rates(&daily_rate("2024-13-04", &CORE_CURRENCIES)).is_err());
+        assert!(validate_rates(&daily_rate("2024-12-04", &["USD", "GBP"])).is_ok());
+
+        let mut zero = daily_rate("2024-12-04", &CORE_CURRENCIES);
+        zero.rates.insert("USD".to_string(), Decimal::ZERO);
+        assert!(validate_rates(&zero).is_err());
+    }
+
+    #[test]
+    fn test_newest_valid_day_ignores_invalid_days() {
+        let days = vec![
+            daily_rate("2024-12-05", &[]),
+            daily_rate("2024-12-04", &CORE_CURRENCIES),
+            daily_rate("2024-12-03", &CORE_CURRENCIES),
+        ];
+
+        assert_eq!(newest_valid_day(&days).unwrap().date, "2024-12-04");
+        assert!(newest_valid_day(&[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_stores_valid_rates() {
+        let provider = FixedProvider(daily_rate("2024-12-04", &CORE_CURRENCIES));
+        let sink = RecordingSink::default();
+
+        let outcome = update_rates(&provider, &sink, &requiring_core(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.rates.date, "2024-12-04");
+        assert!(outcome.changed);
+        assert_eq!(*sink.stored.lock().unwrap(), vec!["2024-12-04"]);
+        assert!(sink.raw.lock().unwrap().is_empty());
+
+        let updates = sink.updates.lock().unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].date, "2024-12-04");
+        assert_eq!(updates[0].rate_count, CORE_CURRENCIES.len() + 1);
+        assert_eq!(updates[0].source, "fixed");
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_skips_source_not_modified() {
+        let stored = daily_rate("2024-12-04", &CORE_CURRENCIES);
+        let provider = UnchangedProvider {
+            not_modified: "2024-12-04".to_string(),
+            full: stored.clone(),
+            full_fetches: StdMutex::new(0),
+        };
+        let sink = RecordingSink::default();
+        *sink.latest.lock().unwrap() = Some(stored);
+
+        let outcome = update_rates(&provider, &sink, &UpdatePolicy::default(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.rates.date, "2024-12-04");
+        assert!(!outcome.changed);
+        assert_eq!(*provider.full_fetches.lock().unwrap(), 0);
+        assert!(sink.stored.lock().unwrap().is_empty());
+        assert!(sink.updates.lock().unwrap().is_empty());
+        // A feed that keeps answering Not Modified must not let the rates expire
+        assert_eq!(*sink.touches.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_refetches_when_unchanged_rates_were_never_stored() {
+        let provider = UnchangedProvider {
+            not_modified: "2024-12-04".to_string(),
+            full: daily_rate("2024-12-04", &CORE_CURRENCIES),
+            full_fetches: StdMutex::new(0),
+        };
+        let sink = RecordingSink::default();
+
+        let outcome = update_rates(&provider, &sink, &UpdatePolicy::default(), None)
+            .await
+            .unwrap();
+
+        assert!(outcome.changed);
+        assert_eq!(*provider.full_fetches.lock().unwrap(), 1);
+        assert_eq!(*sink.stored.lock().unwrap(), vec!["2024-12-04"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_skips_already_stored_date() {
+        let provider = FixedProvider(daily_rate("2024-12-04", &CORE_CURRENCIES));
+        let sink = RecordingSink::default();
+        let policy = UpdatePolicy {
+            store_raw_xml: true,
+            ..UpdatePolicy::default()
+        };
+
+        update_rates(&provider, &sink, &policy, None).await.unwrap();
+        assert_eq!(*sink.touches.lock().unwrap(), 0);
+        let outcome = update_rates(&provider, &sink, &policy, None).await.unwrap();
+
+        assert_eq!(outcome.rates.date, "2024-12-04");
+        assert!(!outcome.changed);
+        assert_eq!(*sink.stored.lock().unwrap(), vec!["2024-12-04"]);
+        assert_eq!(sink.raw.lock().unwrap().len(), 1);
+        // The unchanged rates' expiry is renewed instead of letting them lapse
+        assert_eq!(*sink.touches.lock().unwrap(), 1);
+
+        // A newer publication is stored as usual
+        let provider = FixedProvider(daily_rate("2024-12-05", &CORE_CURRENCIES));
+        let outcome = update_rates(&provider, &sink, &policy, None).await.unwrap();
+
+        assert!(outcome.changed);
+        assert_eq!(
+            *sink.stored.lock().unwrap(),
+            vec!["2024-12-04", "2024-12-05"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_keeps_newer_stored_rates() {
+        // A fallback mirror a day behind the source that stored the latest rates
+        let provider = FixedProvider(daily_rate("2024-12-03", &CORE_CURRENCIES));
+        let sink = RecordingSink::default();
+        *sink.latest.lock().unwrap() = Some(daily_rate("2024-12-04", &CORE_CURRENCIES));
+
+        let outcome = update_rates(&provider, &sink, &UpdatePolicy::default(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.rates.date, "2024-12-04");
+        assert!(!outcome.changed);
+        assert!(sink.stored.lock().unwrap().is_empty());
+        assert!(sink.updates.lock().unwrap().is_empty());
+        assert_eq!(
+            sink.latest.lock().unwrap().as_ref().unwrap().date,
+            "2024-12-04"
+        );
+        assert_eq!(*sink.touches.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_promote_newest_day_requires_required_currencies() {
+        let sink = RecordingSink::default();
+        *sink.latest.lock().unwrap() = Some(daily_rate("2024-12-03", &CORE_CURRENCIES));
+        let days = vec![
+            daily_rate("2024-12-04", &["USD", "GBP"]),
+            daily_rate("2024-12-03", &CORE_CURRENCIES),
+        ];
+
+        promote_newest_day(&days, &sink, &required()).await.unwrap();
+        assert!(sink.stored.lock().unwrap().is_empty());
+
+        // Without required currencies the newest day replaces the latest rates
+        promote_newest_day(&days, &sink, &[]).await.unwrap();
+        assert_eq!(*sink.stored.lock().unwrap(), vec!["2024-12-04"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_stores_raw_payload_when_enabled() {
+        let provider = FixedProvider(daily_rate("2024-12-04", &CORE_CURRENCIES));
+        let sink = RecordingSink::default();
+        let policy = UpdatePolicy {
+            store_raw_xml: true,
+            ..UpdatePolicy::default()
+        };
+
+        update_rates(&provider, &sink, &policy, None).await.unwrap();
+
+        assert_eq!(
+            *sink.raw.lock().unwrap(),
+            vec![(
+                "2024-12-04".to_string(),
+                "<Cube time=\"2024-12-04\"/>".to_string()
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_not_blocked_by_unreachable_alert_webhook() {
+        // Bind then drop a listener so nothing accepts on the port
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let policy = UpdatePolicy {
+            alert: Some(
+                RateAlerter::new(
+                    format!("http://{}/alert", addr),
+                    dec!(1),
+                    &crate::services::HttpClientOptions::default(),
+                )
+                .unwrap(),
+            ),
+            ..UpdatePolicy::default()
+        };
+        let sink = RecordingSink::default();
+        *sink.latest.lock().unwrap() = Some(daily_rate("2024-12-03", &["USD"]));
+
+        let mut moved = daily_rate("2024-12-04", &["USD"]);
+        moved.rates.insert("USD".to_string(), dec!(3));
+        let outcome = update_rates(&FixedProvider(moved), &sink, &policy, None)
+            .await
+            .unwrap();
+
+        assert!(outcome.changed);
+        assert_eq!(*sink.stored.lock().unwrap(), vec!["2024-12-04"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_leaves_store_untouched_on_invalid_rates() {
+        let provider = FixedProvider(daily_rate("2024-12-04", &[]));
+        let sink = RecordingSink::default();
+
+        let result = update_rates(&provider, &sink, &UpdatePolicy::default(), None).await;
+
+        assert!(matches!(result, Err(ApiError::EcbFetchError(_))));
+        assert!(sink.stored.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_releases_lock_after_storing() {
+        let provider = FixedProvider(daily_rate("2024-12-04", &CORE_CURRENCIES));
+        let sink = RecordingSink::default();
+
+        update_rates(&provider, &sink, &locking(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(*sink.stored.lock().unwrap(), vec!["2024-12-04"]);
+        assert!(sink.lock_holder.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_skips_fetch_while_locked_elsewhere() {
+        let provider = FixedProvider(daily_rate("2024-12-05", &CORE_CURRENCIES));
+        let sink = RecordingSink::default();
+        *sink.latest.lock().unwrap() = Some(daily_rate("2024-12-04", &CORE_CURRENCIES));
+        *sink.lock_holder.lock().unwrap() = Some("other-replica".to_string());
+
+        // The wait times out at once, so the stored rates are returned unchanged
+        let outcome = update_rates(&provider, &sink, &locking(), Some("2024-12-04"))
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.rates.date, "2024-12-04");
+        assert!(!outcome.changed);
+        assert!(sink.stored.lock().unwrap().is_empty());
+
+        // Rates the holder stored since this replica's last update are new to its subscribers
+        let outcome = update_rates(&provider, &sink, &locking(), Some("2024-12-03"))
+            .await
+            .unwrap();
+        assert!(outcome.changed);
+        assert_eq!(
+            sink.lock_holder.lock().unwrap().as_deref(),
+            Some("other-replica")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_locked_elsewhere_without_stored_rates() {
+        let provider = FixedProvider(daily_rate("2024-12-05", &CORE_CURRENCIES));
+        let sink = RecordingSink::default();
+        *sink.lock_holder.lock().unwrap() = Some("other-replica".to_string());
+
+        let result = update_rates(&provider, &sink, &locking(), None).await;
+
+        assert!(matches!(result, Err(ApiError::NoRatesAvailable)));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_locked_runs_once() {
+        let sink = RecordingSink::default();
+
+        let stored = backfill_locked(&sink, locking().lock, async { Ok(90) })
+            .await
+            .unwrap();
+        assert_eq!(stored, Some(90));
+        assert!(sink.lock_holder.lock().unwrap().is_none());
+
+        // A replica booting while another backfills leaves the download to the holder
+        *sink.lock_holder.lock().unwrap() = Some("other-replica".to_string());
+        let ran = StdMutex::new(false);
+        let stored = backfill_locked(&sink, locking().lock, async {
+            *ran.lock().unwrap() = true;
+            Ok(90)
+        })
+        .await
+        .unwrap();
+        assert_eq!(stored, None);
+        assert!(!*ran.lock().unwrap());
+    }
+
+    #[test]
+    fn test_missing_currencies() {
+        let rates = daily_rate("2024-12-04", &["USD", "JPY"]);
+
+        assert_eq!(missing_currencies(&rates, &required()), vec!["GBP", "CHF"]);
+        assert!(missing_currencies(&rates, &[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_rates_rejects_missing_required_currencies() {
+        let provider = FixedProvider(daily_rate("2024-12-04", &["USD", "GBP"]));
+        let sink = RecordingSink::default();
+
+        let result = update_rates(&provider, &sink, &requiring_core(), None).await;
+
+        match result {
+            Err(ApiError::MissingRequiredCurrencies(codes)) => {
+                assert_eq!(codes, vec!["JPY", "CHF"]);
+            }
+            other => panic!("Expected MissingRequiredCurrencies, got {:?}", other),
+        }
+        assert!(sink.stored.lock().unwrap().is_empty());
+
+        // Without required currencies the same rates are accepted
+        update_rates(&provider, &sink, &UpdatePolicy::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(*sink.stored.lock().unwrap(), vec!["2024-12-04"]);
+    }
+
+    #[test]
+    fn test_parse_cron_requires_seconds() {
+        assert!(parse_cron("0 0 15 * * *").is_ok());
+        assert!(parse_cron("0 15 * * *").is_err());
+        assert!(parse_cron("0 0 15 * * MON-FRI").is_ok());
+    }
+
+    #[test]
+    fn test_parse_cron_rejects_out_of_range() {
+        let err = parse_cron("0 0 25 * * *").unwrap_err();
+        assert_eq!(err, "hour field '25' is out of range or malformed");
+    }
+
+    #[test]
+    fn test_parse_cron_wrong_field_count() {
+        let err = parse_cron("0 0 25 * *").unwrap_err();
+        assert!(
+            err.starts_with("expected 6 fields"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_scheduler_liveness_overdue() {
+        let started = Utc.with_ymd_and_hms(2024, 12, 4, 16, 0, 0).unwrap();
+        let liveness = SchedulerLiveness::new(parse_cron("0 0 15 * * *").unwrap(), started);
+        let due = Utc.with_ymd_and_hms(2024, 12, 5, 15, 0, 0).unwrap();
+
+        // Nothing due yet, then within the grace period, then missed
+        assert!(!liveness.is_overdue(started + Duration::from_secs(3600)));
+        assert!(!liveness.is_overdue(due + Duration::from_secs(60)));
+        assert!(liveness.is_overdue(due + SCHEDULER_GRACE + Duration::from_secs(1)));
+
+        // A successful run moves the next deadline to the following day
+        liveness.record_run(due + Duration::from_secs(5));
+        assert_eq!(liveness.last_run(), Some(due + Duration::from_secs(5)));
+        assert!(!liveness.is_overdue(due + SCHEDULER_GRACE + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_scheduler_liveness_counts_run_during_refresh() {
+        let started = Utc.with_ymd_and_hms(2024, 12, 4, 16, 0, 0).unwrap();
+        let liveness = SchedulerLiveness::new(parse_cron("0 0 15 * * *").unwrap(), started);
+        let due = Utc.with_ymd_and_hms(2024, 12, 5, 15, 0, 0).unwrap();
+
+        liveness.record_outcome(&Err(ApiError::EcbFetchError("timeout".to_string())), due);
+        assert_eq!(liveness.last_run(), None);
+
+        // The job fired while a manual refresh held the update lock
+        liveness.record_outcome(&Err(ApiError::RefreshInProgress), due);
+        assert_eq!(liveness.last_run(), Some(due));
+        assert!(!liveness.is_overdue(due + SCHEDULER_GRACE + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_next_fire_times() {
+        let cron = parse_cron("0 0 15 * * *").unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 12, 4, 16, 0, 0).unwrap();
+
+        let times = next_fire_times(&cron, after, 3);
+
+        assert_eq!(
+            times,
+            vec![
+                Utc.with_ymd_and_hms(2024, 12, 5, 15, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 12, 6, 15, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 12, 7, 15, 0, 0).unwrap(),
+            ]
+        );
     }
 }
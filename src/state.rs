@@ -0,0 +1,38 @@
+use crate::config::Config;
+use crate::middleware::InFlight;
+use crate::services::{EcbProbe, RateUpdater, RedisStore, SchedulerLiveness};
+use axum::extract::FromRef;
+use metrics_exporter_prometheus::PrometheusHandle;
+use std::sync::Arc;
+use tokio::sync::{Semaphore, watch};
+
+/// Shared state available to all handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub store: RedisStore,
+    pub config: Arc<Config>,
+    pub metrics: PrometheusHandle,
+    pub updater: RateUpdater,
+    /// Slots for concurrent WebSocket rate streams
+    pub ws_connections: Arc<Semaphore>,
+    /// ECB reachability check reported by /health, only with HEALTH_CHECK_ECB
+    pub ecb_probe: Option<EcbProbe>,
+    /// Last successful scheduled update, reported by /health
+    pub scheduler: SchedulerLiveness,
+    /// Requests being handled, counted for the shutdown drain
+    pub in_flight: InFlight,
+    /// Turns true once shutdown starts, so long-lived WebSocket streams close themselves
+    pub shutdown: watch::Receiver<bool>,
+}
+
+impl FromRef<AppState> for RedisStore {
+    fn from_ref(state: &AppState) -> Self {
+        state.store.clone()
+    }
+}
+
+impl FromRef<AppState> for RateUpdater {
+    fn from_ref(state: &AppState) -> Self {
+        state.updater.clone()
+    }
+}
@@ -0,0 +1,35 @@
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder, PrometheusHandle};
+
+/// Total HTTP requests, labelled by method, matched path and status
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+/// HTTP request latency histogram, labelled by method and matched path
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+/// ECB fetch attempts, labelled by outcome (success, not_modified, failure); retries count individually
+pub const ECB_FETCH_ATTEMPTS_TOTAL: &str = "ecb_fetch_attempts_total";
+/// Rate update runs, labelled by outcome (success, unchanged, skipped, failure)
+pub const RATE_UPDATES_TOTAL: &str = "rate_updates_total";
+/// Updates rejected for lacking a REQUIRED_CURRENCIES code, labelled by currency
+pub const MISSING_REQUIRED_CURRENCY_TOTAL: &str = "rate_updates_missing_required_total";
+/// Number of currencies in the latest stored rates
+pub const RATES_LOADED: &str = "exchange_rates_loaded";
+/// Hours since the latest stored rates were published
+pub const RATE_AGE_HOURS: &str = "exchange_rate_age_hours";
+/// Round trip of the last successful Redis PING (from /health and /readiness checks)
+pub const REDIS_PING_SECONDS: &str = "redis_ping_seconds";
+/// Commands that found the Redis connection lost, each followed by a reconnect
+pub const REDIS_RECONNECTS_TOTAL: &str = "redis_reconnects_total";
+
+/// Latency buckets (seconds) for HTTP request durations
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+/// Install the global Prometheus recorder and return a handle for rendering
+pub fn install_recorder() -> Result<PrometheusHandle, BuildError> {
+    PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full(HTTP_REQUEST_DURATION_SECONDS.to_string()),
+            LATENCY_BUCKETS,
+        )?
+        .install_recorder()
+}
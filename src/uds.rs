@@ -0,0 +1,107 @@
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use tokio::net::UnixListener;
+
+/// Listening socket file, removed again when dropped
+pub struct SocketFile {
+    path: PathBuf,
+}
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!("Failed to remove socket {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Bind a listener at `path`, replacing a socket left behind by an unclean exit
+/// A socket something still listens on, or any other file at `path`, is left alone and the
+/// bind fails
+pub fn bind(path: &Path) -> io::Result<(UnixListener, SocketFile)> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => match UnixStream::connect(path) {
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!(
+                        "BIND_UDS path {} is in use by another server",
+                        path.display()
+                    ),
+                ));
+            }
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                tracing::info!("Removing stale socket {}", path.display());
+                std::fs::remove_file(path)?;
+            }
+            Err(e) => return Err(e),
+        },
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "BIND_UDS path {} exists and is not a socket",
+                    path.display()
+                ),
+            ));
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let listener = UnixListener::bind(path)?;
+    Ok((
+        listener,
+        SocketFile {
+            path: path.to_path_buf(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn socket_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("currency-api-{}-{}.sock", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_stale_socket_and_cleans_up() {
+        let path = socket_path("stale");
+
+        // A listener dropped without cleanup leaves its socket file behind
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let (_listener, socket) = bind(&path).unwrap();
+        assert!(path.exists());
+
+        drop(socket);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_bind_refuses_to_replace_live_socket() {
+        let path = socket_path("live");
+        let (_listener, _socket) = bind(&path).unwrap();
+
+        let err = bind(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        assert!(path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_bind_refuses_to_replace_regular_file() {
+        let path = socket_path("regular");
+        std::fs::write(&path, "keep me").unwrap();
+
+        let err = bind(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}